@@ -83,6 +83,21 @@ impl Cli {
         Ok(())
     }
 
+    pub fn check_output(
+        &self,
+        cmd_line: &[String],
+        env: &HashMap<String, String>,
+        shell: bool,
+    ) -> Result<String, BError> {
+        self.debug(format!("env: {:?}", env));
+        self.debug(cmd_line.join(" "));
+        self.system.check_output(&CallParams {
+            cmd_line: cmd_line.to_owned(),
+            env: env.to_owned(),
+            shell,
+        })
+    }
+
     pub fn rmdir_all(&self, path: &PathBuf) -> Result<(), BError> {
         self.system.rmdir_all(path)?;
         Ok(())
@@ -156,7 +171,7 @@ impl Cli {
         }
     }
 
-    pub fn _warn(&self, message: String) {
+    pub fn warn(&self, message: String) {
         (*self.logger).warn(message);
     }
 
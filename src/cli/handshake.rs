@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::configs::YAAB_CONFIG_VERSION;
+use crate::error::BError;
+
+/*
+ * When yaab is bootstrapped into a container the host yaab is passing its own
+ * version and the version of the config format it supports to the yaab running
+ * inside the container. The yaab inside the container is then verifying that
+ * it supports the same config format as the host before doing anything else.
+ * An older yaab in the image not aware of the handshake is ignoring the env
+ * variables and is used as before.
+ */
+pub const YAAB_HOST_VERSION: &str = "YAAB_HOST_VERSION";
+pub const YAAB_HOST_CONFIG_VERSION: &str = "YAAB_HOST_CONFIG_VERSION";
+
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/*
+ * The version reported by 'yaab --version' including the config version.
+ */
+pub fn long_version() -> &'static str {
+    static LONG_VERSION: OnceLock<String> = OnceLock::new();
+    LONG_VERSION.get_or_init(|| format!("{} (config version {})", version(), YAAB_CONFIG_VERSION))
+}
+
+pub fn env() -> HashMap<String, String> {
+    HashMap::from([
+        (String::from(YAAB_HOST_VERSION), version().to_string()),
+        (
+            String::from(YAAB_HOST_CONFIG_VERSION),
+            YAAB_CONFIG_VERSION.to_string(),
+        ),
+    ])
+}
+
+pub fn args() -> Vec<String> {
+    vec![
+        String::from("-e"),
+        format!("{}={}", YAAB_HOST_VERSION, version()),
+        String::from("-e"),
+        format!("{}={}", YAAB_HOST_CONFIG_VERSION, YAAB_CONFIG_VERSION),
+    ]
+}
+
+/*
+ * Nothing to verify if yaab was not bootstrapped by a host yaab
+ */
+pub fn check(env: &HashMap<String, String>) -> Result<(), BError> {
+    if let Some(config_version) = env.get(YAAB_HOST_CONFIG_VERSION) {
+        if config_version != YAAB_CONFIG_VERSION {
+            let host_version: String = env
+                .get(YAAB_HOST_VERSION)
+                .cloned()
+                .unwrap_or(String::from("unknown"));
+            return Err(BError::HandshakeError(format!(
+                "The yaab {} in the container supports config version '{}' but the host yaab {} is using config version '{}'. Update yaab in the docker image or enable 'hostyaab' in the workspace config",
+                version(),
+                YAAB_CONFIG_VERSION,
+                host_version,
+                config_version
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::cli::handshake;
+    use crate::configs::YAAB_CONFIG_VERSION;
+    use crate::error::BError;
+
+    #[test]
+    fn test_handshake_compatible() {
+        assert!(handshake::check(&HashMap::new()).is_ok());
+        assert!(handshake::check(&handshake::env()).is_ok());
+    }
+
+    #[test]
+    fn test_handshake_incompatible() {
+        let env: HashMap<String, String> = HashMap::from([
+            (
+                String::from(handshake::YAAB_HOST_VERSION),
+                String::from("1.0.0"),
+            ),
+            (
+                String::from(handshake::YAAB_HOST_CONFIG_VERSION),
+                String::from("6"),
+            ),
+        ]);
+        let result: Result<(), BError> = handshake::check(&env);
+        match result {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    format!("The yaab {} in the container supports config version '{}' but the host yaab 1.0.0 is using config version '6'. Update yaab in the docker image or enable 'hostyaab' in the workspace config", handshake::version(), YAAB_CONFIG_VERSION)
+                );
+            }
+        }
+    }
+}
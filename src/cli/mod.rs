@@ -1,5 +1,7 @@
 pub mod cli;
+pub mod handshake;
 pub mod logger;
+pub mod signal;
 pub mod system;
 pub mod yaab;
 
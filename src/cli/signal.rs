@@ -0,0 +1,180 @@
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
+
+use crate::error::BError;
+
+/*
+ * The exit status used by yaab when it has been interrupted by SIGINT or
+ * SIGTERM. Follows the shell convention of 128 + SIGINT.
+ */
+pub const YAAB_INTERRUPTED_EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static RECEIVED: AtomicBool = AtomicBool::new(false);
+static CHILD: Mutex<Option<Child>> = Mutex::new(None);
+static SESSION: OnceLock<String> = OnceLock::new();
+static CONTAINERS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/*
+ * The child currently running. A child running in its own process group is
+ * signaled together with everything it has started. A child sharing the
+ * foreground process group with yaab is already receiving any SIGINT from
+ * the terminal so only a SIGTERM is forwarded to it.
+ */
+#[derive(Clone, Copy)]
+struct Child {
+    pid: i32,
+    group: bool,
+}
+
+fn child() -> MutexGuard<'static, Option<Child>> {
+    CHILD.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/*
+ * Install the signal handling for yaab. Any SIGINT or SIGTERM received by
+ * yaab is forwarded to the child currently running and yaab is flagged as
+ * interrupted so that no more commands are started. The flag is set directly
+ * by the signal handler so that it is set before yaab can see a child that
+ * was interrupted by the same signal exit.
+ * Work done by yaab itself like collecting or uploading artifacts is
+ * checking the flag and stops as soon as possible. If no child is running
+ * and yaab is already interrupted the signal is not waiting for that and
+ * yaab exits right away.
+ */
+pub fn install() -> Result<(), BError> {
+    for sig in [SIGINT, SIGTERM] {
+        unsafe {
+            signal_hook::low_level::register(sig, || INTERRUPTED.store(true, Ordering::SeqCst))?;
+        }
+    }
+    let mut signals: Signals = Signals::new([SIGINT, SIGTERM])?;
+    std::thread::spawn(move || {
+        for sig in signals.forever() {
+            let child: MutexGuard<'static, Option<Child>> = child();
+            let again: bool = RECEIVED.swap(true, Ordering::SeqCst);
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            match *child {
+                Some(c) if c.group => unsafe {
+                    libc::killpg(c.pid, sig);
+                },
+                Some(c) if sig == SIGTERM => unsafe {
+                    libc::kill(c.pid, sig);
+                },
+                Some(_) => {}
+                None if again => std::process::exit(YAAB_INTERRUPTED_EXIT_CODE),
+                None => {}
+            }
+        }
+    });
+    Ok(())
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+pub fn set_interrupted(interrupted: bool) {
+    INTERRUPTED.store(interrupted, Ordering::SeqCst);
+    RECEIVED.store(interrupted, Ordering::SeqCst);
+}
+
+/*
+ * Used by any long running work done by yaab itself to stop once yaab has
+ * been interrupted.
+ */
+pub fn check() -> Result<(), BError> {
+    if interrupted() {
+        return Err(BError::Interrupted);
+    }
+    Ok(())
+}
+
+/*
+ * The child is spawned and registered while holding the lock used by the
+ * signal handler so that a signal received while the child is started is
+ * either stopping yaab from starting it or is forwarded to it. The group
+ * should be true if the child is started in its own process group.
+ */
+pub fn spawn_child(command: &mut Command, group: bool) -> Result<std::process::Child, BError> {
+    let mut running: MutexGuard<'static, Option<Child>> = child();
+    check()?;
+    let spawned: std::process::Child = command.spawn()?;
+    *running = Some(Child {
+        pid: spawned.id() as i32,
+        group,
+    });
+    Ok(spawned)
+}
+
+pub fn clear_child() {
+    *child() = None;
+}
+
+/*
+ * A child that was terminated by SIGINT or SIGTERM is treated as interrupted.
+ * The exit code of a child is never used to tell if it was interrupted since
+ * a command could be exiting with 130 or 143 for other reasons. A child like
+ * "docker run -t" exiting with 130 on Ctrl-C is still caught since it shares
+ * the foreground process group with yaab and yaab receives the same SIGINT.
+ */
+pub fn child_interrupted(status: &ExitStatus) -> bool {
+    matches!(status.signal(), Some(sig) if sig == SIGINT || sig == SIGTERM)
+}
+
+/*
+ * Unique id for this run of yaab used to label all containers started by yaab
+ * so they can be removed if yaab is interrupted. The pid alone is not enough
+ * since yaab could be running with the same pid inside of a container.
+ */
+pub fn session() -> &'static str {
+    SESSION.get_or_init(|| format!("{}-{:08x}", std::process::id(), rand::random::<u32>()))
+}
+
+/*
+ * Persistent containers are not removed when yaab is interrupted but they
+ * are stopped to make sure nothing is left running in them.
+ */
+pub fn register_container(name: &str) {
+    if let Ok(mut containers) = CONTAINERS.lock() {
+        if !containers.iter().any(|c| c == name) {
+            containers.push(name.to_string());
+        }
+    }
+}
+
+pub fn containers() -> Vec<String> {
+    match CONTAINERS.lock() {
+        Ok(containers) => containers.clone(),
+        Err(_err) => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    use crate::cli::signal;
+
+    #[test]
+    fn test_signal_child_interrupted() {
+        assert!(signal::child_interrupted(&ExitStatus::from_raw(2)));
+        assert!(signal::child_interrupted(&ExitStatus::from_raw(15)));
+        assert!(!signal::child_interrupted(&ExitStatus::from_raw(130 << 8)));
+        assert!(!signal::child_interrupted(&ExitStatus::from_raw(143 << 8)));
+        assert!(!signal::child_interrupted(&ExitStatus::from_raw(1 << 8)));
+        assert!(!signal::child_interrupted(&ExitStatus::from_raw(0)));
+    }
+
+    #[test]
+    fn test_signal_session() {
+        assert!(signal::session().starts_with(&format!("{}-", std::process::id())));
+        assert_eq!(signal::session(), signal::session());
+    }
+}
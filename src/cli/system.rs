@@ -1,8 +1,10 @@
+use crate::cli::signal;
 use crate::error::BError;
 
 use mockall::*;
 use std::collections::HashMap;
 use std::fmt;
+use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
 use std::str;
 
@@ -37,6 +39,7 @@ impl fmt::Display for CallParams {
 #[automock]
 pub trait System {
     fn check_call(&self, params: &CallParams) -> Result<(), BError>;
+    fn check_output(&self, params: &CallParams) -> Result<String, BError>;
     fn init_env_file(
         &self,
         init_file: &PathBuf,
@@ -54,8 +57,19 @@ impl BSystem {
     }
 }
 
-impl System for BSystem {
-    fn check_call(&self, params: &CallParams) -> Result<(), BError> {
+/*
+ * True if yaab is running in the foreground process group of the terminal
+ * and not for example as a background job of a shell.
+ */
+fn foreground() -> bool {
+    unsafe {
+        libc::isatty(libc::STDIN_FILENO) == 1
+            && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+    }
+}
+
+impl BSystem {
+    fn call(&self, params: &CallParams, foreground: bool) -> Result<(), BError> {
         let mut cmd: String = String::new();
         params.cmd_line.iter().for_each(|c| {
             cmd.push_str(c);
@@ -66,14 +80,29 @@ impl System for BSystem {
 
         // TODO: we should consider how to handle different shells for now we
         // will stick to bash since that is what OE/Yocto requires
-        let mut child: std::process::Child = std::process::Command::new("/bin/bash")
+        let mut command: std::process::Command = std::process::Command::new("/bin/bash");
+        command
             .arg("-c")
             .arg(cmd.as_str().trim_end())
             .stdout(os_pipe::dup_stdout()?)
             .stderr(os_pipe::dup_stderr()?)
             .env_clear()
-            .envs(&params.env)
-            .spawn()?;
+            .envs(&params.env);
+
+        /*
+         * When yaab owns the terminal the child is kept in the foreground
+         * process group of yaab so that an interactive child like a docker
+         * shell can read from the terminal and so that a Ctrl-C is received
+         * by both the child and yaab. In any other case the child is started
+         * in its own process group so that any signal received by yaab can
+         * be forwarded to the child and everything it has started. The
+         * terminal is never handed over so a yaab running as a background
+         * job is not taking the terminal from the shell.
+         */
+        if !foreground {
+            command.process_group(0);
+        }
+        let mut child: std::process::Child = signal::spawn_child(&mut command, !foreground)?;
 
         /*
         Lets keep this for now we might need this later
@@ -90,13 +119,59 @@ impl System for BSystem {
         }*/
 
         // Wait for the command to finish
-        let status: std::process::ExitStatus = child.wait()?;
+        let result: std::io::Result<std::process::ExitStatus> = child.wait();
+        signal::clear_child();
+        let status: std::process::ExitStatus = result?;
+        if signal::interrupted() || signal::child_interrupted(&status) {
+            return Err(BError::Interrupted);
+        }
         if !status.success() {
             return Err(BError::CliError(format!("{}", status)));
         }
 
         Ok(())
     }
+}
+
+impl System for BSystem {
+    fn check_call(&self, params: &CallParams) -> Result<(), BError> {
+        self.call(params, foreground())
+    }
+
+    /*
+     * Same as check_call but the stdout of the command is captured and
+     * returned instead of being written to the stdout of yaab. The stderr
+     * is captured as well and included in the error if the command fails.
+     */
+    fn check_output(&self, params: &CallParams) -> Result<String, BError> {
+        let mut command: std::process::Command = std::process::Command::new("/bin/bash");
+        command
+            .arg("-c")
+            .arg(params.to_string())
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .env_clear()
+            .envs(&params.env)
+            .process_group(0);
+
+        let child: std::process::Child = signal::spawn_child(&mut command, true)?;
+        let result: std::io::Result<std::process::Output> = child.wait_with_output();
+        signal::clear_child();
+        let output: std::process::Output = result?;
+        if signal::interrupted() || signal::child_interrupted(&output.status) {
+            return Err(BError::Interrupted);
+        }
+        if !output.status.success() {
+            return Err(BError::CliError(format!(
+                "{}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
 
     fn init_env_file(
         &self,
@@ -169,11 +244,12 @@ impl System for BSystem {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::os::unix::process::CommandExt;
     use std::path::PathBuf;
     use std::{fs::File, io::Write};
     use tempdir::TempDir;
 
-    use crate::cli::{BSystem, System};
+    use crate::cli::{signal, BSystem, System};
     use crate::error::BError;
 
     use super::CallParams;
@@ -320,4 +396,102 @@ mod tests {
         assert!(work_dir.clone().join("test2").exists());
         assert!(work_dir.clone().join("test3").exists());
     }
+
+    #[test]
+    fn test_system_check_output() {
+        let system: BSystem = BSystem::new();
+        let params: CallParams = CallParams {
+            cmd_line: vec![String::from("echo"), String::from("$TEST_VALUE")],
+            env: HashMap::from([(String::from("TEST_VALUE"), String::from("value"))]),
+            shell: true,
+        };
+        assert_eq!(
+            system
+                .check_output(&params)
+                .expect("Failed to capture output"),
+            "value\n"
+        );
+        let params: CallParams = CallParams {
+            cmd_line: vec![String::from("echo error >&2; exit 3")],
+            env: HashMap::new(),
+            shell: true,
+        };
+        match system.check_output(&params) {
+            Ok(_output) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), "exit status: 3: error");
+            }
+        }
+    }
+
+    #[test]
+    fn test_system_check_call_interrupted() {
+        let system: BSystem = BSystem::new();
+        let params: CallParams = CallParams {
+            cmd_line: vec![String::from("kill -TERM $$")],
+            env: HashMap::new(),
+            shell: true,
+        };
+        let result: Result<(), BError> = system.check_call(&params);
+        match result {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), "Interrupted");
+            }
+        }
+    }
+
+    /*
+     * Runs the test below in its own process group the same way a shell is
+     * running yaab in the foreground of a terminal. The child is sending
+     * SIGINT to its process group like a Ctrl-C from the terminal and exits
+     * with 130 like "docker run -t" is doing.
+     */
+    #[test]
+    fn test_system_check_call_foreground_interrupted() {
+        let output: std::process::Output = std::process::Command::new(
+            std::env::current_exe().expect("Failed to get the test executable"),
+        )
+        .args([
+            "--exact",
+            "cli::system::tests::test_system_check_call_foreground_child",
+            "--ignored",
+        ])
+        .env("YAAB_TEST_FOREGROUND_CHILD", "1")
+        .stdin(std::process::Stdio::null())
+        .process_group(0)
+        .output()
+        .expect("Failed to run the foreground test");
+        let stdout: String = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("1 passed"), "{}", stdout);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_system_check_call_foreground_child() {
+        if std::env::var("YAAB_TEST_FOREGROUND_CHILD").is_err() {
+            return;
+        }
+        signal::install().expect("Failed to install the signal handling");
+        let system: BSystem = BSystem::new();
+        let params: CallParams = CallParams {
+            cmd_line: vec![String::from("trap 'exit 130' INT; kill -INT 0; sleep 10")],
+            env: HashMap::new(),
+            shell: true,
+        };
+        let result: Result<(), BError> = system.call(&params, true);
+        match result {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), "Interrupted");
+            }
+        }
+    }
 }
@@ -1,7 +1,8 @@
-use crate::cli::{BLogger, Cli};
+use crate::cli::{handshake, signal, BLogger, Cli};
 use crate::commands::YCommand;
 use crate::configs::WsConfigFileHandler;
 use crate::error::BError;
+use crate::executers::Docker;
 use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
 
 use clap::Command;
@@ -25,6 +26,7 @@ impl Yaab {
             Box::new(BSystem::new()),
             Command::new("yaab")
                 .version(env!("CARGO_PKG_VERSION"))
+                .long_version(handshake::long_version())
                 .subcommand_required(true)
                 .arg_required_else_help(true)
                 .about("Yet Another Android Build tool")
@@ -40,6 +42,10 @@ impl Yaab {
             Ok(content) => {
                 return content;
             }
+            Err(BError::Interrupted) => {
+                self.interrupted();
+                std::process::exit(signal::YAAB_INTERRUPTED_EXIT_CODE);
+            }
             Err(err) => {
                 self.cli.error(format!("{}", err.to_string()));
                 std::process::exit(1);
@@ -47,7 +53,27 @@ impl Yaab {
         }
     }
 
+    /*
+     * When yaab is interrupted the signal has already been forwarded to the
+     * running child so all we need to do is to make sure that no container
+     * started by yaab is left running holding on to the workspace.
+     */
+    fn interrupted(&self) {
+        self.cli.error(String::from("Interrupted"));
+        signal::set_interrupted(false);
+        if Docker::find_runtime().is_some() {
+            let cmd_line: Vec<String> =
+                Docker::cleanup_cmd_line(signal::session(), &signal::containers());
+            if let Err(err) = self.cli.check_call(&cmd_line, &self.cli.env(), true) {
+                self.cli
+                    .error(format!("Failed to clean up containers: {}", err));
+            }
+        }
+    }
+
     pub fn assemble(&self) {
+        self.match_or_exit::<()>(signal::install());
+        self.match_or_exit::<()>(handshake::check(&self.cli.env()));
         let work_dir: PathBuf = self.cli.get_curr_dir();
         let home_dir: PathBuf = self.cli.get_home_dir();
         let cfg_handler: WsConfigFileHandler = WsConfigFileHandler::new(&work_dir, &home_dir);
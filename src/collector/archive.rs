@@ -1,10 +1,11 @@
 use crate::cli::Cli;
 use crate::collector::{Collected, Collector, CollectorFactory};
 use crate::error::BError;
-use crate::fs::Archiver;
+use crate::fs::{parse_size, Archiver};
 use crate::workspace::WsArtifactsHandler;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tempdir::TempDir;
 
 pub struct ArchiveCollector<'a> {
@@ -32,7 +33,16 @@ impl<'a> Collector for ArchiveCollector<'a> {
         }
 
         let files: Vec<PathBuf> = collected.iter().map(|f| f.dest.clone()).collect();
-        let archiver: Archiver = Archiver::new(&archive_path)?;
+        let mut archiver: Archiver = Archiver::new(&archive_path)?;
+        if !self.artifact.data().level().is_empty() {
+            archiver.set_level(self.parse("level", self.artifact.data().level())?);
+        }
+        if !self.artifact.data().threads().is_empty() {
+            archiver.set_threads(self.parse("threads", self.artifact.data().threads())?);
+        }
+        if self.artifact.data().reproducible() {
+            archiver.set_reproducible(self.mtime(src)?);
+        }
         archiver.add_files(&files, &archive_tmp_dir)?;
         self.info(
             self.cli,
@@ -42,12 +52,49 @@ impl<'a> Collector for ArchiveCollector<'a> {
             ),
         );
 
+        /*
+         * A split archive is collected as the volumes of the archive so each
+         * volume is added to the artifacts index with its own checksum.
+         */
+        if let Some(split_size) = self.split_size()? {
+            let volumes: Vec<PathBuf> = archiver.split(split_size)?;
+            self.info(
+                self.cli,
+                format!(
+                    "Archive '{}' split into {} volume(s)",
+                    archive_name,
+                    volumes.len()
+                ),
+            );
+            return Ok(volumes
+                .into_iter()
+                .map(|v| Collected {
+                    src: PathBuf::from(""),
+                    dest: v,
+                })
+                .collect());
+        }
+
         Ok(vec![Collected {
             src: PathBuf::from(""),
             dest: archive_path,
         }])
     }
 
+    /*
+     * The files of an archive are resolved as if the archive was a directory
+     * so any file in the archive is listed with the archive as its parent.
+     */
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let archive_path: PathBuf = dest.join(PathBuf::from(self.artifact.data().name()));
+        let mut resolved: Vec<Collected> = vec![];
+        for child in self.artifact.children().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            resolved.append(&mut collector.resolve(src, &archive_path)?);
+        }
+        Ok(resolved)
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         if self.artifact.data().name().is_empty() || self.artifact.children().is_empty() {
             return Err(BError::ValueError(String::from(
@@ -59,6 +106,78 @@ impl<'a> Collector for ArchiveCollector<'a> {
 }
 
 impl<'a> ArchiveCollector<'a> {
+    /*
+     * The mtime used for a reproducible archive is taken from the mtime attribute
+     * of the archive node which can be set using a context variable. If it is not
+     * set SOURCE_DATE_EPOCH is used and if that is not set either the commit time
+     * of the git repository the artifacts are collected from. Only if none of
+     * them are available the mtime of all files is set to 0.
+     */
+    fn mtime(&self, src: &Path) -> Result<u64, BError> {
+        let mut mtime: String = self.artifact.data().mtime().to_string();
+        if mtime.is_empty() {
+            mtime = std::env::var("SOURCE_DATE_EPOCH").unwrap_or_default();
+        }
+        if mtime.is_empty() {
+            mtime = self.commit_time(src).unwrap_or(String::from("0"));
+            self.info(
+                self.cli,
+                format!(
+                    "No mtime or SOURCE_DATE_EPOCH set for reproducible archive '{}', using {}",
+                    self.artifact.data().name(),
+                    mtime
+                ),
+            );
+        }
+        self.parse::<u64>("mtime", &mtime)
+    }
+
+    fn commit_time(&self, src: &Path) -> Option<String> {
+        let cli: &Cli = self.cli?;
+        let output: String = cli
+            .check_output(
+                &[
+                    String::from("git"),
+                    String::from("-C"),
+                    src.to_string_lossy().to_string(),
+                    String::from("log"),
+                    String::from("-1"),
+                    String::from("--format=%ct"),
+                ],
+                &cli.env(),
+                true,
+            )
+            .ok()?;
+        let commit_time: String = output.trim().to_string();
+        if commit_time.is_empty() {
+            return None;
+        }
+        Some(commit_time)
+    }
+
+    fn split_size(&self) -> Result<Option<u64>, BError> {
+        let split_size: &str = self.artifact.data().split_size();
+        if split_size.is_empty() {
+            return Ok(None);
+        }
+        match parse_size(split_size) {
+            Some(size) if size > 0 => Ok(Some(size)),
+            _ => Err(BError::ValueError(format!(
+                "Archive node attribute 'splitsize' must be a size in bytes or K, M, G or T, got '{}'!",
+                split_size
+            ))),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, attribute: &str, value: &str) -> Result<T, BError> {
+        value.parse::<T>().map_err(|_e| {
+            BError::ValueError(format!(
+                "Archive node attribute '{}' must be a number, got '{}'!",
+                attribute, value
+            ))
+        })
+    }
+
     pub fn new(artifact: &'a WsArtifactsHandler, cli: Option<&'a Cli>) -> Self {
         ArchiveCollector { artifact, cli }
     }
@@ -66,11 +185,13 @@ impl<'a> ArchiveCollector<'a> {
 
 #[cfg(test)]
 mod tests {
+    use crate::cli::{BLogger, CallParams, Cli, MockSystem};
     use crate::collector::{ArchiveCollector, Collected, Collector};
     use crate::data::WsBuildData;
     use crate::helper::Helper;
     use crate::workspace::WsArtifactsHandler;
 
+    use std::collections::HashMap;
     use std::path::PathBuf;
     use tempdir::TempDir;
 
@@ -125,6 +246,185 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_archive_collector_commit_time() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("file1.txt")];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "archive.tar",
+            "reproducible": "true",
+            "artifacts": [
+                {
+                    "source": "file1.txt"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_env().returning(HashMap::new);
+        mocked_system
+            .expect_check_output()
+            .with(mockall::predicate::eq(CallParams {
+                cmd_line: vec![
+                    String::from("git"),
+                    String::from("-C"),
+                    task_build_dir.to_string_lossy().to_string(),
+                    String::from("log"),
+                    String::from("-1"),
+                    String::from("--format=%ct"),
+                ],
+                env: HashMap::new(),
+                shell: true,
+            }))
+            .once()
+            .returning(|_x| Ok(String::from("1700000000\n")));
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let collector: ArchiveCollector = ArchiveCollector::new(&artifacts, Some(&cli));
+        assert_eq!(
+            collector.commit_time(&task_build_dir),
+            Some(String::from("1700000000"))
+        );
+        let collector: ArchiveCollector = ArchiveCollector::new(&artifacts, None);
+        assert_eq!(collector.commit_time(&task_build_dir), None);
+    }
+
+    #[test]
+    fn test_archive_collector_split() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("file1.txt")];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "archive.tar",
+            "splitsize": "1K",
+            "artifacts": [
+                {
+                    "source": "file1.txt"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: ArchiveCollector = ArchiveCollector::new(&artifacts, None);
+        assert!(collector.verify_attributes().is_ok());
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert!(collected.len() > 1);
+        for (i, c) in collected.iter().enumerate() {
+            assert_eq!(
+                c.dest,
+                artifacts_dir.join(format!("archive.tar.part-{:02}", i))
+            );
+            assert!(std::fs::metadata(&c.dest).unwrap().len() <= 1024);
+        }
+        assert!(!artifacts_dir.join("archive.tar").exists());
+    }
+
+    #[test]
+    fn test_archive_collector_compression() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("file1.txt")];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "archive.tar.zst",
+            "level": "19",
+            "threads": "2",
+            "artifacts": [
+                {
+                    "source": "file1.txt"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: ArchiveCollector = ArchiveCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert!(artifacts_dir.join("archive.tar.zst").exists());
+        assert_eq!(collected.len(), 1);
+    }
+
+    #[test]
+    fn test_archive_collector_error_level() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("file1.txt")];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "archive.tar.xz",
+            "level": "max",
+            "artifacts": [
+                {
+                    "source": "file1.txt"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: ArchiveCollector = ArchiveCollector::new(&artifacts, None);
+        let result = collector.collect(&task_build_dir, &build_data.settings().artifacts_dir());
+        match result {
+            Ok(_c) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Archive node attribute 'level' must be a number, got 'max'!"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_archive_collector_nested() {
         let archive_name: &str = "archive.zip";
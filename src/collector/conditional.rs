@@ -12,24 +12,39 @@ pub struct ConditionalCollector<'a> {
 
 impl<'a> Collector for ConditionalCollector<'a> {
     fn collect(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
-        let condition: bool = self.artifact.data().condition();
         let mut collected: Vec<Collected> = vec![];
 
-        if condition {
-            for child in self.artifact.children().iter() {
-                let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
-                let mut c: Vec<Collected> = collector.collect(src, dest)?;
-                collected.append(&mut c);
+        if !self.artifact.data().condition() {
+            if self.artifact.else_children().is_empty() {
+                self.info(self.cli, "Skipping collecting condition false".to_string());
+            } else {
+                self.info(
+                    self.cli,
+                    "Condition false collecting else artifacts".to_string(),
+                );
             }
-        } else {
-            self.info(self.cli, "Skipping collecting condition false".to_string());
+        }
+
+        for child in self.selected().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            let mut c: Vec<Collected> = collector.collect(src, dest)?;
+            collected.append(&mut c);
         }
 
         Ok(collected)
     }
 
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let mut resolved: Vec<Collected> = vec![];
+        for child in self.selected().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            resolved.append(&mut collector.resolve(src, dest)?);
+        }
+        Ok(resolved)
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
-        if self.artifact.children().is_empty() {
+        if self.artifact.children().is_empty() && self.artifact.else_children().is_empty() {
             return Err(BError::ValueError(String::from(
                 "Conditional node requires list of artifacts!",
             )));
@@ -43,6 +58,13 @@ impl<'a> ConditionalCollector<'a> {
     pub fn new(artifact: &'a WsArtifactsHandler, cli: Option<&'a Cli>) -> Self {
         ConditionalCollector { artifact, cli }
     }
+
+    fn selected(&self) -> &'a Vec<WsArtifactsHandler> {
+        if self.artifact.data().condition() {
+            return self.artifact.children();
+        }
+        self.artifact.else_children()
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +156,139 @@ mod tests {
         assert!(collected.is_empty());
     }
 
+    #[test]
+    fn test_conditional_collector_else() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("signed.img"),
+            task_build_dir.clone().join("unsigned.img"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "conditional",
+            "condition": "$#[SIGNED]",
+            "artifacts": [
+                {
+                    "source": "signed.img",
+                    "dest": "image.img"
+                }
+            ],
+            "else": [
+                {
+                    "source": "unsigned.img",
+                    "dest": "image.img"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let mut artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let variables: IndexMap<String, String> = indexmap! {
+            "SIGNED".to_string() => "no".to_string(),
+        };
+        let context: Context = Context::new(&variables);
+        artifacts.expand_ctx(&context).unwrap();
+        let collector: ConditionalCollector = ConditionalCollector::new(&artifacts, None);
+        assert!(collector.verify_attributes().is_ok());
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(
+            &collected,
+            &vec![Collected {
+                src: task_build_dir.clone().join("unsigned.img"),
+                dest: artifacts_dir.clone().join("image.img")
+            },]
+        );
+    }
+
+    #[test]
+    fn test_conditional_collector_else_only() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("unsigned.img")];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "conditional",
+            "condition": "$#[SIGNED]",
+            "else": [
+                {
+                    "source": "unsigned.img"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let mut artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let variables: IndexMap<String, String> = indexmap! {
+            "SIGNED".to_string() => "no".to_string(),
+        };
+        let context: Context = Context::new(&variables);
+        artifacts.expand_ctx(&context).unwrap();
+        let collector: ConditionalCollector = ConditionalCollector::new(&artifacts, None);
+        assert!(collector.verify_attributes().is_ok());
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(
+            &collected,
+            &vec![Collected {
+                src: task_build_dir.clone().join("unsigned.img"),
+                dest: artifacts_dir.clone().join("unsigned.img")
+            },]
+        );
+    }
+
+    #[test]
+    fn test_conditional_collector_no_artifacts() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "conditional",
+            "condition": "true"
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &vec![],
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: ConditionalCollector = ConditionalCollector::new(&artifacts, None);
+        match collector.verify_attributes() {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Conditional node requires list of artifacts!"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_conditional_collector_ctx() {
         let temp_dir: TempDir =
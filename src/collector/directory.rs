@@ -31,6 +31,16 @@ impl<'a> Collector for DirectoryCollector<'a> {
         Ok(collected)
     }
 
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let directory_path: PathBuf = dest.join(PathBuf::from(self.artifact.data().name()));
+        let mut resolved: Vec<Collected> = vec![];
+        for child in self.artifact.children().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            resolved.append(&mut collector.resolve(src, &directory_path)?);
+        }
+        Ok(resolved)
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         if self.artifact.data().name().is_empty() || self.artifact.children().is_empty() {
             return Err(BError::ValueError(String::from(
@@ -56,6 +66,7 @@ mod tests {
     use crate::workspace::WsArtifactsHandler;
 
     use indexmap::{indexmap, IndexMap};
+    use std::os::unix::fs::MetadataExt;
     use std::path::PathBuf;
     use tempdir::TempDir;
 
@@ -185,6 +196,117 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_directory_collector_exclude() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("bin/tool"),
+            task_build_dir.clone().join("bin/tool.debug"),
+            task_build_dir.clone().join("lib/lib.so"),
+            task_build_dir.clone().join("lib/lib.so.debug"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "directory",
+            "name": "dir",
+            "exclude": [
+                "*.debug"
+            ],
+            "artifacts": [
+                {
+                    "source": "bin/*"
+                },
+                {
+                    "type": "directory",
+                    "name": "lib",
+                    "artifacts": [
+                        {
+                            "source": "lib/*"
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: DirectoryCollector = DirectoryCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(
+            &collected,
+            &vec![
+                Collected {
+                    src: task_build_dir.clone().join("bin/tool"),
+                    dest: artifacts_dir.clone().join("dir/tool")
+                },
+                Collected {
+                    src: task_build_dir.clone().join("lib/lib.so"),
+                    dest: artifacts_dir.clone().join("dir/lib/lib.so")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directory_collector_mode() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("out/system.img"),
+            task_build_dir.clone().join("out/build.prop"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "directory",
+            "name": "images",
+            "mode": "hardlink",
+            "artifacts": [
+                {
+                    "source": "out/system.img"
+                },
+                {
+                    "source": "out/build.prop",
+                    "mode": "copy"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: DirectoryCollector = DirectoryCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        let ino = |path: PathBuf| std::fs::metadata(path).unwrap().ino();
+        assert_eq!(
+            ino(task_build_dir.join("out/system.img")),
+            ino(artifacts_dir.join("images/system.img"))
+        );
+        assert_ne!(
+            ino(task_build_dir.join("out/build.prop")),
+            ino(artifacts_dir.join("images/build.prop"))
+        );
+    }
+
     #[test]
     fn test_directory_collector_context() {
         let temp_dir: TempDir =
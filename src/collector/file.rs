@@ -1,8 +1,11 @@
 use crate::cli::Cli;
 use crate::collector::{Collected, Collector};
 use crate::error::BError;
+use crate::fs::{Copier, CopyMode};
 use crate::workspace::WsArtifactsHandler;
 
+use glob::Pattern;
+use regex::Regex;
 use std::path::{Path, PathBuf};
 
 pub struct FileCollector<'a> {
@@ -12,29 +15,23 @@ pub struct FileCollector<'a> {
 
 impl<'a> Collector for FileCollector<'a> {
     fn collect(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
-        let dest_str: &str = self.artifact.data().dest();
-        let src_path: PathBuf = src.join(PathBuf::from(self.artifact.data().source()));
-        let dest_path: PathBuf = dest.join(PathBuf::from(dest_str));
-        let files: Vec<PathBuf> = self.list_files(&src_path)?;
-        let base_dir: &Path = src_path.parent().unwrap();
+        let (files, excluded) = self.resolve_files(src, dest)?;
         let mut collected: Vec<Collected> = vec![];
+        let copier: Copier = Copier {
+            symlinks: self.artifact.data().symlinks(),
+            preserve: self.artifact.data().preserve(),
+            sparse: self.artifact.data().sparse(),
+            mode: self.artifact.mode().parse::<CopyMode>()?,
+        };
 
-        if files.is_empty() && !src_path.exists() {
-            return Err(BError::IOError(format!(
-                "File '{}' dose not exists",
-                src_path.display()
-            )));
+        for f in excluded.iter() {
+            self.info(self.cli, format!("Exclude file {}", f.display()));
         }
 
-        for f in files.iter() {
-            let mut dest_file: PathBuf = dest_path.clone();
-            if self.is_dir(&dest_path, dest_str) {
-                let src_prefix: &Path = f.strip_prefix(base_dir)?;
-                //println!("Prefix: {}", src_prefix.display());
-                dest_file = dest_file.join(PathBuf::from(src_prefix));
-            }
-
-            if !f.exists() {
+        for file in files.into_iter() {
+            let f: &PathBuf = &file.src;
+            let dest_file: &PathBuf = &file.dest;
+            if !(f.exists() || copier.symlinks && f.is_symlink()) {
                 return Err(BError::IOError(format!(
                     "File '{}' dose not exists",
                     f.display()
@@ -46,16 +43,18 @@ impl<'a> Collector for FileCollector<'a> {
                 format!("Copy file {} => {}", f.display(), dest_file.display()),
             );
             std::fs::create_dir_all(dest_file.parent().unwrap())?;
-            std::fs::copy(f, &dest_file)?;
-            collected.push(Collected {
-                src: f.clone(),
-                dest: dest_file.clone(),
-            });
+            copier.copy(f, dest_file)?;
+            collected.push(file);
         }
 
         Ok(collected)
     }
 
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let (files, _excluded) = self.resolve_files(src, dest)?;
+        Ok(files)
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         if self.artifact.data().source().is_empty() {
             return Err(BError::ValueError(String::from(
@@ -68,6 +67,127 @@ impl<'a> Collector for FileCollector<'a> {
 }
 
 impl<'a> FileCollector<'a> {
+    /*
+     * Resolve the source against the build directory and return the files to
+     * collect together with the files that are excluded. If the source is not
+     * matching any file and does not exist the source itself is returned so
+     * that it is reported as missing.
+     */
+    fn resolve_files(
+        &self,
+        src: &Path,
+        dest: &Path,
+    ) -> Result<(Vec<Collected>, Vec<PathBuf>), BError> {
+        let dest_str: &str = self.artifact.data().dest();
+        let src_path: PathBuf = src.join(PathBuf::from(self.artifact.data().source()));
+        let dest_path: PathBuf = dest.join(PathBuf::from(dest_str));
+        let files: Vec<PathBuf> = self.list_files(&src_path)?;
+        let base_dir: &Path = src_path.parent().unwrap();
+        let exclude: Vec<Pattern> = self.exclude_patterns()?;
+        let rename: Option<Regex> = self.rename_regex()?;
+        let mut resolved: Vec<Collected> = vec![];
+        let mut excluded: Vec<PathBuf> = vec![];
+
+        if files.is_empty() && !src_path.exists() {
+            resolved.push(Collected {
+                src: src_path.clone(),
+                dest: dest_path.clone(),
+            });
+            return Ok((resolved, excluded));
+        }
+
+        for f in files.iter() {
+            let src_prefix: &Path = f.strip_prefix(base_dir)?;
+            if self.excluded(&exclude, src_prefix) {
+                excluded.push(f.clone());
+                continue;
+            }
+
+            let mut dest_file: PathBuf = dest_path.clone();
+            if self.is_dir(&dest_path, dest_str) {
+                dest_file = dest_file.join(PathBuf::from(src_prefix));
+            }
+            if let Some(regex) = &rename {
+                dest_file = self.renamed(regex, &dest_file);
+            }
+            resolved.push(Collected {
+                src: f.clone(),
+                dest: dest_file,
+            });
+        }
+        Ok((resolved, excluded))
+    }
+
+    fn exclude_patterns(&self) -> Result<Vec<Pattern>, BError> {
+        let mut patterns: Vec<Pattern> = vec![];
+        for e in self.artifact.data().exclude().iter() {
+            patterns.push(Pattern::new(e)?);
+        }
+        Ok(patterns)
+    }
+
+    fn rename_regex(&self) -> Result<Option<Regex>, BError> {
+        let rename: &str = self.artifact.data().rename();
+        if rename.is_empty() {
+            return Ok(None);
+        }
+        match Regex::new(rename) {
+            Ok(regex) => Ok(Some(regex)),
+            Err(e) => Err(BError::CollectorError(format!(
+                "Invalid rename pattern '{}': {}",
+                rename, e
+            ))),
+        }
+    }
+
+    /*
+     * A pattern ending with a '/' is excluding a directory and is matched
+     * against all the directories in the path of the file relative to the
+     * source. A pattern containing a '/' is matched against the relative path
+     * of the file and any other pattern is matched against the file name.
+     */
+    fn excluded(&self, patterns: &[Pattern], path: &Path) -> bool {
+        patterns.iter().any(|p| {
+            let pattern: &str = p.as_str();
+            if let Some(dir) = pattern.strip_suffix('/') {
+                return match Pattern::new(dir) {
+                    Ok(dir_pattern) => path
+                        .parent()
+                        .map(|parent| {
+                            parent
+                                .components()
+                                .any(|c| dir_pattern.matches(&c.as_os_str().to_string_lossy()))
+                        })
+                        .unwrap_or(false),
+                    Err(_e) => false,
+                };
+            }
+            if pattern.contains('/') {
+                return p.matches_path(path);
+            }
+            path.file_name()
+                .map(|name| p.matches(&name.to_string_lossy()))
+                .unwrap_or(false)
+        })
+    }
+
+    /*
+     * The rename rule is only applied to the file name and the replacement can
+     * refer to the captures of the regex using $1 or ${name}.
+     */
+    fn renamed(&self, regex: &Regex, dest_file: &Path) -> PathBuf {
+        match dest_file.file_name() {
+            Some(name) => {
+                let name: String = name.to_string_lossy().to_string();
+                let renamed: String = regex
+                    .replace(&name, self.artifact.data().replace())
+                    .to_string();
+                dest_file.with_file_name(renamed)
+            }
+            None => dest_file.to_path_buf(),
+        }
+    }
+
     fn is_dir(&self, dest_path: &PathBuf, dest_str: &str) -> bool {
         let file_name = dest_path.file_name();
 
@@ -96,7 +216,9 @@ impl<'a> FileCollector<'a> {
                     match entry {
                         Ok(path) => {
                             //println!("{:?}", path.clone().display());
-                            if path.is_dir() {
+                            if path.is_dir()
+                                && !(self.artifact.data().symlinks() && path.is_symlink())
+                            {
                                 let mut f: Vec<PathBuf> = self.list_files(&path.join("*"))?;
                                 files.append(&mut f);
                             } else {
@@ -132,6 +254,7 @@ mod tests {
     use crate::workspace::WsArtifactsHandler;
 
     use indexmap::{indexmap, IndexMap};
+    use std::os::unix::fs::MetadataExt;
     use std::path::PathBuf;
     use tempdir::TempDir;
 
@@ -414,6 +537,193 @@ mod tests {
         assert!(dest.exists());
     }
 
+    #[test]
+    fn test_file_collector_exclude() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("out/a.img"),
+            task_build_dir.clone().join("out/a.img.debug"),
+            task_build_dir.clone().join("out/obj/x.o"),
+            task_build_dir.clone().join("out/sub/b.img"),
+            task_build_dir.clone().join("out/sub/tmp/c.img"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "source": "out/*",
+            "dest": "images/",
+            "exclude": [
+                "*.debug",
+                "obj/",
+                "sub/tmp/*"
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let collector: FileCollector = FileCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(
+            collected,
+            vec![
+                Collected {
+                    src: task_build_dir.join("out/a.img"),
+                    dest: artifacts_dir.join("images/a.img")
+                },
+                Collected {
+                    src: task_build_dir.join("out/sub/b.img"),
+                    dest: artifacts_dir.join("images/sub/b.img")
+                },
+            ]
+        );
+        assert!(!artifacts_dir.join("images/a.img.debug").exists());
+    }
+
+    #[test]
+    fn test_file_collector_rename() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("out/system.img"),
+            task_build_dir.clone().join("out/vendor.img"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "source": "out/*.img",
+            "rename": {
+                "pattern": "^(?P<name>.*)\\.img$",
+                "replace": "${name}-$#[YAAB_PLATFORM_RELEASE].img"
+            }
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let mut artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_PLATFORM_RELEASE".to_string() => "1.0.0-20".to_string(),
+        };
+        artifacts.expand_ctx(&Context::new(&variables)).unwrap();
+        let collector: FileCollector = FileCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(
+            collected,
+            vec![
+                Collected {
+                    src: task_build_dir.join("out/system.img"),
+                    dest: artifacts_dir.join("system-1.0.0-20.img")
+                },
+                Collected {
+                    src: task_build_dir.join("out/vendor.img"),
+                    dest: artifacts_dir.join("vendor-1.0.0-20.img")
+                },
+            ]
+        );
+        for c in collected.iter() {
+            assert!(c.dest.exists());
+        }
+    }
+
+    #[test]
+    fn test_file_collector_symlinks() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("out/lib/libfoo.so.1")];
+        let json_artifacts_config: &str = r#"
+        {
+            "source": "out/*",
+            "symlinks": "true",
+            "preserve": "true"
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        std::os::unix::fs::symlink("libfoo.so.1", task_build_dir.join("out/lib/libfoo.so"))
+            .expect("Failed to create link");
+        std::os::unix::fs::symlink("lib", task_build_dir.join("out/lib64"))
+            .expect("Failed to create link");
+        let collector: FileCollector = FileCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        assert_eq!(collected.len(), 3);
+        assert!(artifacts_dir.join("lib/libfoo.so").is_symlink());
+        assert!(!artifacts_dir.join("lib/libfoo.so.1").is_symlink());
+        assert!(artifacts_dir.join("lib64").is_symlink());
+        assert_eq!(
+            std::fs::read_link(artifacts_dir.join("lib64")).expect("Failed to read link"),
+            PathBuf::from("lib")
+        );
+    }
+
+    #[test]
+    fn test_file_collector_mode() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![task_build_dir.clone().join("out/system.img")];
+        let json_settings: &str = r#"
+        {
+            "version": "5",
+            "workspace": {
+                "collectmode": "hardlink"
+            }
+        }"#;
+        let json_artifacts_config: &str = r#"
+        {
+            "source": "out/system.img"
+        }"#;
+        let build_data: WsBuildData =
+            Helper::setup_build_data(&work_dir, None, Some(json_settings));
+        let artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        assert_eq!(artifacts.mode(), "hardlink");
+        let collector: FileCollector = FileCollector::new(&artifacts, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        let src_ino: u64 = std::fs::metadata(task_build_dir.join("out/system.img"))
+            .unwrap()
+            .ino();
+        let dest_ino: u64 = std::fs::metadata(artifacts_dir.join("system.img"))
+            .unwrap()
+            .ino();
+        assert_eq!(src_ino, dest_ino);
+    }
+
     #[test]
     fn test_file_collector_source_missing() {
         let src_file_name: &str = "file.txt";
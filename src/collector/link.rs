@@ -46,6 +46,13 @@ impl<'a> Collector for LinkCollector<'a> {
         Ok(collected)
     }
 
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        Ok(vec![Collected {
+            src: src.join(PathBuf::from(self.artifact.data().source())),
+            dest: dest.join(PathBuf::from(self.artifact.data().name())),
+        }])
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         if self.artifact.data().source().is_empty() {
             return Err(BError::ValueError(String::from(
@@ -1,10 +1,13 @@
 use crate::cli::Cli;
 use crate::collector::{Collected, Collector};
+use crate::data::context::{CTX_KEY_ARTIFACTS_DIR, CTX_KEY_WORKSPACE_DIR};
 use crate::error::BError;
-use crate::fs::Manifest;
+use crate::executers::Docker;
+use crate::fs::{workspace_revisions, ArtifactsIndex, Manifest};
 use crate::workspace::WsArtifactsHandler;
 
-use std::path::PathBuf;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
 
 pub struct ManifestCollector<'a> {
     artifact: &'a WsArtifactsHandler,
@@ -21,7 +24,7 @@ impl<'a> Collector for ManifestCollector<'a> {
             self.cli,
             format!("Creating manifest file '{}'", manifest_file),
         );
-        manifest.write(self.artifact.data().manifest())?;
+        manifest.write(&self.content(dest)?)?;
         self.info(
             self.cli,
             format!(
@@ -37,6 +40,13 @@ impl<'a> Collector for ManifestCollector<'a> {
         }])
     }
 
+    fn resolve(&self, _src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        Ok(vec![Collected {
+            src: PathBuf::from(""),
+            dest: dest.join(PathBuf::from(self.artifact.data().name())),
+        }])
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         if self.artifact.data().name().is_empty() || self.artifact.data().manifest().is_empty() {
             return Err(BError::ValueError(String::from(
@@ -51,6 +61,74 @@ impl<'a> ManifestCollector<'a> {
     pub fn new(artifact: &'a WsArtifactsHandler, cli: Option<&'a Cli>) -> Self {
         ManifestCollector { artifact, cli }
     }
+
+    fn ctx_dir(&self, key: &str) -> Option<PathBuf> {
+        self.artifact
+            .data()
+            .context()
+            .get(&key.to_lowercase())
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /*
+     * The content of the manifest with the generated sections added. Each
+     * section is added as a key of its own replacing any key with the same
+     * name in the content from the build config.
+     */
+    fn content(&self, dest: &Path) -> Result<String, BError> {
+        let generate: &Vec<String> = self.artifact.data().generate();
+        if generate.is_empty() {
+            return Ok(self.artifact.data().manifest().to_string());
+        }
+
+        let mut content: Value = serde_json::from_str(self.artifact.data().manifest())?;
+        let artifacts_dir: PathBuf = self
+            .ctx_dir(CTX_KEY_ARTIFACTS_DIR)
+            .unwrap_or(dest.to_path_buf());
+        for section in generate.iter() {
+            let value: Value = match section.as_str() {
+                "artifacts" => ArtifactsIndex::load(&artifacts_dir, "")?.artifacts_json(),
+                "tasks" => ArtifactsIndex::load(&artifacts_dir, "")?.tasks_json(),
+                "docker" => {
+                    let image: &str = self.artifact.docker_image();
+                    let digest: String = match self.cli {
+                        Some(cli) if !image.is_empty() => Docker::digest(cli, image),
+                        _ => String::new(),
+                    };
+                    json!({
+                        "image": image,
+                        "digest": digest,
+                    })
+                }
+                "revisions" => match (self.cli, self.ctx_dir(CTX_KEY_WORKSPACE_DIR)) {
+                    (Some(cli), Some(work_dir)) => Value::Object(
+                        workspace_revisions(cli, &work_dir)
+                            .into_iter()
+                            .map(|(project, revision)| (project, json!(revision)))
+                            .collect(),
+                    ),
+                    _ => Value::Object(Map::new()),
+                },
+                "context" => Value::Object(
+                    self.artifact
+                        .data()
+                        .context()
+                        .iter()
+                        .map(|(key, value)| (key.to_ascii_uppercase(), json!(value)))
+                        .collect(),
+                ),
+                _ => {
+                    return Err(BError::ValueError(format!(
+                        "Invalid manifest section '{}'",
+                        section
+                    )));
+                }
+            };
+            content[section] = value;
+        }
+        Ok(serde_json::to_string_pretty(&content)?)
+    }
 }
 
 #[cfg(test)]
@@ -58,12 +136,15 @@ mod tests {
     use crate::collector::{Collected, Collector, ManifestCollector};
     use crate::configs::Context;
     use crate::data::WsBuildData;
+    use crate::fs::ArtifactsIndex;
     use crate::helper::Helper;
     use crate::workspace::WsArtifactsHandler;
     use indexmap::{indexmap, IndexMap};
+    use serde_json::Value;
     use std::fs::File;
     use std::io::Read;
     use std::path::PathBuf;
+    use std::time::Duration;
     use tempdir::TempDir;
 
     #[test]
@@ -185,4 +266,71 @@ mod tests {
         let json_manifest_content: &str = r#"{"data":{"test4":"value4","test5":"value5","test6":"var3"},"test1":"var1","test2":"value2","test3":"var2"}"#;
         assert_eq!(json_manifest_content, contents);
     }
+
+    #[test]
+    fn test_manifest_collector_generate() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "manifest",
+            "name": "build-info.json",
+            "generate": [
+                "artifacts",
+                "tasks",
+                "docker",
+                "context"
+            ],
+            "content": {
+                "version": "$#[VERSION]"
+            }
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        std::fs::create_dir_all(&artifacts_dir).expect("Failed to create dir");
+        std::fs::write(artifacts_dir.join("boot.img"), "boot").expect("Failed to write file");
+        let mut index: ArtifactsIndex = ArtifactsIndex::new(&artifacts_dir, "default");
+        index
+            .add(&artifacts_dir.join("boot.img"), "task1", "file")
+            .expect("Failed to add entry");
+        index.add_task("task1", Duration::from_secs(42));
+        index.write(false).expect("Failed to write index");
+        let mut artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let variables: IndexMap<String, String> = indexmap! {
+            "VERSION".to_string() => "1.0.0".to_string(),
+            "YAAB_ARTIFACTS_DIR".to_string() => artifacts_dir.to_string_lossy().to_string(),
+        };
+        let context: Context = Context::new(&variables);
+        artifacts.expand_ctx(&context).unwrap();
+        let collector: ManifestCollector = ManifestCollector::new(&artifacts, None);
+        assert!(collector.verify_attributes().is_ok());
+        collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        let manifest: Value = serde_json::from_str(
+            &std::fs::read_to_string(artifacts_dir.join("build-info.json"))
+                .expect("Failed to read manifest file!"),
+        )
+        .expect("Failed to parse manifest file!");
+        assert_eq!(manifest["version"], "1.0.0");
+        assert_eq!(manifest["artifacts"][0]["path"], "boot.img");
+        assert_eq!(
+            manifest["artifacts"][0]["sha256"],
+            ArtifactsIndex::sha256(&artifacts_dir.join("boot.img")).expect("Failed to hash")
+        );
+        assert_eq!(manifest["tasks"][0]["name"], "task1");
+        assert_eq!(manifest["tasks"][0]["duration"], 42);
+        assert!(manifest["docker"]["image"].is_string());
+        assert_eq!(manifest["context"]["VERSION"], "1.0.0");
+        assert!(manifest.get("revisions").is_none());
+    }
 }
@@ -5,6 +5,7 @@ pub mod factory;
 pub mod file;
 pub mod link;
 pub mod manifest;
+pub mod switch;
 
 pub use archive::ArchiveCollector;
 pub use conditional::ConditionalCollector;
@@ -13,6 +14,7 @@ pub use factory::CollectorFactory;
 pub use file::FileCollector;
 pub use link::LinkCollector;
 pub use manifest::ManifestCollector;
+pub use switch::SwitchCollector;
 
 use crate::cli::Cli;
 use crate::error::BError;
@@ -30,6 +32,15 @@ pub trait Collector {
         Ok(vec![])
     }
 
+    /*
+     * Resolve the artifact against the build directory without collecting it
+     * and return what would be collected. A source that does not exist is
+     * included so it can be reported as missing.
+     */
+    fn resolve(&self, _src: &PathBuf, _dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        Ok(vec![])
+    }
+
     fn verify_attributes(&self) -> Result<(), BError> {
         Ok(())
     }
@@ -0,0 +1,174 @@
+use crate::cli::Cli;
+use crate::collector::{Collected, Collector, CollectorFactory};
+use crate::error::BError;
+use crate::workspace::WsArtifactsHandler;
+
+use std::path::PathBuf;
+
+pub struct SwitchCollector<'a> {
+    artifact: &'a WsArtifactsHandler,
+    cli: Option<&'a Cli>,
+}
+
+impl<'a> Collector for SwitchCollector<'a> {
+    fn collect(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let mut collected: Vec<Collected> = vec![];
+
+        match self.artifact.cases().get(self.artifact.data().value()) {
+            Some(_case) => {
+                self.info(
+                    self.cli,
+                    format!("Collecting case '{}'", self.artifact.data().value()),
+                );
+            }
+            None => {
+                self.info(
+                    self.cli,
+                    format!(
+                        "No case matching '{}' collecting default",
+                        self.artifact.data().value()
+                    ),
+                );
+            }
+        }
+
+        for child in self.selected().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            let mut c: Vec<Collected> = collector.collect(src, dest)?;
+            collected.append(&mut c);
+        }
+
+        Ok(collected)
+    }
+
+    fn resolve(&self, src: &PathBuf, dest: &PathBuf) -> Result<Vec<Collected>, BError> {
+        let mut resolved: Vec<Collected> = vec![];
+        for child in self.selected().iter() {
+            let collector: Box<dyn Collector> = CollectorFactory::create(child, None)?;
+            resolved.append(&mut collector.resolve(src, dest)?);
+        }
+        Ok(resolved)
+    }
+
+    fn verify_attributes(&self) -> Result<(), BError> {
+        if self.artifact.cases().is_empty() {
+            return Err(BError::ValueError(String::from(
+                "Switch node requires a list of cases!",
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> SwitchCollector<'a> {
+    pub fn new(artifact: &'a WsArtifactsHandler, cli: Option<&'a Cli>) -> Self {
+        SwitchCollector { artifact, cli }
+    }
+
+    /*
+     * The value is expanded using the context the same way as the condition
+     * of a conditional and the case matching the value is selected. If no case
+     * is matching the default artifacts are selected.
+     */
+    fn selected(&self) -> &'a Vec<WsArtifactsHandler> {
+        match self.artifact.cases().get(self.artifact.data().value()) {
+            Some(case) => case,
+            None => self.artifact.else_children(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collector::{Collected, Collector, SwitchCollector};
+    use crate::configs::Context;
+    use crate::data::WsBuildData;
+    use crate::helper::Helper;
+    use crate::workspace::WsArtifactsHandler;
+
+    use indexmap::{indexmap, IndexMap};
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    fn helper_test_switch_collector(value: &str) -> (PathBuf, PathBuf, Vec<Collected>) {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let task_build_dir: PathBuf = work_dir.clone().join("task/dir");
+        let files: Vec<PathBuf> = vec![
+            task_build_dir.clone().join("user.img"),
+            task_build_dir.clone().join("userdebug.img"),
+            task_build_dir.clone().join("eng.img"),
+        ];
+        let json_artifacts_config: &str = r#"
+        {
+            "type": "switch",
+            "value": "$#[VARIANT]",
+            "cases": {
+                "user": [
+                    {
+                        "source": "user.img",
+                        "dest": "image.img"
+                    }
+                ],
+                "userdebug": [
+                    {
+                        "source": "userdebug.img",
+                        "dest": "image.img"
+                    }
+                ]
+            },
+            "default": [
+                {
+                    "source": "eng.img",
+                    "dest": "image.img"
+                }
+            ]
+        }"#;
+        let build_data: WsBuildData = Helper::setup_build_data(&work_dir, None, None);
+        let mut artifacts: WsArtifactsHandler = Helper::setup_collector_test_ws(
+            &work_dir,
+            &task_build_dir,
+            &files,
+            &build_data,
+            json_artifacts_config,
+        );
+        let variables: IndexMap<String, String> = indexmap! {
+            "VARIANT".to_string() => value.to_string(),
+        };
+        let context: Context = Context::new(&variables);
+        artifacts.expand_ctx(&context).unwrap();
+        let collector: SwitchCollector = SwitchCollector::new(&artifacts, None);
+        assert!(collector.verify_attributes().is_ok());
+        let artifacts_dir: PathBuf = build_data.settings().artifacts_dir();
+        let collected: Vec<Collected> = collector
+            .collect(&task_build_dir, &artifacts_dir)
+            .expect("Failed to collect artifacts");
+        (task_build_dir, artifacts_dir, collected)
+    }
+
+    #[test]
+    fn test_switch_collector_case() {
+        let (task_build_dir, artifacts_dir, collected) = helper_test_switch_collector("userdebug");
+        assert_eq!(
+            &collected,
+            &vec![Collected {
+                src: task_build_dir.clone().join("userdebug.img"),
+                dest: artifacts_dir.clone().join("image.img")
+            },]
+        );
+    }
+
+    #[test]
+    fn test_switch_collector_default() {
+        let (task_build_dir, artifacts_dir, collected) = helper_test_switch_collector("eng");
+        assert_eq!(
+            &collected,
+            &vec![Collected {
+                src: task_build_dir.clone().join("eng.img"),
+                dest: artifacts_dir.clone().join("image.img")
+            },]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+
+use crate::cli::Cli;
+use crate::commands::{BError, YBaseCommand, YCommand};
+use crate::fs::Archiver;
+use crate::workspace::Workspace;
+
+static YCOMMAND: &str = "archive";
+static YCOMMAND_ABOUT: &str =
+    "Join or extract an archive split into volumes by an archive artifact.";
+pub struct ArchiveCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for ArchiveCommand {
+    fn get_config_name(&self, _cli: &Cli) -> String {
+        String::from("NA")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        self.cmd.require_docker
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let action: String = self.get_arg_str(cli, "action", YCOMMAND)?;
+        let archive: String = self.get_arg_str(cli, "archive", YCOMMAND)?;
+        let archiver: Archiver = self.archiver(workspace, &archive)?;
+
+        match action.as_str() {
+            "join" => {
+                let output: String = self.get_arg_str(cli, "output", YCOMMAND)?;
+                let mut dest: PathBuf = archiver.path().clone();
+                if !output.is_empty() {
+                    dest = PathBuf::from(output);
+                }
+                archiver.join(&dest)?;
+                cli.info(format!(
+                    "Joined {} volume(s) into '{}'",
+                    archiver.volumes().len(),
+                    dest.display()
+                ));
+                Ok(())
+            }
+            "extract" => {
+                let dir: String = self.get_arg_str(cli, "dir", YCOMMAND)?;
+                let mut dest: PathBuf = cli.get_curr_dir();
+                if !dir.is_empty() {
+                    dest = PathBuf::from(dir);
+                }
+                archiver.extract(&dest)?;
+                cli.info(format!(
+                    "Extracted '{}' to '{}'",
+                    archiver.name(),
+                    dest.display()
+                ));
+                Ok(())
+            }
+            _ => Err(BError::CliError(format!("Invalid action '{}'", action))),
+        }
+    }
+}
+
+impl ArchiveCommand {
+    /*
+     * The archive can be specified using the path of the archive or any of its
+     * volumes. A relative path is first looked up in the artifacts directory.
+     */
+    fn archiver(&self, workspace: &Workspace, archive: &str) -> Result<Archiver, BError> {
+        let mut archiver: Archiver = Archiver::from_volume(&PathBuf::from(archive))?;
+        let in_artifacts: Archiver =
+            Archiver::from_volume(&workspace.settings().artifacts_dir().join(archive))?;
+        if archiver.path().is_relative()
+            && (in_artifacts.path().exists() || !in_artifacts.volumes().is_empty())
+        {
+            archiver = in_artifacts;
+        }
+        if !archiver.path().exists() && archiver.volumes().is_empty() {
+            return Err(BError::IOError(format!(
+                "No archive or archive volumes found for '{}'",
+                archiver.path().display()
+            )));
+        }
+        Ok(archiver)
+    }
+
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND)
+            .about(YCOMMAND_ABOUT)
+            .arg_required_else_help(true)
+            .arg(
+                clap::Arg::new("action")
+                    .value_name("action")
+                    .required(true)
+                    .value_parser(["join", "extract"])
+                    .help("Join the volumes of a split archive into the archive or extract the archive."),
+            )
+            .arg(
+                clap::Arg::new("archive")
+                    .value_name("archive")
+                    .required(true)
+                    .help("The archive or any of the volumes of the archive."),
+            )
+            .arg(
+                clap::Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .value_name("path")
+                    .default_value("")
+                    .help("The path of the joined archive. Default is the path of the archive next to the volumes."),
+            )
+            .arg(
+                clap::Arg::new("dir")
+                    .short('d')
+                    .long("dir")
+                    .value_name("dir")
+                    .default_value("")
+                    .help("The directory to extract the archive to. Default is the current directory."),
+            )
+            .arg(
+                clap::Arg::new("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("verbose")
+                    .help("Set verbose level."),
+            );
+        ArchiveCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: false,
+                require_docker: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::commands::{ArchiveCommand, YCommand};
+    use crate::fs::Archiver;
+    use crate::helper::Helper;
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    #[test]
+    fn test_cmd_archive_extract() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch"
+        }
+        "#;
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let files: Vec<PathBuf> = vec![artifacts_dir.join("images/file1.txt")];
+        Helper::create_test_files(&files);
+        let archiver: Archiver =
+            Archiver::new(&artifacts_dir.join("images.tar.gz")).expect("Failed to setup archiver");
+        archiver
+            .add_files(&files, &artifacts_dir)
+            .expect("Failed to create archive");
+        archiver.split(100).expect("Failed to split archive");
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cmd: ArchiveCommand = ArchiveCommand::new();
+        let extract_dir: PathBuf = work_dir.join("extract");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec![
+                "yaab",
+                "archive",
+                "extract",
+                "images.tar.gz.part-00",
+                "-d",
+                extract_dir.to_str().unwrap(),
+            ]),
+        );
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute archive command");
+        assert_eq!(
+            std::fs::read(extract_dir.join("images/file1.txt")).expect("Failed to read file"),
+            std::fs::read(&files[0]).expect("Failed to read file")
+        );
+
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "archive", "join", "images.tar.gz"]),
+        );
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute archive command");
+        assert!(artifacts_dir.join("images.tar.gz").exists());
+    }
+}
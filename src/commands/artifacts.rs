@@ -0,0 +1,691 @@
+use indexmap::IndexMap;
+use std::path::PathBuf;
+
+use crate::cli::Cli;
+use crate::collector::{Collected, Collector, CollectorFactory};
+use crate::commands::{BError, BuildCommand, YBaseCommand, YCommand};
+use crate::data::WsContextData;
+use crate::fs::index::ARTIFACTS_INDEX;
+use crate::fs::{ArtifactsBuild, ArtifactsIndex, IndexChange, Retention};
+use crate::workspace::{Workspace, WsTaskHandler};
+
+static YCOMMAND: &str = "artifacts";
+static YCOMMAND_ABOUT: &str =
+    "List, verify and diff the artifacts of a build config or prune the builds in the artifacts directory.";
+pub struct ArtifactsCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for ArtifactsCommand {
+    fn get_config_name(&self, cli: &Cli) -> String {
+        if let Some(sub_matches) = cli.get_args().subcommand_matches(YCOMMAND) {
+            if sub_matches.contains_id("config") {
+                if let Some(value) = sub_matches.get_one::<String>("config") {
+                    return value.clone();
+                }
+            }
+        }
+
+        String::from("NA")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        self.cmd.require_docker
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let action: String = self.get_arg_str(cli, "action", YCOMMAND)?;
+
+        match action.as_str() {
+            "list" => self.list(cli, workspace),
+            "verify" => self.verify(cli, workspace),
+            "diff" => self.diff(cli, workspace),
+            "prune" => self.prune(cli, workspace),
+            _ => Err(BError::CliError(format!("Invalid action '{}'", action))),
+        }
+    }
+}
+
+impl ArtifactsCommand {
+    /*
+     * Resolve all the artifacts of the selected tasks against the current build
+     * directories without collecting them. When no task is specified all tasks
+     * that are enabled and whose condition is met are included. The context is
+     * setup the same way as for the build so the artifacts are resolved to the
+     * same place as if collected by the build.
+     */
+    fn resolve(
+        &self,
+        cli: &Cli,
+        workspace: &mut Workspace,
+    ) -> Result<Vec<(String, Collected)>, BError> {
+        let config: String = self.get_arg_str(cli, "config", YCOMMAND)?;
+        let version: String = self.get_arg_str(cli, "platform_version", YCOMMAND)?;
+        let build_id: String = self.get_arg_str(cli, "build_id", YCOMMAND)?;
+        let sha: String = self.get_arg_str(cli, "build_sha", YCOMMAND)?;
+        let variant: String = self.get_arg_str(cli, "variant", YCOMMAND)?;
+        let ctx: Vec<String> = self.get_arg_many(cli, "ctx", YCOMMAND)?;
+        let tasks: Vec<String> = self.get_arg_many(cli, "tasks", YCOMMAND)?;
+        let args_context: IndexMap<String, String> = self.setup_context(ctx);
+        let extra_ctx: IndexMap<String, String> =
+            BuildCommand::build_ctx(&version, &build_id, &sha, &variant);
+        let mut context: WsContextData = WsContextData::new(&args_context)?;
+        context.update(&extra_ctx);
+
+        if config == "NA" || !workspace.valid_config(config.as_str()) {
+            return Err(BError::CliError(format!(
+                "Unsupported build config '{}'",
+                config
+            )));
+        }
+        workspace.update_ctx(&context)?;
+
+        let mut selected: Vec<&WsTaskHandler> = vec![];
+        if tasks.len() == 1 && tasks[0] == "all" {
+            for (_name, task) in workspace.config().tasks() {
+                if !task.data().disabled() && task.data().condition() {
+                    selected.push(task);
+                }
+            }
+        } else {
+            for name in tasks.iter() {
+                selected.push(workspace.config().task(name)?);
+            }
+        }
+
+        let artifacts_dir: PathBuf = workspace.config().build_data().artifacts_dir();
+        let mut resolved: Vec<(String, Collected)> = vec![];
+        for task in selected {
+            for artifact in task.artifacts().iter() {
+                let collector: Box<dyn Collector> = CollectorFactory::create(artifact, Some(cli))?;
+                for c in collector.resolve(task.data().build_dir(), &artifacts_dir)? {
+                    resolved.push((task.data().name().to_string(), c));
+                }
+            }
+        }
+        Ok(resolved)
+    }
+
+    /*
+     * Generated artifacts like manifests have no source and are never missing.
+     */
+    fn missing(collected: &Collected) -> bool {
+        !collected.src.as_os_str().is_empty()
+            && !collected.src.exists()
+            && !collected.src.is_symlink()
+    }
+
+    fn list(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let resolved: Vec<(String, Collected)> = self.resolve(cli, workspace)?;
+        let artifacts_dir: PathBuf = workspace.config().build_data().artifacts_dir();
+        cli.stdout(format!("{:<15} {:<50} {}", "TASK", "ARTIFACT", "SOURCE"));
+        for (task, c) in resolved.iter() {
+            cli.stdout(format!(
+                "{:<15} {:<50} {}{}",
+                task,
+                c.dest
+                    .strip_prefix(&artifacts_dir)
+                    .unwrap_or(&c.dest)
+                    .display(),
+                if c.src.as_os_str().is_empty() {
+                    String::from("<generated>")
+                } else {
+                    c.src.display().to_string()
+                },
+                if Self::missing(c) { " [missing]" } else { "" }
+            ));
+        }
+        Ok(())
+    }
+
+    fn verify(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let resolved: Vec<(String, Collected)> = self.resolve(cli, workspace)?;
+        let missing: Vec<&(String, Collected)> =
+            resolved.iter().filter(|(_t, c)| Self::missing(c)).collect();
+        for (task, c) in missing.iter() {
+            cli.error(format!(
+                "Artifact '{}' for task '{}' is missing",
+                c.src.display(),
+                task
+            ));
+        }
+        if !missing.is_empty() {
+            return Err(BError::CollectorError(format!(
+                "{} of {} artifact(s) are missing",
+                missing.len(),
+                resolved.len()
+            )));
+        }
+        cli.info(format!("All {} artifact(s) are available", resolved.len()));
+        Ok(())
+    }
+
+    /*
+     * A relative directory is first looked up in the artifacts directory so
+     * two versioned builds can be compared using the path of the build in
+     * the artifacts directory.
+     */
+    fn index_dir(&self, workspace: &Workspace, dir: &str) -> Result<PathBuf, BError> {
+        let mut path: PathBuf = PathBuf::from(dir);
+        let in_artifacts: PathBuf = workspace.settings().artifacts_dir().join(dir);
+        if path.is_relative() && in_artifacts.is_dir() {
+            path = in_artifacts;
+        }
+        if !path.join(ARTIFACTS_INDEX).exists() {
+            return Err(BError::IOError(format!(
+                "No artifacts index found in '{}'",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+
+    fn diff(&self, cli: &Cli, workspace: &Workspace) -> Result<(), BError> {
+        let dirs: Vec<String> = self.get_arg_many(cli, "dirs", YCOMMAND)?;
+        if dirs.len() != 2 {
+            return Err(BError::CliError(String::from(
+                "The diff action requires two directories",
+            )));
+        }
+        let dir_a: PathBuf = self.index_dir(workspace, &dirs[0])?;
+        let dir_b: PathBuf = self.index_dir(workspace, &dirs[1])?;
+        let index_a: ArtifactsIndex = ArtifactsIndex::load(&dir_a, "")?;
+        let index_b: ArtifactsIndex = ArtifactsIndex::load(&dir_b, "")?;
+
+        let changes: Vec<IndexChange> = index_a.diff(&index_b);
+        for change in changes.iter() {
+            cli.stdout(match change {
+                IndexChange::Added(e) => format!("+ {} ({} bytes)", e.path, e.size),
+                IndexChange::Removed(e) => format!("- {} ({} bytes)", e.path, e.size),
+                IndexChange::Changed(a, b) => {
+                    format!("~ {} ({} => {} bytes)", a.path, a.size, b.size)
+                }
+            });
+        }
+        cli.info(format!(
+            "{} artifact(s) differ between '{}' and '{}'",
+            changes.len(),
+            dir_a.display(),
+            dir_b.display()
+        ));
+        Ok(())
+    }
+
+    /*
+     * Remove the builds from the artifacts directory according to the retention
+     * policy of the workspace. Any retention option on the command line takes
+     * precedence over the one in the workspace settings.
+     */
+    fn prune(&self, cli: &Cli, workspace: &Workspace) -> Result<(), BError> {
+        let dry_run: bool = self.get_arg_flag(cli, "dry-run", YCOMMAND)?;
+        let artifacts_dir: PathBuf = workspace.settings().artifacts_dir();
+        let mut retention: Retention = workspace.settings().artifacts_retention().clone();
+        let keep: String = self.get_arg_str(cli, "keep", YCOMMAND)?;
+        let max_age: String = self.get_arg_str(cli, "max-age", YCOMMAND)?;
+        let max_size: String = self.get_arg_str(cli, "max-size", YCOMMAND)?;
+        if !keep.is_empty() {
+            retention.keep = keep;
+        }
+        if !max_age.is_empty() {
+            retention.max_age = max_age;
+        }
+        if !max_size.is_empty() {
+            retention.max_size = max_size;
+        }
+
+        if retention.is_empty() {
+            cli.info(String::from(
+                "No retention policy defined, nothing to prune",
+            ));
+            return Ok(());
+        }
+
+        let pruned: Vec<ArtifactsBuild> = retention.prune(&artifacts_dir, dry_run)?;
+        for build in pruned.iter() {
+            cli.info(format!(
+                "{} '{}' ({} MB)",
+                if dry_run { "Would remove" } else { "Removed" },
+                build.path.display(),
+                build.size / (1024 * 1024)
+            ));
+        }
+        cli.info(format!(
+            "{} build(s) pruned from '{}'",
+            pruned.len(),
+            artifacts_dir.display()
+        ));
+        Ok(())
+    }
+
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND)
+            .about(YCOMMAND_ABOUT)
+            .arg_required_else_help(true)
+            .arg(
+                clap::Arg::new("action")
+                    .value_name("action")
+                    .required(true)
+                    .value_parser(["list", "verify", "diff", "prune"])
+                    .help("List or verify the artifacts of a build config, diff the artifacts of two builds or remove builds according to the retention policy."),
+            )
+            .arg(
+                clap::Arg::new("dirs")
+                    .value_name("dir")
+                    .num_args(0..=2)
+                    .help("The two artifacts directories to diff."),
+            )
+            .arg(
+                clap::Arg::new("config")
+                    .short('c')
+                    .long("config")
+                    .help("The build config to list or verify the artifacts for")
+                    .value_name("name")
+                    .default_value("NA"),
+            )
+            .arg(
+                clap::Arg::new("tasks")
+                    .short('t')
+                    .long("tasks")
+                    .value_name("tasks")
+                    .default_value("all")
+                    .value_delimiter(',')
+                    .help("The task(s) to list or verify the artifacts for."),
+            )
+            .arg(
+                clap::Arg::new("platform_version")
+                    .short('r')
+                    .long("platform-version")
+                    .value_name("x.y.z")
+                    .default_value("0.0.0")
+                    .help("Platform version number for the build. Will be available as context variable YAAB_PLATFORM_VERSION"),
+            )
+            .arg(
+                clap::Arg::new("build_sha")
+                    .short('s')
+                    .long("build-sha")
+                    .value_name("sha")
+                    .default_value("dev")
+                    .help("Sha for the current build. Will be available as a context variable YAAB_BUILD_SHA"),
+            )
+            .arg(
+                clap::Arg::new("variant")
+                    .short('a')
+                    .long("variant")
+                    .value_name("variant")
+                    .default_value("userdebug")
+                    .value_parser(["user", "userdebug", "eng"])
+                    .help("Specify the variant of the build it can be one of user, userdebug, eng. Will be available as a context variable YAAB_BUILD_VARIANT"),
+            )
+            .arg(
+                clap::Arg::new("build_id")
+                    .short('n')
+                    .long("build-id")
+                    .value_name("nbr")
+                    .default_value("0")
+                    .help("Build id number can be used if x.y.z is not enough for some reason and will be part of YAAB_PLATFORM_RELEASE x.y.z-w"),
+            )
+            .arg(
+                clap::Arg::new("ctx")
+                    .action(clap::ArgAction::Append)
+                    .short('x')
+                    .long("context")
+                    .value_name("KEY=VALUE")
+                    .help("Adding variable to the context. Any KEY that already exists in the context will be overwriten."),
+            )
+            .arg(
+                clap::Arg::new("dry-run")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("dry-run")
+                    .help("List the builds that would be removed without removing them."),
+            )
+            .arg(
+                clap::Arg::new("keep")
+                    .long("keep")
+                    .value_name("count")
+                    .default_value("")
+                    .help("Keep the last number of builds."),
+            )
+            .arg(
+                clap::Arg::new("max-age")
+                    .long("max-age")
+                    .value_name("age")
+                    .default_value("")
+                    .help("Remove builds older than the age in days (d), hours (h) or minutes (m)."),
+            )
+            .arg(
+                clap::Arg::new("max-size")
+                    .long("max-size")
+                    .value_name("size")
+                    .default_value("")
+                    .help("Remove the oldest builds until the total size is below the size in bytes or K, M, G or T."),
+            )
+            .arg(
+                clap::Arg::new("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("verbose")
+                    .help("Set verbose level."),
+            );
+        ArtifactsCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: false,
+                require_docker: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::collector::Collected;
+    use crate::commands::{ArtifactsCommand, YCommand};
+    use crate::fs::ArtifactsIndex;
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    #[test]
+    fn test_cmd_artifacts_prune() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "workspace": {
+                "artifactslayout": "$#[YAAB_PLATFORM_RELEASE]",
+                "retention": {
+                    "keep": "1"
+                }
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch"
+        }
+        "#;
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let now: SystemTime = SystemTime::now();
+        for (i, build) in ["1.0.0", "1.0.1"].iter().enumerate() {
+            std::fs::create_dir_all(artifacts_dir.join(build)).expect("Failed to create dir");
+            std::fs::File::create(artifacts_dir.join(build).join("artifacts.json"))
+                .expect("Failed to write index")
+                .set_modified(now - Duration::from_secs(100 - i as u64))
+                .expect("Failed to set mtime");
+        }
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cmd: ArtifactsCommand = ArtifactsCommand::new();
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "artifacts", "prune", "--dry-run"]),
+        );
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute artifacts command");
+        assert!(artifacts_dir.join("1.0.0").exists());
+
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "artifacts", "prune"]),
+        );
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute artifacts command");
+        assert!(!artifacts_dir.join("1.0.0").exists());
+        assert!(artifacts_dir.join("1.0.1").exists());
+    }
+
+    #[test]
+    fn test_cmd_artifacts_verify() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "tasks": {
+                "task1": {
+                    "index": "1",
+                    "name": "task1",
+                    "builddir": "out",
+                    "artifacts": [
+                        {
+                            "source": "boot.img"
+                        },
+                        {
+                            "type": "conditional",
+                            "condition": "true",
+                            "artifacts": [
+                                {
+                                    "source": "system.img"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "manifest",
+                            "name": "manifest.json",
+                            "content": {
+                                "key": "value"
+                            }
+                        }
+                    ]
+                }
+            }
+        }
+        "#;
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        std::fs::create_dir_all(work_dir.join("out")).expect("Failed to create dir");
+        std::fs::write(work_dir.join("out/boot.img"), "boot").expect("Failed to write file");
+        let cmd: ArtifactsCommand = ArtifactsCommand::new();
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "artifacts", "verify", "-c", "default"]),
+        );
+        match cmd.execute(&cli, &mut workspace) {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), "1 of 3 artifact(s) are missing");
+            }
+        }
+
+        std::fs::write(work_dir.join("out/system.img"), "system").expect("Failed to write file");
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute artifacts command");
+    }
+
+    #[test]
+    fn test_cmd_artifacts_list_build_ctx() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "workspace": {
+                "artifactslayout": "$#[YAAB_PLATFORM_RELEASE]/$#[YAAB_BUILD_VARIANT]"
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "tasks": {
+                "task1": {
+                    "index": "1",
+                    "name": "task1",
+                    "builddir": "out",
+                    "artifacts": [
+                        {
+                            "source": "boot.img",
+                            "dest": "$#[YAAB_BUILD_SHA]/boot.img"
+                        }
+                    ]
+                }
+            }
+        }
+        "#;
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cmd: ArtifactsCommand = ArtifactsCommand::new();
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec![
+                "yaab",
+                "artifacts",
+                "list",
+                "-c",
+                "default",
+                "-r",
+                "1.0.0",
+                "-n",
+                "2",
+                "-s",
+                "abc",
+                "-a",
+                "user",
+            ]),
+        );
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute artifacts command");
+        assert_eq!(
+            workspace.config().build_data().artifacts_dir(),
+            work_dir.join("artifacts/1.0.0-2/user")
+        );
+        let resolved: Vec<(String, Collected)> = cmd
+            .resolve(&cli, &mut workspace)
+            .expect("Failed to resolve");
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(
+            resolved[0].1.dest,
+            work_dir.join("artifacts/1.0.0-2/user/abc/boot.img")
+        );
+    }
+
+    #[test]
+    fn test_cmd_artifacts_diff() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch"
+        }
+        "#;
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        for build in ["1.0.0", "1.0.1"] {
+            let build_dir: PathBuf = artifacts_dir.join(build);
+            std::fs::create_dir_all(&build_dir).expect("Failed to create dir");
+            std::fs::write(build_dir.join("system.img"), build).expect("Failed to write file");
+            let mut index: ArtifactsIndex = ArtifactsIndex::new(&build_dir, "default");
+            index
+                .add(&build_dir.join("system.img"), "task1", "file")
+                .expect("Failed to add entry");
+            index.write(false).expect("Failed to write index");
+        }
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let mut mocked_logger: MockLogger = MockLogger::new();
+        mocked_logger
+            .expect_stdout()
+            .with(mockall::predicate::eq(String::from(
+                "~ system.img (5 => 5 bytes)",
+            )))
+            .once()
+            .returning(|_x| ());
+        mocked_logger.expect_info().returning(|_x| ());
+        let cli: Cli = Cli::new(
+            Box::new(mocked_logger),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "artifacts", "diff", "1.0.0", "1.0.1"]),
+        );
+        let cmd: ArtifactsCommand = ArtifactsCommand::new();
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute artifacts command");
+    }
+}
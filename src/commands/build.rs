@@ -3,9 +3,10 @@ use std::collections::HashMap;
 
 use crate::cli::Cli;
 use crate::commands::{YBaseCommand, YCommand};
-use crate::data::WsContextData;
+use crate::data::{WsBuildData, WsContextData};
 use crate::error::BError;
-use crate::executers::Docker;
+use crate::executers::inside_container;
+use crate::fs::ArtifactsIndex;
 use crate::workspace::{Workspace, WsTaskHandler};
 
 static YCOMMAND: &str = "build";
@@ -77,7 +78,7 @@ impl YCommand for BuildCommand {
          */
         if !workspace.settings().docker_disabled()
             && self.is_docker_required()
-            && !Docker::inside_docker()
+            && !inside_container()
         {
             return self.bootstrap(&cli.get_cmd_line(), cli, workspace, &volumes, interactive);
         }
@@ -91,26 +92,8 @@ impl YCommand for BuildCommand {
         let env_variables: HashMap<String, String> = self.setup_env(env);
         let args_context: IndexMap<String, String> = self.setup_context(ctx);
 
-        let mut extra_ctx: IndexMap<String, String> = indexmap! {
-            "YAAB_PLATFORM_VERSION".to_string() => version.clone(),
-            "YAAB_BUILD_ID".to_string() => build_id.clone(),
-            "YAAB_BUILD_SHA".to_string() => sha,
-            "YAAB_RELEASE_BUILD".to_string() => "0".to_string(),
-            "YAAB_BUILD_VARIANT".to_string() => variant.clone(),
-            "YAAB_PLATFORM_RELEASE".to_string() => format!("{}-{}", version, build_id),
-        };
-
-        if variant == "user" {
-            /*
-             * Build commands defined in the build config needs to
-             * know if it is release build or not running by including
-             * the YAAB_BUILD_VARIANT to the context we can expose this to
-             * the build commands. We are keeping YAAB_RELEASE_BUILD for
-             * backwards compatibility but should be replaced with BUILD_VARIANT
-             */
-            extra_ctx.insert("YAAB_BUILD_VARIANT".to_string(), "user".to_string());
-            extra_ctx.insert("YAAB_RELEASE_BUILD".to_string(), "1".to_string());
-        }
+        let extra_ctx: IndexMap<String, String> =
+            Self::build_ctx(&version, &build_id, &sha, &variant);
 
         // We need to add the extra context variables to the list of bitbake variables
         // so they can be added to the bitbake local.conf file if used as env variables
@@ -132,6 +115,19 @@ impl YCommand for BuildCommand {
             });
         }
 
+        if !dry_run {
+            /*
+             * Every build is starting with a fresh artifacts index so that
+             * the index is only listing the artifacts produced by this build
+             * and not artifacts left in the artifacts directory by an earlier
+             * build.
+             */
+            let build_data: &WsBuildData = workspace.config().build_data();
+            build_data.verify_artifacts_dir()?;
+            ArtifactsIndex::new(&build_data.artifacts_dir(), build_data.name())
+                .write(build_data.settings().artifacts_sha256sums())?;
+        }
+
         if tasks.len() > 1 {
             // More then one task was specified on the command line
             for t_name in tasks {
@@ -181,6 +177,39 @@ impl YCommand for BuildCommand {
 }
 
 impl BuildCommand {
+    /*
+     * The context variables setup from the build args. Shared with any other
+     * command that should expand the build config the same way as the build.
+     */
+    pub fn build_ctx(
+        version: &str,
+        build_id: &str,
+        sha: &str,
+        variant: &str,
+    ) -> IndexMap<String, String> {
+        let mut extra_ctx: IndexMap<String, String> = indexmap! {
+            "YAAB_PLATFORM_VERSION".to_string() => version.to_string(),
+            "YAAB_BUILD_ID".to_string() => build_id.to_string(),
+            "YAAB_BUILD_SHA".to_string() => sha.to_string(),
+            "YAAB_RELEASE_BUILD".to_string() => "0".to_string(),
+            "YAAB_BUILD_VARIANT".to_string() => variant.to_string(),
+            "YAAB_PLATFORM_RELEASE".to_string() => format!("{}-{}", version, build_id),
+        };
+
+        if variant == "user" {
+            /*
+             * Build commands defined in the build config needs to
+             * know if it is release build or not running by including
+             * the YAAB_BUILD_VARIANT to the context we can expose this to
+             * the build commands. We are keeping YAAB_RELEASE_BUILD for
+             * backwards compatibility but should be replaced with BUILD_VARIANT
+             */
+            extra_ctx.insert("YAAB_BUILD_VARIANT".to_string(), "user".to_string());
+            extra_ctx.insert("YAAB_RELEASE_BUILD".to_string(), "1".to_string());
+        }
+        extra_ctx
+    }
+
     fn setup_env(&self, env: Vec<String>) -> HashMap<String, String> {
         let variables: HashMap<String, String> = env
             .iter()
@@ -5,7 +5,7 @@ use crate::cli::Cli;
 use crate::commands::{YBaseCommand, YCommand};
 use crate::data::WsContextData;
 use crate::error::BError;
-use crate::executers::Docker;
+use crate::executers::inside_container;
 use crate::workspace::{Workspace, WsTaskHandler};
 
 static YCOMMAND: &str = "clean";
@@ -62,7 +62,7 @@ impl YCommand for CleanCommand {
          */
         if !workspace.settings().docker_disabled()
             && self.is_docker_required()
-            && !Docker::inside_docker()
+            && !inside_container()
         {
             return self.bootstrap(
                 &cli.get_cmd_line(),
@@ -0,0 +1,337 @@
+use indexmap::IndexMap;
+
+use crate::cli::Cli;
+use crate::commands::{BuildCommand, YBaseCommand, YCommand};
+use crate::data::WsContextData;
+use crate::error::BError;
+use crate::executers::inside_container;
+use crate::workspace::{Workspace, WsTaskHandler};
+
+static YCOMMAND: &str = "collect";
+static YCOMMAND_ABOUT: &str =
+    "Collect the artifacts of a build config from the existing build directories without building.";
+
+pub struct CollectCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for CollectCommand {
+    fn get_config_name(&self, cli: &Cli) -> String {
+        if let Some(sub_matches) = cli.get_args().subcommand_matches(YCOMMAND) {
+            if sub_matches.contains_id("config") {
+                if let Some(value) = sub_matches.get_one::<String>("config") {
+                    return value.clone();
+                }
+            }
+        }
+
+        String::from("default")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        true
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let config: String = self.get_arg_str(cli, "config", YCOMMAND)?;
+        let version: String = self.get_arg_str(cli, "platform_version", YCOMMAND)?;
+        let build_id: String = self.get_arg_str(cli, "build_id", YCOMMAND)?;
+        let sha: String = self.get_arg_str(cli, "build_sha", YCOMMAND)?;
+        let ctx: Vec<String> = self.get_arg_many(cli, "ctx", YCOMMAND)?;
+        let volumes: Vec<String> = self.get_arg_many(cli, "volume", YCOMMAND)?;
+        let tasks: Vec<String> = self.get_arg_many(cli, "tasks", YCOMMAND)?;
+        let variant: String = self.get_arg_str(cli, "variant", YCOMMAND)?;
+        let verbose: bool = self.get_arg_flag(cli, "verbose", YCOMMAND)?;
+
+        if !workspace.valid_config(config.as_str()) {
+            return Err(BError::CliError(format!(
+                "Unsupported build config '{}'",
+                config
+            )));
+        }
+
+        /*
+         * The artifacts are collected from the build directories so when the build
+         * is done inside docker the collect should be done inside docker as well.
+         */
+        if !workspace.settings().docker_disabled()
+            && self.is_docker_required()
+            && !inside_container()
+        {
+            return self.bootstrap(&cli.get_cmd_line(), cli, workspace, &volumes, false);
+        }
+
+        /*
+         * The context is setup the same way as for the build so the artifacts
+         * are expanded and collected to the same place as if collected by the
+         * build.
+         */
+        let args_context: IndexMap<String, String> = self.setup_context(ctx);
+        let extra_ctx: IndexMap<String, String> =
+            BuildCommand::build_ctx(&version, &build_id, &sha, &variant);
+        let mut context: WsContextData = WsContextData::new(&args_context)?;
+        context.update(&extra_ctx);
+        workspace.update_ctx(&context)?;
+
+        if verbose {
+            let variables: IndexMap<String, String> = workspace.context()?;
+            cli.debug(format!("Context varibles for build config '{}':", config));
+            variables.iter().for_each(|(key, value)| {
+                cli.stdout(format!("{}={}", key.to_ascii_uppercase(), value));
+            });
+        }
+
+        if tasks.len() == 1 && tasks[0] == "all" {
+            for (_t_name, task) in workspace.config().tasks() {
+                self.collect_task(cli, workspace, task, false)?;
+            }
+        } else {
+            for t_name in tasks.iter() {
+                let task: &WsTaskHandler = workspace.config().task(t_name)?;
+                self.collect_task(cli, workspace, task, true)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CollectCommand {
+    fn collect_task(
+        &self,
+        cli: &Cli,
+        workspace: &Workspace,
+        task: &WsTaskHandler,
+        force: bool,
+    ) -> Result<(), BError> {
+        if !force && task.data().disabled() {
+            cli.info(format!("Task '{}' disabled, skipping", task.data().name()));
+            return Ok(());
+        }
+
+        if !force && !task.data().condition() {
+            cli.info(format!(
+                "Task condition for '{}' is not met, skipping",
+                task.data().name()
+            ));
+            return Ok(());
+        }
+
+        if !task.data().build_dir().exists() {
+            return Err(BError::CollectorError(format!(
+                "Build directory '{}' for task '{}' does not exist, run the build first",
+                task.data().build_dir().display(),
+                task.data().name()
+            )));
+        }
+
+        task.collect(cli, workspace.config().build_data())?;
+        Ok(())
+    }
+
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND)
+            .about(YCOMMAND_ABOUT)
+            .arg_required_else_help(true)
+            .arg(
+                clap::Arg::new("config")
+                    .short('c')
+                    .long("config")
+                    .help("The build config defining the artifacts to collect")
+                    .value_name("name")
+                    .required(true),
+            )
+            .arg(
+                clap::Arg::new("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("verbose")
+                    .help("Set verbose level."),
+            )
+            .arg(
+                clap::Arg::new("tasks")
+                    .short('t')
+                    .long("tasks")
+                    .value_name("tasks")
+                    .default_value("all")
+                    .value_delimiter(',')
+                    .help("The task(s) to collect the artifacts for."),
+            )
+            .arg(
+                clap::Arg::new("volume")
+                    .action(clap::ArgAction::Append)
+                    .short('v')
+                    .long("docker-volume")
+                    .value_name("path:path")
+                    .help("Docker volume to mount bind when boot strapping into docker."),
+            )
+            .arg(
+                clap::Arg::new("platform_version")
+                    .short('r')
+                    .long("platform-version")
+                    .value_name("x.y.z")
+                    .default_value("0.0.0")
+                    .help("Platform version number for the build. Will be available as context variable YAAB_PLATFORM_VERSION"),
+            )
+            .arg(
+                clap::Arg::new("build_sha")
+                    .short('s')
+                    .long("build-sha")
+                    .value_name("sha")
+                    .default_value("dev")
+                    .help("Sha for the current build. Will be available as a context variable YAAB_BUILD_SHA"),
+            )
+            .arg(
+                clap::Arg::new("variant")
+                    .short('a')
+                    .long("variant")
+                    .value_name("variant")
+                    .default_value("userdebug")
+                    .value_parser(["user", "userdebug", "eng"])
+                    .help("Specify the variant of the build it can be one of user, userdebug, eng. Will be available as a context variable YAAB_BUILD_VARIANT"),
+            )
+            .arg(
+                clap::Arg::new("build_id")
+                    .short('n')
+                    .long("build-id")
+                    .value_name("nbr")
+                    .default_value("0")
+                    .help("Build id number can be used if x.y.z is not enough for some reason and will be part of YAAB_PLATFORM_RELEASE x.y.z-w"),
+            )
+            .arg(
+                clap::Arg::new("ctx")
+                    .action(clap::ArgAction::Append)
+                    .short('x')
+                    .long("context")
+                    .value_name("KEY=VALUE")
+                    .help("Adding variable to the context. Any KEY that already exists in the context will be overwriten."),
+            );
+        CollectCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: false,
+                require_docker: true,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::commands::{CollectCommand, YCommand};
+    use crate::error::BError;
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    fn helper_test_collect_subcommand(
+        work_dir: &PathBuf,
+        cmd_line: Vec<&str>,
+    ) -> Result<(), BError> {
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "docker": {
+                "disabled": "true"
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "tasks": {
+                "task1": {
+                    "index": "1",
+                    "name": "task1",
+                    "builddir": "out",
+                    "artifacts": [
+                        {
+                            "source": "boot.img",
+                            "dest": "$#[YAAB_PLATFORM_RELEASE]/boot.img"
+                        }
+                    ]
+                },
+                "task2": {
+                    "index": "2",
+                    "name": "task2",
+                    "builddir": "out2",
+                    "disabled": "true",
+                    "artifacts": [
+                        {
+                            "source": "system.img"
+                        }
+                    ]
+                }
+            }
+        }
+        "#;
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)?;
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)?;
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))?;
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(cmd_line),
+        );
+        let cmd: CollectCommand = CollectCommand::new();
+        cmd.execute(&cli, &mut workspace)
+    }
+
+    #[test]
+    fn test_cmd_collect() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        std::fs::create_dir_all(work_dir.join("out")).expect("Failed to create dir");
+        std::fs::write(work_dir.join("out/boot.img"), "boot").expect("Failed to write file");
+        helper_test_collect_subcommand(
+            &work_dir,
+            vec!["yaab", "collect", "-c", "default", "-r", "1.0.0", "-n", "2"],
+        )
+        .expect("Failed to execute collect command");
+        assert!(work_dir.join("artifacts/1.0.0-2/boot.img").exists());
+        assert!(work_dir.join("artifacts/artifacts.json").exists());
+    }
+
+    #[test]
+    fn test_cmd_collect_missing_build_dir() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let result: Result<(), BError> = helper_test_collect_subcommand(
+            &work_dir,
+            vec!["yaab", "collect", "-c", "default", "-t", "task2"],
+        );
+        match result {
+            Err(BError::CollectorError(msg)) => {
+                assert_eq!(
+                    msg,
+                    format!(
+                        "Build directory '{}' for task 'task2' does not exist, run the build first",
+                        work_dir.join("out2").display()
+                    )
+                );
+            }
+            _ => panic!("Expected a collector error"),
+        }
+    }
+}
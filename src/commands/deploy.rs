@@ -3,7 +3,10 @@ use std::collections::HashMap;
 
 use crate::cli::Cli;
 use crate::commands::{BError, YBaseCommand, YCommand};
-use crate::data::{WsContextData, CTX_KEY_DEVICE, CTX_KEY_IMAGE};
+use crate::data::{
+    DType, WsContextData, WsDeployData, WsDeviceData, CTX_KEY_DEPLOY_MODE, CTX_KEY_DEVICE,
+    CTX_KEY_IMAGE, CTX_KEY_PRODUCT_NAME,
+};
 use crate::workspace::{Workspace, WsCustomSubCmdHandler};
 
 static YCOMMAND: &str = "deploy";
@@ -46,7 +49,26 @@ impl YCommand for DeployCommand {
         let args_context: IndexMap<String, String> = self.setup_context(ctx);
         let mut context: WsContextData = WsContextData::new(&args_context)?;
 
-        if device != String::from("NA") {
+        if !workspace.valid_config(config.as_str()) {
+            return Err(BError::CliError(format!(
+                "Unsupported build config '{}'",
+                config
+            )));
+        }
+
+        /*
+         * The device is either the name of a device in the device inventory
+         * of the workspace config or the serial, IP or device file of the
+         * device to deploy to.
+         */
+        let inventory: Option<WsDeviceData> = workspace.settings().device(&device).cloned();
+        if let Some(inventory_device) = &inventory {
+            let dtype: DType = self.deploy_mode(workspace, inventory_device)?;
+            context.update(&indexmap! {
+                CTX_KEY_DEVICE.to_string() => inventory_device.target(&dtype),
+                CTX_KEY_DEPLOY_MODE.to_string() => inventory_device.mode().clone(),
+            });
+        } else if device != "NA" {
             context.update(&indexmap! {
                 CTX_KEY_DEVICE.to_string() => device,
             });
@@ -58,25 +80,91 @@ impl YCommand for DeployCommand {
             });
         }
 
-        if !workspace.valid_config(config.as_str()) {
-            return Err(BError::CliError(format!(
-                "Unsupported build config '{}'",
-                config
-            )));
-        }
-
         /*
          * We will update the context with the variables from the cli
          * and then expand the context variables in the config
          */
         workspace.update_ctx(&context)?;
 
+        if let Some(inventory_device) = &inventory {
+            self.verify_product(workspace, inventory_device)?;
+            self.connect(cli, workspace, inventory_device)?;
+        }
+
         let deploy: &WsCustomSubCmdHandler = workspace.config().deploy();
         deploy.run(cli, &cli.env(), false, self.cmd.interactive)
     }
 }
 
 impl DeployCommand {
+    /*
+     * The deploy mode is the type of the deploy node if set otherwise the
+     * default deploy mode of the device.
+     */
+    fn deploy_mode(&self, workspace: &Workspace, device: &WsDeviceData) -> Result<DType, BError> {
+        let mut mode: &String = device.mode();
+        if let Some(deploy) = workspace.config().deploy().deploy() {
+            if !deploy.mode().is_empty() {
+                mode = deploy.mode();
+            }
+        }
+        match mode.is_empty() {
+            true => Ok(DType::Cmd),
+            false => WsDeployData::parse_dtype(mode),
+        }
+    }
+
+    /*
+     * Refuse to deploy the build of one product to a device running
+     * another product since that would most likely brick the device.
+     */
+    fn verify_product(&self, workspace: &Workspace, device: &WsDeviceData) -> Result<(), BError> {
+        let product: String = workspace
+            .config()
+            .build_data()
+            .context()
+            .get_ctx_value(CTX_KEY_PRODUCT_NAME);
+        if !device.product().is_empty() && device.product() != &product {
+            return Err(BError::DeployError(format!(
+                "Device '{}' is a '{}' device but the build config is for product '{}'",
+                device.name(),
+                device.product(),
+                product
+            )));
+        }
+        Ok(())
+    }
+
+    /*
+     * A device using the tcp transport has to be connected before adb can
+     * use it.
+     */
+    fn connect(
+        &self,
+        cli: &Cli,
+        workspace: &Workspace,
+        device: &WsDeviceData,
+    ) -> Result<(), BError> {
+        let adb: bool = workspace
+            .config()
+            .deploy()
+            .deploy()
+            .map(|d| d.dtype() == &DType::Adb)
+            .unwrap_or(false);
+        if adb && device.transport() == "tcp" {
+            cli.check_call(
+                &vec![
+                    String::from("adb"),
+                    String::from("connect"),
+                    device.serial().clone(),
+                ],
+                &cli.env(),
+                true,
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn new() -> Self {
         let subcmd: clap::Command = clap::Command::new(YCOMMAND)
         .about(YCOMMAND_ABOUT)
@@ -109,7 +197,7 @@ impl DeployCommand {
                 .long("device")
                 .value_name("device")
                 .default_value("NA")
-                .help("The device can either be an IP or a device file like /dev/ttyUSB. Will be exposed as a context variable $#[DEVICE]"),
+                .help("The device can either be the name of a device in the device inventory, an IP or a device file like /dev/ttyUSB. Will be exposed as a context variable $#[DEVICE]"),
         )
         .arg(
             clap::Arg::new("image")
@@ -141,6 +229,7 @@ mod tests {
 
     use crate::cli::*;
     use crate::commands::{DeployCommand, YCommand};
+    use crate::data::DType;
     use crate::error::BError;
     use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
 
@@ -630,4 +719,153 @@ mod tests {
         let cmd: DeployCommand = DeployCommand::new();
         let _result: Result<(), BError> = cmd.execute(&cli, &mut workspace);
     }
+
+    #[test]
+    fn test_cmd_deploy_inventory_device() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "8A3X0N1Q2",
+                    "product": "blueline"
+                }
+            ]
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "product": "blueline",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "deploy": {
+                "cmd": "$#[YAAB_SCRIPTS_DIR]/script.sh $#[YAAB_DEVICE]"
+            }
+        }
+        "#;
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system
+            .expect_check_call()
+            .with(mockall::predicate::eq(CallParams {
+                cmd_line: [
+                    &format!("{}/scripts/script.sh", work_dir.display()),
+                    "8A3X0N1Q2",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+                env: HashMap::new(),
+                shell: true,
+            }))
+            .once()
+            .returning(|_x| Ok(()));
+        mocked_system.expect_env().returning(HashMap::new);
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec![
+                "yaab",
+                "deploy",
+                "-c",
+                "default",
+                "--device",
+                "lab-pixel-3",
+            ]),
+        );
+        let cmd: DeployCommand = DeployCommand::new();
+        cmd.execute(&cli, &mut workspace).expect("Failed to deploy");
+    }
+
+    #[test]
+    fn test_cmd_deploy_inventory_device_product_mismatch() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "192.168.1.10:5555",
+                    "product": "blueline",
+                    "transport": "tcp",
+                    "deploy": "fastboot"
+                }
+            ]
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "product": "crosshatch",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "deploy": {
+                "images": [
+                    "boot=boot.img"
+                ]
+            }
+        }
+        "#;
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_check_call().never();
+        mocked_system.expect_env().returning(HashMap::new);
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec![
+                "yaab",
+                "deploy",
+                "-c",
+                "default",
+                "--device",
+                "lab-pixel-3",
+            ]),
+        );
+        let cmd: DeployCommand = DeployCommand::new();
+        match cmd.execute(&cli, &mut workspace) {
+            Ok(_) => panic!("We should have recived an error because the product is not matching"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Device 'lab-pixel-3' is a 'blueline' device but the build config is for product 'crosshatch'"
+            ),
+        }
+        let deploy = workspace.config().deploy().deploy().unwrap();
+        assert_eq!(deploy.dtype(), &DType::Fastboot);
+        assert_eq!(deploy.device(), "tcp:192.168.1.10:5555");
+    }
 }
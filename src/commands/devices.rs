@@ -0,0 +1,328 @@
+use indexmap::IndexMap;
+use std::collections::HashMap;
+
+use crate::cli::Cli;
+use crate::commands::{BError, YBaseCommand, YCommand};
+use crate::data::{DType, WsDeviceData};
+use crate::workspace::Workspace;
+
+static YCOMMAND: &str = "devices";
+static YCOMMAND_ABOUT: &str =
+    "List the devices in the device inventory and if they are attached using adb or fastboot.";
+pub struct DevicesCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for DevicesCommand {
+    fn get_config_name(&self, _cli: &Cli) -> String {
+        String::from("default")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        self.cmd.require_docker
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let devices: &Vec<WsDeviceData> = workspace.settings().devices();
+        if devices.is_empty() {
+            cli.info(String::from(
+                "No devices defined, add them to the 'devices' node of the workspace config",
+            ));
+            return Ok(());
+        }
+
+        let env: HashMap<String, String> = cli.env();
+        let adb: IndexMap<String, String> = self.attached(cli, "adb", &env)?;
+        let fastboot: IndexMap<String, String> = self.attached(cli, "fastboot", &env)?;
+
+        cli.stdout(format!(
+            "{:<20} {:<25} {:<15} {:<10} {:<10} {}",
+            "NAME", "SERIAL", "PRODUCT", "TRANSPORT", "DEPLOY", "STATUS"
+        ));
+        for device in devices.iter() {
+            let status: String = match adb.get(device.serial()) {
+                Some(state) if state == "device" => String::from("adb"),
+                Some(state) => format!("adb ({})", state),
+                None => match fastboot.contains_key(&device.target(&DType::Fastboot)) {
+                    true => String::from("fastboot"),
+                    false => String::from("not attached"),
+                },
+            };
+            cli.stdout(format!(
+                "{:<20} {:<25} {:<15} {:<10} {:<10} {}",
+                device.name(),
+                device.serial(),
+                device.product(),
+                device.transport(),
+                device.mode(),
+                status
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl DevicesCommand {
+    /*
+     * The serial and state of every device listed by 'adb devices' or
+     * 'fastboot devices'. If the tool is not available or fails a warning is
+     * logged and no devices are considered attached using that tool.
+     */
+    fn attached(
+        &self,
+        cli: &Cli,
+        tool: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<IndexMap<String, String>, BError> {
+        let mut attached: IndexMap<String, String> = IndexMap::new();
+        let output: String =
+            match cli.check_output(&[tool.to_string(), String::from("devices")], env, true) {
+                Ok(output) => output,
+                Err(BError::Interrupted) => return Err(BError::Interrupted),
+                Err(err) => {
+                    cli.warn(format!(
+                        "Failed to list the devices attached using '{}': {}",
+                        tool, err
+                    ));
+                    return Ok(attached);
+                }
+            };
+
+        for line in output.lines() {
+            if let Some((serial, state)) = line.split_once('\t') {
+                attached.insert(serial.trim().to_string(), state.trim().to_string());
+            }
+        }
+        Ok(attached)
+    }
+
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND).about(YCOMMAND_ABOUT).arg(
+            clap::Arg::new("verbose")
+                .action(clap::ArgAction::SetTrue)
+                .long("verbose")
+                .help("Set verbose level."),
+        );
+        DevicesCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: true,
+                require_docker: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::commands::{DevicesCommand, YCommand};
+    use crate::error::BError;
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    fn helper_devices_call(
+        mocked_system: &mut MockSystem,
+        tool: &'static str,
+        result: Result<&'static str, &'static str>,
+    ) {
+        mocked_system
+            .expect_check_output()
+            .with(mockall::predicate::eq(CallParams {
+                cmd_line: vec![tool.to_string(), String::from("devices")],
+                env: HashMap::new(),
+                shell: true,
+            }))
+            .once()
+            .returning(move |_x| match result {
+                Ok(output) => Ok(output.to_string()),
+                Err(error) => Err(BError::CliError(error.to_string())),
+            });
+    }
+
+    #[test]
+    fn test_cmd_devices() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "8A3X0N1Q2",
+                    "product": "blueline",
+                    "deploy": "adb"
+                },
+                {
+                    "name": "lab-tablet",
+                    "serial": "192.168.1.10:5555",
+                    "transport": "tcp"
+                },
+                {
+                    "name": "lab-board",
+                    "serial": "192.168.1.11:5554",
+                    "transport": "tcp",
+                    "deploy": "fastboot"
+                },
+                {
+                    "name": "lab-spare",
+                    "serial": "9B4Y1P2R3"
+                }
+            ]
+        }"#;
+        let mut mocked_logger: MockLogger = MockLogger::new();
+        for line in [
+            ["NAME", "SERIAL", "PRODUCT", "TRANSPORT", "DEPLOY", "STATUS"],
+            ["lab-pixel-3", "8A3X0N1Q2", "blueline", "usb", "adb", "adb"],
+            [
+                "lab-tablet",
+                "192.168.1.10:5555",
+                "",
+                "tcp",
+                "",
+                "adb (unauthorized)",
+            ],
+            [
+                "lab-board",
+                "192.168.1.11:5554",
+                "",
+                "tcp",
+                "fastboot",
+                "fastboot",
+            ],
+            ["lab-spare", "9B4Y1P2R3", "", "usb", "", "not attached"],
+        ] {
+            mocked_logger
+                .expect_stdout()
+                .with(mockall::predicate::eq(format!(
+                    "{:<20} {:<25} {:<15} {:<10} {:<10} {}",
+                    line[0], line[1], line[2], line[3], line[4], line[5]
+                )))
+                .once()
+                .returning(|_x| ());
+        }
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_env().returning(HashMap::new);
+        helper_devices_call(
+            &mut mocked_system,
+            "adb",
+            Ok("List of devices attached\n8A3X0N1Q2\tdevice\n192.168.1.10:5555\tunauthorized\n\n"),
+        );
+        helper_devices_call(
+            &mut mocked_system,
+            "fastboot",
+            Ok("tcp:192.168.1.11:5554\tfastboot\n"),
+        );
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(&work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(r#"{"version": "5"}"#, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(mocked_logger),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "devices"]),
+        );
+        let cmd: DevicesCommand = DevicesCommand::new();
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to list devices");
+    }
+
+    #[test]
+    fn test_cmd_devices_missing_tool() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "8A3X0N1Q2"
+                }
+            ]
+        }"#;
+        let mut mocked_logger: MockLogger = MockLogger::new();
+        for tool in ["adb", "fastboot"] {
+            mocked_logger
+                .expect_warn()
+                .with(mockall::predicate::eq(format!(
+                    "Failed to list the devices attached using '{}': exit status: 127: bash: {}: command not found",
+                    tool, tool
+                )))
+                .once()
+                .returning(|_x| ());
+        }
+        for line in [
+            ["NAME", "SERIAL", "PRODUCT", "TRANSPORT", "DEPLOY", "STATUS"],
+            ["lab-pixel-3", "8A3X0N1Q2", "", "usb", "", "not attached"],
+        ] {
+            mocked_logger
+                .expect_stdout()
+                .with(mockall::predicate::eq(format!(
+                    "{:<20} {:<25} {:<15} {:<10} {:<10} {}",
+                    line[0], line[1], line[2], line[3], line[4], line[5]
+                )))
+                .once()
+                .returning(|_x| ());
+        }
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_env().returning(HashMap::new);
+        helper_devices_call(
+            &mut mocked_system,
+            "adb",
+            Err("exit status: 127: bash: adb: command not found"),
+        );
+        helper_devices_call(
+            &mut mocked_system,
+            "fastboot",
+            Err("exit status: 127: bash: fastboot: command not found"),
+        );
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(&work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(r#"{"version": "5"}"#, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(mocked_logger),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "devices"]),
+        );
+        let cmd: DevicesCommand = DevicesCommand::new();
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to list devices");
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::cli::Cli;
+use crate::commands::{BError, YBaseCommand, YCommand};
+use crate::executers::Docker;
+use crate::workspace::Workspace;
+
+static YCOMMAND: &str = "docker";
+static YCOMMAND_ABOUT: &str =
+    "Manage the persistent docker containers started by yaab for the workspace.";
+pub struct DockerCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for DockerCommand {
+    fn get_config_name(&self, cli: &Cli) -> String {
+        if let Some(sub_matches) = cli.get_args().subcommand_matches(YCOMMAND) {
+            if sub_matches.contains_id("config") {
+                if let Some(value) = sub_matches.get_one::<String>("config") {
+                    return value.clone();
+                }
+            }
+        }
+
+        String::from("NA")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        self.cmd.require_docker
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let config: String = self.get_arg_str(cli, "config", YCOMMAND)?;
+        let action: String = self.get_arg_str(cli, "action", YCOMMAND)?;
+        let work_dir: PathBuf = workspace.settings().work_dir();
+
+        /*
+         * The containers are filtered on the workspace and if a build config
+         * is specified only the containers for that build config are included
+         */
+        let mut filter: &str = "";
+        if config != "NA" {
+            if !workspace.valid_config(config.as_str()) {
+                return Err(BError::CliError(format!(
+                    "Unsupported build config '{}'",
+                    config
+                )));
+            }
+            filter = config.as_str();
+        }
+
+        let cmd_line: Vec<String> = match action.as_str() {
+            "status" => Docker::persistent_status_cmd_line(&work_dir, filter),
+            "stop" => {
+                cli.info(String::from("Stopping persistent containers"));
+                Docker::persistent_stop_cmd_line(&work_dir, filter)
+            }
+            "prune" => {
+                cli.info(String::from("Removing persistent containers"));
+                Docker::persistent_prune_cmd_line(&work_dir, filter)
+            }
+            _ => {
+                return Err(BError::CliError(format!("Invalid action '{}'", action)));
+            }
+        };
+
+        cli.check_call(&cmd_line, &HashMap::new(), true)
+    }
+}
+
+impl DockerCommand {
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND)
+            .about(YCOMMAND_ABOUT)
+            .arg_required_else_help(true)
+            .arg(
+                clap::Arg::new("action")
+                    .value_name("action")
+                    .required(true)
+                    .value_parser(["status", "stop", "prune"])
+                    .help(
+                        "List, stop or remove the persistent containers started for the workspace.",
+                    ),
+            )
+            .arg(
+                clap::Arg::new("config")
+                    .short('c')
+                    .long("config")
+                    .help("Only manage the persistent containers started for this build config")
+                    .value_name("name")
+                    .default_value("NA"),
+            )
+            .arg(
+                clap::Arg::new("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("verbose")
+                    .help("Set verbose level."),
+            );
+        DockerCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: false,
+                require_docker: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::commands::{DockerCommand, YCommand};
+    use crate::executers::Docker;
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    #[test]
+    fn test_cmd_docker_prune() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            },
+            "docker": {
+                "persistent": "true"
+            }
+        }"#;
+        let json_build_config: &str = r#"
+        {
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch"
+        }
+        "#;
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system
+            .expect_check_call()
+            .with(mockall::predicate::eq(CallParams {
+                cmd_line: Docker::persistent_prune_cmd_line(work_dir, "default"),
+                env: HashMap::new(),
+                shell: true,
+            }))
+            .once()
+            .returning(|_x| Ok(()));
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "docker", "prune", "-c", "default"]),
+        );
+        let cmd: DockerCommand = DockerCommand::new();
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute docker command");
+    }
+}
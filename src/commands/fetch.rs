@@ -0,0 +1,213 @@
+use indexmap::IndexMap;
+use std::path::PathBuf;
+
+use crate::cli::Cli;
+use crate::commands::{BError, YBaseCommand, YCommand};
+use crate::data::WsContextData;
+use crate::workspace::Workspace;
+use crate::workspace::WsCustomSubCmdHandler;
+
+static YCOMMAND: &str = "fetch";
+static YCOMMAND_ABOUT: &str =
+    "Fetch the artifacts of a previous build from where the upload sub-command uploaded them.";
+pub struct FetchCommand {
+    cmd: YBaseCommand,
+}
+
+impl YCommand for FetchCommand {
+    fn get_config_name(&self, cli: &Cli) -> String {
+        if let Some(sub_matches) = cli.get_args().subcommand_matches(YCOMMAND) {
+            if sub_matches.contains_id("config") {
+                if let Some(value) = sub_matches.get_one::<String>("config") {
+                    return value.clone();
+                }
+            }
+        }
+
+        String::from("default")
+    }
+
+    fn cmd_str(&self) -> &str {
+        &self.cmd.cmd_str
+    }
+
+    fn subcommand(&self) -> &clap::Command {
+        &self.cmd.sub_cmd
+    }
+
+    fn is_docker_required(&self) -> bool {
+        self.cmd.require_docker
+    }
+
+    fn execute(&self, cli: &Cli, workspace: &mut Workspace) -> Result<(), BError> {
+        let config: String = self.get_arg_str(cli, "config", YCOMMAND)?;
+        let ctx: Vec<String> = self.get_arg_many(cli, "ctx", YCOMMAND)?;
+        let dir: String = self.get_arg_str(cli, "dir", YCOMMAND)?;
+        let args_context: IndexMap<String, String> = self.setup_context(ctx);
+        let context: WsContextData = WsContextData::new(&args_context)?;
+
+        if !workspace.valid_config(config.as_str()) {
+            return Err(BError::CliError(format!(
+                "Unsupported build config '{}'",
+                config
+            )));
+        }
+
+        /*
+         * The upload node is expanded using the context so the build to fetch
+         * is selected by passing the context variables used by the path of the
+         * upload node like the build id.
+         */
+        workspace.update_ctx(&context)?;
+
+        let upload: &WsCustomSubCmdHandler = workspace.config().upload();
+        let mut dest: PathBuf = workspace.settings().artifacts_dir();
+        if let Some(data) = upload.upload() {
+            dest = PathBuf::from(data.dir());
+        }
+        if !dir.is_empty() {
+            dest = PathBuf::from(dir);
+        }
+
+        upload.fetch(cli, &cli.env(), &dest)
+    }
+}
+
+impl FetchCommand {
+    pub fn new() -> Self {
+        let subcmd: clap::Command = clap::Command::new(YCOMMAND)
+            .about(YCOMMAND_ABOUT)
+            .arg(
+                clap::Arg::new("config")
+                    .short('c')
+                    .long("config")
+                    .help("The build config defining the upload node")
+                    .value_name("name")
+                    .required(true),
+            )
+            .arg(
+                clap::Arg::new("dir")
+                    .short('d')
+                    .long("dir")
+                    .value_name("dir")
+                    .default_value("")
+                    .help("The directory to fetch the artifacts to. Default is the artifacts directory."),
+            )
+            .arg(
+                clap::Arg::new("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .long("verbose")
+                    .help("Set verbose level."),
+            )
+            .arg(
+                clap::Arg::new("ctx")
+                    .action(clap::ArgAction::Append)
+                    .short('x')
+                    .long("context")
+                    .value_name("KEY=VALUE")
+                    .help("Adding variable to the context. Any KEY that already exists in the context will be overwriten."),
+            );
+        FetchCommand {
+            cmd: YBaseCommand {
+                cmd_str: String::from(YCOMMAND),
+                sub_cmd: subcmd,
+                interactive: false,
+                require_docker: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::cli::*;
+    use crate::commands::{FetchCommand, YCommand};
+    use crate::workspace::{Workspace, WsBuildConfigHandler, WsSettingsHandler};
+
+    #[test]
+    fn test_cmd_fetch_http() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &PathBuf = &temp_dir.into_path();
+        let server: tiny_http::Server =
+            tiny_http::Server::http("127.0.0.1:0").expect("Failed to start server");
+        let url: String = format!("http://{}", server.server_addr());
+        let handle = std::thread::spawn(move || {
+            let mut urls: Vec<String> = vec![];
+            for _i in 0..2 {
+                let request: tiny_http::Request = server.recv().expect("Failed to receive");
+                let body: &str = match request.url() {
+                    "/builds/default/41/artifacts.json" => {
+                        r#"{"artifacts": [{"path": "image.img", "sha256": "b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c"}]}"#
+                    }
+                    _ => "foo\n",
+                };
+                urls.push(request.url().to_string());
+                request
+                    .respond(tiny_http::Response::from_string(body))
+                    .expect("Failed to respond");
+            }
+            urls
+        });
+        let json_ws_settings: &str = r#"
+        {
+            "version": "5",
+            "builds": {
+                "supported": [
+                    "default"
+                ]
+            }
+        }"#;
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "version": "5",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch",
+            "upload": {{
+                "type": "http",
+                "url": "{}/builds",
+                "path": "$#[YAAB_PRODUCT_NAME]/$#[BUILD]"
+            }}
+        }}
+        "#,
+            url
+        );
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_env().returning(HashMap::new);
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(work_dir, json_ws_settings)
+            .expect("Failed to parse settings");
+        let config: WsBuildConfigHandler =
+            WsBuildConfigHandler::from_str(&json_build_config, &settings)
+                .expect("Failed to parse build config");
+        let mut workspace: Workspace =
+            Workspace::new(Some(work_dir.to_owned()), Some(settings), Some(config))
+                .expect("Failed to setup workspace");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab", "fetch", "-c", "default", "-x", "BUILD=41"]),
+        );
+        let cmd: FetchCommand = FetchCommand::new();
+        cmd.execute(&cli, &mut workspace)
+            .expect("Failed to execute fetch command");
+        assert_eq!(
+            handle.join().expect("Failed to join server"),
+            vec![
+                "/builds/default/41/artifacts.json",
+                "/builds/default/41/image.img"
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(work_dir.join("artifacts/image.img"))
+                .expect("Failed to read file"),
+            "foo\n"
+        );
+    }
+}
@@ -160,6 +160,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_docker_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("docker");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "docker");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_artifacts_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("artifacts");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "artifacts");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_collect_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("collect");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "collect");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_archive_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("archive");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "archive");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_fetch_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("fetch");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "fetch");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_devices_command() {
+        let cmd_handler: CmdHandler = CmdHandler::new();
+        let cmd: Result<&Box<dyn YCommand>, BError> = cmd_handler.get_cmd("devices");
+
+        match cmd {
+            Ok(command) => {
+                assert_eq!(command.cmd_str(), "devices");
+            }
+            Err(err) => {
+                panic!("Expected OK result, but got an error '{}'", err);
+            }
+        }
+    }
+
     #[test]
     fn test_get_invalid_command() {
         let cmd_handler: CmdHandler = CmdHandler::new();
@@ -1,6 +1,12 @@
+pub mod archive;
+pub mod artifacts;
 pub mod build;
 pub mod clean;
+pub mod collect;
 pub mod deploy;
+pub mod devices;
+pub mod docker;
+pub mod fetch;
 pub mod handler;
 pub mod list;
 pub mod setup;
@@ -15,11 +21,12 @@ use std::str::FromStr;
 use std::fmt;
 use std::error::Error;
 
-use crate::cli::Cli;
+use crate::cli::{handshake, Cli};
+use crate::configs::YAAB_HOST_BINARY;
 use crate::data::TType;
 use crate::error::BError;
 use crate::executers::docker::Docker;
-use crate::executers::DockerImage;
+use crate::executers::{Apptainer, BootstrapParams, ContainerExecuter};
 use crate::workspace::Workspace;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -84,22 +91,64 @@ pub trait YCommand {
 
     fn bootstrap(
         &self,
-        cmd_line: &Vec<String>,
+        cmd_line: &[String],
         cli: &Cli,
         workspace: &Workspace,
-        volumes: &Vec<String>,
+        volumes: &[String],
         interactive: bool,
     ) -> Result<(), BError> {
-        let docker: Docker = Docker::new(workspace.settings().docker_image(), interactive);
-
         /*
-         * When we bootstrap yaab into docker we should make sure that we pull
+         * When we bootstrap yaab into a container we should make sure that we pull
          * in the entire env from the parent
          */
-        let env: HashMap<String, String> = cli.env();
+        let mut env: HashMap<String, String> = cli.env();
+        env.extend(handshake::env());
+
+        let mut cmd_line: Vec<String> = cmd_line.to_vec();
+        let mut volumes: Vec<String> = volumes.to_vec();
+        if workspace.settings().docker_host_yaab() {
+            volumes.push(self.host_yaab()?);
+            if let Some(yaab) = cmd_line.first_mut() {
+                *yaab = String::from(YAAB_HOST_BINARY);
+            }
+        }
+        let params: BootstrapParams = BootstrapParams {
+            cmd_line,
+            docker_top_dir: workspace.settings().docker_top_dir(),
+            work_dir: workspace.settings().work_dir(),
+            docker_args: workspace.settings().docker_args().clone(),
+            volumes,
+            env,
+        };
+
+        if workspace.settings().apptainer_enabled() {
+            let mut apptainer: Apptainer = Apptainer::new(
+                workspace.settings().container_runtime(),
+                &workspace.settings().apptainer_image(),
+                interactive,
+            );
+            apptainer.set_mounts(workspace.settings().docker_mounts());
+
+            cli.info(format!("Bootstrap yaab into '{}'", apptainer.image()));
+            cli.debug(format!("env: {:?}", params.env));
+            return apptainer.bootstrap_yaab(&params, cli);
+        }
+
+        let mut docker: Docker = if workspace.settings().docker_persistent() {
+            Docker::persistent(
+                workspace.settings().docker_image(),
+                interactive,
+                &workspace.settings().work_dir(),
+                workspace.config().build_data().name(),
+            )
+        } else {
+            Docker::new(workspace.settings().docker_image(), interactive)
+        };
+        docker.set_mounts(workspace.settings().docker_mounts());
+        docker.set_resources(workspace.settings().docker_resources().clone());
 
         cli.info(format!("Bootstrap yaab into '{}'", docker.image()));
-        cli.debug(format!("env: {:?}", env));
+        cli.debug(format!("env: {:?}", params.env));
 
         if !PathBuf::from("/usr/bin/docker").exists() {
             return Err(BError::DockerError());
@@ -116,16 +165,17 @@ pub trait YCommand {
          * docker implemmentation to rust docker API.
          */
         // docker.pull(cli)?;
+        docker.bootstrap_yaab(&params, cli)
+    }
 
-        return docker.bootstrap_yaab(
-            cmd_line,
-            cli,
-            &workspace.settings().docker_top_dir(),
-            &workspace.settings().work_dir(),
-            workspace.settings().docker_args(),
-            volumes,
-            &env,
-        );
+    /*
+     * Mount the yaab binary currently running on the host read-only into the
+     * container so that the same yaab is used both on the host and inside the
+     * container regardless of the yaab version installed in the image.
+     */
+    fn host_yaab(&self) -> Result<String, BError> {
+        let exe: PathBuf = std::env::current_exe()?;
+        Ok(format!("{}:{}:ro", exe.display(), YAAB_HOST_BINARY))
     }
 
     fn get_config_name(&self, _cli: &Cli) -> String {
@@ -251,15 +301,27 @@ pub fn get_supported_cmds() -> HashMap<&'static str, Box<dyn YCommand>> {
     supported_cmds.insert("upload", Box::new(UploadCommand::new()));
     supported_cmds.insert("setup", Box::new(SetupCommand::new()));
     supported_cmds.insert("sync", Box::new(SyncCommand::new()));
+    supported_cmds.insert("docker", Box::new(DockerCommand::new()));
+    supported_cmds.insert("artifacts", Box::new(ArtifactsCommand::new()));
+    supported_cmds.insert("collect", Box::new(CollectCommand::new()));
+    supported_cmds.insert("archive", Box::new(ArchiveCommand::new()));
+    supported_cmds.insert("fetch", Box::new(FetchCommand::new()));
+    supported_cmds.insert("devices", Box::new(DevicesCommand::new()));
 
     // Add more commands as needed
 
     supported_cmds
 }
 
+pub use archive::ArchiveCommand;
+pub use artifacts::ArtifactsCommand;
 pub use build::BuildCommand;
 pub use clean::CleanCommand;
+pub use collect::CollectCommand;
 pub use deploy::DeployCommand;
+pub use devices::DevicesCommand;
+pub use docker::DockerCommand;
+pub use fetch::FetchCommand;
 pub use handler::CmdHandler;
 pub use list::ListCommand;
 pub use setup::SetupCommand;
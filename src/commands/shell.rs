@@ -5,7 +5,7 @@ use std::path::PathBuf;
 use crate::cli::Cli;
 use crate::commands::{BError, YBaseCommand, YCommand};
 use crate::data::TType;
-use crate::executers::{Docker, DockerImage};
+use crate::executers::{container_executer, inside_container, ContainerExecuter, DockerImage};
 use crate::workspace::Workspace;
 
 use super::Variant;
@@ -60,7 +60,7 @@ impl YCommand for ShellCommand {
          */
         if !workspace.settings().docker_disabled()
             && self.is_docker_required()
-            && !Docker::inside_docker()
+            && !inside_container()
         {
             let mut cmd_line: Vec<String> = vec![String::from("yaab"), String::from("shell")];
 
@@ -277,7 +277,8 @@ impl ShellCommand {
         cli.info(String::from("Start shell setting up build env"));
         if !docker.is_empty() {
             let image: DockerImage = DockerImage::new(&docker)?;
-            let executer: Docker = Docker::new(image, true);
+            let executer: Box<dyn ContainerExecuter> =
+                container_executer(workspace.settings(), image, true);
             return executer.run_cmd(&cmd_line, &env, &workspace.settings().work_dir(), cli);
         }
 
@@ -308,7 +309,8 @@ impl ShellCommand {
         cli.info(format!("Running command '{}'", cmd));
         if !docker.is_empty() {
             let image: DockerImage = DockerImage::new(&docker)?;
-            let executer: Docker = Docker::new(image, true);
+            let executer: Box<dyn ContainerExecuter> =
+                container_executer(workspace.settings(), image, true);
             return executer.run_cmd(&cmd_line, &env, &workspace.settings().work_dir(), cli);
         }
 
@@ -326,7 +328,8 @@ impl ShellCommand {
         cli.info(String::from("Starting shell"));
         if !docker.is_empty() {
             let image: DockerImage = DockerImage::new(&docker)?;
-            let executer: Docker = Docker::new(image, true);
+            let executer: Box<dyn ContainerExecuter> =
+                container_executer(workspace.settings(), image, true);
             return executer.run_cmd(
                 &cmd_line,
                 &HashMap::new(),
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use crate::cli::Cli;
 use crate::commands::{BError, YBaseCommand, YCommand};
 use crate::data::WsContextData;
-use crate::executers::Docker;
+use crate::executers::inside_container;
 use crate::workspace::Workspace;
 use crate::workspace::WsCustomSubCmdHandler;
 
@@ -62,7 +62,7 @@ impl YCommand for UploadCommand {
          */
         if !workspace.settings().docker_disabled()
             && self.is_docker_required()
-            && !Docker::inside_docker()
+            && !inside_container()
         {
             return self.bootstrap(
                 &cli.get_cmd_line(),
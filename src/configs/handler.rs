@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::configs::WsSettings;
 use crate::error::BError;
 use crate::fs::ConfigFileReader;
 use crate::main;
@@ -8,6 +9,7 @@ use crate::workspace::{WsBuildConfigHandler, WsSettingsHandler};
 use super::Config;
 
 const WORKSPACE_SETTINGS: &str = "workspace.json";
+const WORKSPACE_DEVICES: &str = "devices.json";
 
 pub struct WsConfigFileHandler {
     work_dir: PathBuf,
@@ -24,6 +26,23 @@ impl WsConfigFileHandler {
     }
 
     pub fn ws_settings(&self) -> Result<WsSettingsHandler, BError> {
+        let mut settings: WsSettingsHandler = self.read_ws_settings()?;
+
+        /*
+         * The device inventory in ${HOME}/.yaab/devices.json is shared by all workspaces and
+         * merged into the device inventory of the workspace settings. This way the devices on
+         * the desk of a baker can be kept in one place while the workspace settings are still
+         * taken from the repo.
+         */
+        let path: PathBuf = self.yaab_dir.clone().join(WORKSPACE_DEVICES);
+        if path.exists() {
+            let devices_str: String = ConfigFileReader::new(&path).read_json()?;
+            settings.merge_devices(WsSettings::devices(&WsSettings::parse(&devices_str)?)?);
+        }
+        Ok(settings)
+    }
+
+    fn read_ws_settings(&self) -> Result<WsSettingsHandler, BError> {
         let mut path: PathBuf = self.yaab_dir.clone().join(WORKSPACE_SETTINGS);
 
         /*
@@ -4,7 +4,7 @@ pub mod settings;
 
 pub use context::Context;
 pub use handler::WsConfigFileHandler;
-pub use settings::WsSettings;
+pub use settings::{WsSettings, YAAB_CONFIG_VERSION, YAAB_HOST_BINARY};
 
 use crate::error::BError;
 use indexmap::IndexMap;
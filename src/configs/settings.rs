@@ -1,5 +1,8 @@
 use crate::configs::Config;
+use crate::data::WsDeviceData;
 use crate::error::BError;
+use crate::executers::DockerResources;
+use crate::fs::Retention;
 use serde_json::Value;
 
 // Not the ideal solution we should see if it is possible to
@@ -9,6 +12,12 @@ pub const _YAAB_DOCKER_ARGS: [&str; 2] = ["--rm=true", "-t"];
 pub const YAAB_DOCKER_IMAGE: &str = "mikrodidakt/yaab/yaab-workspace";
 pub const YAAB_DOCKER_TAG: &str = env!("CARGO_PKG_VERSION");
 pub const YAAB_DOCKER_REGISTRY: &str = "ghcr.io";
+// The version of the config format supported by this version of yaab
+pub const YAAB_CONFIG_VERSION: &str = "5";
+// Path to the host yaab binary inside the container when mounted
+pub const YAAB_HOST_BINARY: &str = "/opt/yaab/bin/yaab";
+// The container runtimes that can be used for the workspace
+pub const YAAB_CONTAINER_RUNTIMES: [&str; 3] = ["docker", "apptainer", "singularity"];
 
 #[derive(Clone)]
 pub struct WsSettings {
@@ -16,6 +25,10 @@ pub struct WsSettings {
     pub configs_dir: String,
     pub builds_dir: String,
     pub artifacts_dir: String,
+    pub artifacts_sha256sums: String,
+    pub artifacts_mode: String,
+    pub artifacts_layout: String,
+    pub artifacts_retention: Retention,
     pub include_dir: String,
     pub scripts_dir: String,
     pub docker_dir: String,
@@ -27,6 +40,18 @@ pub struct WsSettings {
     pub docker_args: Vec<String>,
     pub docker_disabled: String,
     pub docker_top_dir: String,
+    pub docker_persistent: String,
+    pub docker_mounts_add: Vec<String>,
+    pub docker_mounts_remove: Vec<String>,
+    pub docker_mounts_readonly: Vec<String>,
+    pub docker_sock: String,
+    pub docker_ssh_agent: String,
+    pub docker_credential_sock: String,
+    pub docker_resources: DockerResources,
+    pub docker_runtime: String,
+    pub docker_sif: String,
+    pub docker_host_yaab: String,
+    pub devices: Vec<WsDeviceData>,
 }
 
 impl Config for WsSettings {}
@@ -39,6 +64,10 @@ impl WsSettings {
         let mut include_dir: String = String::from("configs/include");
         let mut builds_dir: String = String::from("builds");
         let mut artifacts_dir: String = String::from("artifacts");
+        let mut artifacts_sha256sums: String = String::from("false");
+        let mut artifacts_mode: String = String::from("copy");
+        let mut artifacts_layout: String = String::from("");
+        let mut artifacts_retention: Retention = Retention::default();
         let mut scripts_dir: String = String::from("scripts");
         let mut docker_dir: String = String::from("docker");
         let mut cache_dir: String = String::from(".cache");
@@ -49,6 +78,17 @@ impl WsSettings {
         let mut docker_args: Vec<String> = vec![];
         let mut docker_disabled: String = String::from("false");
         let mut docker_top_dir: String = String::from("");
+        let mut docker_persistent: String = String::from("false");
+        let mut docker_mounts_add: Vec<String> = vec![];
+        let mut docker_mounts_remove: Vec<String> = vec![];
+        let mut docker_mounts_readonly: Vec<String> = vec![];
+        let mut docker_sock: String = String::from("true");
+        let mut docker_ssh_agent: String = String::from("false");
+        let mut docker_credential_sock: String = String::from("");
+        let mut docker_resources: DockerResources = DockerResources::default();
+        let mut docker_runtime: String = String::from("docker");
+        let mut docker_sif: String = String::from("");
+        let mut docker_host_yaab: String = String::from("false");
 
         match Self::get_value("workspace", &data) {
             Ok(ws_data) => {
@@ -63,6 +103,13 @@ impl WsSettings {
                     Self::get_str_value("buildsdir", ws_data, Some(String::from("builds")))?;
                 artifacts_dir =
                     Self::get_str_value("artifactsdir", ws_data, Some(String::from("artifacts")))?;
+                artifacts_sha256sums =
+                    Self::get_str_value("sha256sums", ws_data, Some(String::from("false")))?;
+                artifacts_mode =
+                    Self::get_str_value("collectmode", ws_data, Some(String::from("copy")))?;
+                artifacts_layout =
+                    Self::get_str_value("artifactslayout", ws_data, Some(String::from("")))?;
+                artifacts_retention = Retention::from_value(ws_data)?;
                 scripts_dir =
                     Self::get_str_value("scriptsdir", ws_data, Some(String::from("scripts")))?;
                 docker_dir =
@@ -100,16 +147,62 @@ impl WsSettings {
                 docker_args = Self::get_array_value("args", docker_data, Some(vec![]))?;
                 docker_top_dir =
                     Self::get_str_value("topdir", docker_data, Some(String::from("")))?;
+                docker_persistent =
+                    Self::get_str_value("persistent", docker_data, Some(String::from("false")))?;
+                docker_ssh_agent =
+                    Self::get_str_value("sshagent", docker_data, Some(String::from("false")))?;
+                docker_credential_sock =
+                    Self::get_str_value("credentialsock", docker_data, Some(String::from("")))?;
+                docker_resources = DockerResources::from_value(docker_data)?;
+                docker_runtime =
+                    Self::get_str_value("runtime", docker_data, Some(String::from("docker")))?;
+                if !YAAB_CONTAINER_RUNTIMES.contains(&docker_runtime.as_str()) {
+                    return Err(BError::ParseError(format!(
+                        "Invalid 'docker' node in workspace config. Invalid runtime '{}', must be one of {}",
+                        docker_runtime,
+                        YAAB_CONTAINER_RUNTIMES.join(", ")
+                    )));
+                }
+                docker_sif = Self::get_str_value("sif", docker_data, Some(String::from("")))?;
+                docker_host_yaab =
+                    Self::get_str_value("hostyaab", docker_data, Some(String::from("false")))?;
+                match Self::get_value("mounts", docker_data) {
+                    Ok(mounts_data) => {
+                        docker_mounts_add =
+                            Self::get_array_value("add", mounts_data, Some(vec![]))?;
+                        docker_mounts_remove =
+                            Self::get_array_value("remove", mounts_data, Some(vec![]))?;
+                        docker_mounts_readonly =
+                            Self::get_array_value("readonly", mounts_data, Some(vec![]))?;
+                        docker_sock = Self::get_str_value(
+                            "dockersock",
+                            mounts_data,
+                            Some(String::from("true")),
+                        )?;
+                    }
+                    Err(_err) => {}
+                }
             }
             Err(_err) => {}
         }
 
+        /*
+         * The device inventory used to select a device by name when
+         * deploying. A user wide inventory can be kept in ~/.yaab/devices.json
+         * which is merged into the inventory of the workspace config.
+         */
+        let devices: Vec<WsDeviceData> = Self::devices(&data)?;
+
         Ok(WsSettings {
             version,
             configs_dir,
             include_dir,
             builds_dir,
             artifacts_dir,
+            artifacts_sha256sums,
+            artifacts_mode,
+            artifacts_layout,
+            artifacts_retention,
             scripts_dir,
             docker_dir,
             cache_dir,
@@ -120,8 +213,42 @@ impl WsSettings {
             docker_args,
             docker_disabled,
             docker_top_dir,
+            docker_persistent,
+            docker_mounts_add,
+            docker_mounts_remove,
+            docker_mounts_readonly,
+            docker_sock,
+            docker_ssh_agent,
+            docker_credential_sock,
+            docker_resources,
+            docker_runtime,
+            docker_sif,
+            docker_host_yaab,
+            devices,
         })
     }
+
+    /*
+     * Parse the devices node either from the workspace config or from the
+     * devices file in ~/.yaab. The name is used to select a device so it has
+     * to be unique.
+     */
+    pub fn devices(data: &Value) -> Result<Vec<WsDeviceData>, BError> {
+        let mut devices: Vec<WsDeviceData> = vec![];
+        if let Some(devices_data) = data.get("devices").and_then(|d| d.as_array()) {
+            for device_data in devices_data.iter() {
+                let device: WsDeviceData = WsDeviceData::new(device_data)?;
+                if devices.iter().any(|d| d.name() == device.name()) {
+                    return Err(BError::ParseError(format!(
+                        "Invalid 'devices' node in workspace config. Duplicate device '{}'",
+                        device.name()
+                    )));
+                }
+                devices.push(device);
+            }
+        }
+        Ok(devices)
+    }
 }
 
 #[cfg(test)]
@@ -155,6 +282,106 @@ mod tests {
         assert_eq!(&settings.cache_dir, "cache_test");
     }
 
+    #[test]
+    fn test_settings_config_sha256sums() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "workspace": {
+              "sha256sums": "true"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.artifacts_sha256sums, "true");
+        let settings = Helper::setup_ws_settings(r#"{"version": "5"}"#);
+        assert_eq!(&settings.artifacts_sha256sums, "false");
+    }
+
+    #[test]
+    fn test_settings_config_collectmode() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "workspace": {
+              "collectmode": "hardlink"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.artifacts_mode, "hardlink");
+        let settings = Helper::setup_ws_settings(r#"{"version": "5"}"#);
+        assert_eq!(&settings.artifacts_mode, "copy");
+    }
+
+    #[test]
+    fn test_settings_config_retention() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "workspace": {
+              "artifactslayout": "$#[YAAB_PLATFORM_RELEASE]/$#[YAAB_BUILD_VARIANT]",
+              "retention": {
+                "keep": "5",
+                "maxage": "30d",
+                "maxsize": "100G"
+              }
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(
+            &settings.artifacts_layout,
+            "$#[YAAB_PLATFORM_RELEASE]/$#[YAAB_BUILD_VARIANT]"
+        );
+        assert_eq!(&settings.artifacts_retention.keep, "5");
+        assert_eq!(&settings.artifacts_retention.max_age, "30d");
+        assert_eq!(&settings.artifacts_retention.max_size, "100G");
+        let settings = Helper::setup_ws_settings(r#"{"version": "5"}"#);
+        assert_eq!(&settings.artifacts_layout, "");
+        assert!(settings.artifacts_retention.is_empty());
+    }
+
+    #[test]
+    fn test_settings_config_devices() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "8A3X0N1Q2",
+                    "product": "blueline",
+                    "deploy": "fastboot"
+                },
+                {
+                    "name": "lab-tablet",
+                    "serial": "192.168.1.10:5555",
+                    "transport": "tcp"
+                }
+            ]
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(settings.devices.len(), 2);
+        assert_eq!(settings.devices[0].name(), "lab-pixel-3");
+        assert_eq!(settings.devices[0].mode(), "fastboot");
+        assert_eq!(settings.devices[1].transport(), "tcp");
+        let settings = Helper::setup_ws_settings(r#"{"version": "5"}"#);
+        assert!(settings.devices.is_empty());
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "serial1"
+                },
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "serial2"
+                }
+            ]
+        }"#;
+        assert!(crate::configs::WsSettings::from_str(json_test_str).is_err());
+    }
+
     #[test]
     fn test_settings_config_default_workspace_dirs() {
         let json_test_str = r#"
@@ -391,6 +618,166 @@ mod tests {
         assert_eq!(&settings.docker_disabled, "true");
     }
 
+    #[test]
+    fn test_settings_config_default_docker_persistent() {
+        let json_test_str = r#"
+        {
+            "version": "5"
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_persistent, "false");
+    }
+
+    #[test]
+    fn test_settings_config_docker_persistent() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "persistent": "true"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_persistent, "true");
+    }
+
+    #[test]
+    fn test_settings_config_default_docker_mounts() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {}
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert!(settings.docker_mounts_add.is_empty());
+        assert!(settings.docker_mounts_remove.is_empty());
+        assert!(settings.docker_mounts_readonly.is_empty());
+        assert_eq!(&settings.docker_sock, "true");
+    }
+
+    #[test]
+    fn test_settings_config_docker_mounts() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "mounts": {
+                    "add": [
+                        "/opt/tools:/opt/tools:ro"
+                    ],
+                    "remove": [
+                        "~/.docker"
+                    ],
+                    "readonly": [
+                        "~/.ssh"
+                    ],
+                    "dockersock": "false"
+                }
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(settings.docker_mounts_add, vec!["/opt/tools:/opt/tools:ro"]);
+        assert_eq!(settings.docker_mounts_remove, vec!["~/.docker"]);
+        assert_eq!(settings.docker_mounts_readonly, vec!["~/.ssh"]);
+        assert_eq!(&settings.docker_sock, "false");
+    }
+
+    #[test]
+    fn test_settings_config_docker_resources() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "resources": {
+                    "memory": "32g",
+                    "cpus": "8"
+                }
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_resources.memory, "32g");
+        assert_eq!(&settings.docker_resources.cpus, "8");
+        assert!(settings.docker_resources.shm_size.is_empty());
+    }
+
+    #[test]
+    fn test_settings_config_docker_runtime() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "runtime": "apptainer",
+                "sif": "images/yaab-workspace.sif"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_runtime, "apptainer");
+        assert_eq!(&settings.docker_sif, "images/yaab-workspace.sif");
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "runtime": "podman"
+            }
+        }"#;
+        match crate::configs::WsSettings::from_str(json_test_str) {
+            Ok(_settings) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid 'docker' node in workspace config. Invalid runtime 'podman', must be one of docker, apptainer, singularity"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_settings_config_docker_host_yaab() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "hostyaab": "true"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_host_yaab, "true");
+        let settings = Helper::setup_ws_settings(r#"{"version": "5"}"#);
+        assert_eq!(&settings.docker_host_yaab, "false");
+    }
+
+    #[test]
+    fn test_settings_config_default_docker_ssh_agent() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {}
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_ssh_agent, "false");
+        assert!(settings.docker_credential_sock.is_empty());
+    }
+
+    #[test]
+    fn test_settings_config_docker_ssh_agent() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "sshagent": "true",
+                "credentialsock": "~/.cache/git/credential/socket"
+            }
+        }"#;
+        let settings = Helper::setup_ws_settings(json_test_str);
+        assert_eq!(&settings.docker_ssh_agent, "true");
+        assert_eq!(
+            &settings.docker_credential_sock,
+            "~/.cache/git/credential/socket"
+        );
+    }
+
     #[test]
     fn test_settings_config_docker_registry() {
         let json_test_str = r#"
@@ -1,9 +1,17 @@
+use indexmap::IndexMap;
 use serde_json::Value;
+use std::fmt;
 
 use crate::configs::Config;
 use crate::configs::Context;
 use crate::error::BError;
 
+/*
+ * The sections that can be generated from the build and added to the content
+ * of a manifest.
+ */
+pub const MANIFEST_SECTIONS: [&str; 5] = ["artifacts", "tasks", "docker", "revisions", "context"];
+
 #[derive(Clone, Eq, Hash, PartialEq, Debug)]
 pub enum AType {
     File,
@@ -12,6 +20,22 @@ pub enum AType {
     Manifest,
     Link,
     Conditional,
+    Switch,
+}
+
+impl fmt::Display for AType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let atype_str = match self {
+            AType::File => "file",
+            AType::Directory => "directory",
+            AType::Archive => "archive",
+            AType::Manifest => "manifest",
+            AType::Link => "link",
+            AType::Conditional => "conditional",
+            AType::Switch => "switch",
+        };
+        write!(f, "{}", atype_str)
+    }
 }
 
 // TODO: we should consider using IndexSet instead of vector to make sure we
@@ -23,6 +47,21 @@ pub struct WsArtifactData {
     pub dest: String, // The dest is optional
     pub manifest: String, // The manifest content will be a json string that can be put in a file. The manifest can then be used by the CI to collect information from the build
     pub condition: String, // The condition is only used if the type is conditional
+    pub value: String,    // The value is only used if the type is switch
+    pub level: String,    // The compression level is only used if the type is archive
+    pub threads: String,  // The compression threads is only used if the type is archive
+    pub reproducible: String, // Reproducible mode is only used if the type is archive
+    pub mtime: String,    // The mtime is only used by a reproducible archive
+    pub split_size: String, // The volume size of a split archive only used if the type is archive
+    pub exclude: Vec<String>, // The exclude is only used if the type is file, directory or archive
+    pub rename: String,   // The rename regex is only used if the type is file or directory
+    pub replace: String,  // The replacement for the rename regex
+    pub symlinks: String, // Keep symlinks as symlinks only used if the type is file or directory
+    pub preserve: String, // Preserve mode bits and timestamps only used if the type is file or directory
+    pub sparse: String,   // Copy sparse files sparsely only used if the type is file or directory
+    pub mode: String,     // The collection mode only used if the type is file or directory
+    pub generate: Vec<String>, // The generated sections only used if the type is manifest
+    pub context: IndexMap<String, String>, // The resolved context used by a generated manifest
 }
 
 impl Config for WsArtifactData {}
@@ -44,6 +83,25 @@ impl WsArtifactData {
         let dest: String = Self::get_str_value("dest", &data, Some(String::from("")))?;
         let manifest: String = Self::get_str_manifest("content", &data, Some(String::from("{}")))?;
         let condition: String = Self::get_str_value("condition", &data, Some(String::from("")))?;
+        let value: String = Self::get_str_value("value", data, Some(String::from("")))?;
+        let level: String = Self::get_str_value("level", data, Some(String::from("")))?;
+        let threads: String = Self::get_str_value("threads", data, Some(String::from("")))?;
+        let reproducible: String =
+            Self::get_str_value("reproducible", data, Some(String::from("false")))?;
+        let mtime: String = Self::get_str_value("mtime", data, Some(String::from("")))?;
+        let split_size: String = Self::get_str_value("splitsize", data, Some(String::from("")))?;
+        let exclude: Vec<String> = Self::get_array_value("exclude", data, Some(vec![]))?;
+        let symlinks: String = Self::get_str_value("symlinks", data, Some(String::from("")))?;
+        let preserve: String = Self::get_str_value("preserve", data, Some(String::from("")))?;
+        let sparse: String = Self::get_str_value("sparse", data, Some(String::from("")))?;
+        let mode: String = Self::get_str_value("mode", data, Some(String::from("")))?;
+        let generate: Vec<String> = Self::get_array_value("generate", data, Some(vec![]))?;
+        let mut rename: String = String::new();
+        let mut replace: String = String::new();
+        if let Ok(rename_data) = Self::get_value("rename", data) {
+            rename = Self::get_str_value("pattern", rename_data, None)?;
+            replace = Self::get_str_value("replace", rename_data, Some(String::from("")))?;
+        }
 
         if ttype != "file"
             && ttype != "directory"
@@ -51,6 +109,7 @@ impl WsArtifactData {
             && ttype != "manifest"
             && ttype != "link"
             && ttype != "conditional"
+            && ttype != "switch"
         {
             return Err(BError::ParseArtifactsError(format!(
                 "Invalid type '{}'",
@@ -88,6 +147,23 @@ impl WsArtifactData {
             )));
         }
 
+        if ttype == "switch" && value.is_empty() {
+            return Err(BError::ParseArtifactsError(
+                "The 'switch' type requires a 'value'".to_string(),
+            ));
+        }
+
+        if let Some(section) = generate
+            .iter()
+            .find(|s| !MANIFEST_SECTIONS.contains(&s.as_str()))
+        {
+            return Err(BError::ParseArtifactsError(format!(
+                "Invalid manifest section '{}', must be one of {}",
+                section,
+                MANIFEST_SECTIONS.join(", ")
+            )));
+        }
+
         let enum_ttype: AType;
         match ttype.as_str() {
             "file" => {
@@ -108,6 +184,9 @@ impl WsArtifactData {
             "conditional" => {
                 enum_ttype = AType::Conditional;
             }
+            "switch" => {
+                enum_ttype = AType::Switch;
+            }
             _ => {
                 return Err(BError::ParseArtifactsError(format!(
                     "Invalid type '{}'",
@@ -126,6 +205,21 @@ impl WsArtifactData {
             dest,
             manifest,
             condition,
+            value,
+            level,
+            threads,
+            reproducible,
+            mtime,
+            split_size,
+            exclude,
+            rename,
+            replace,
+            symlinks,
+            preserve,
+            sparse,
+            mode,
+            generate,
+            context: IndexMap::new(),
         })
     }
 
@@ -135,16 +229,33 @@ impl WsArtifactData {
                 self.name = ctx.expand_str(&self.name)?;
                 self.source = ctx.expand_str(&self.source)?;
                 self.dest = ctx.expand_str(&self.dest)?;
+                for e in self.exclude.iter_mut() {
+                    *e = ctx.expand_str(e)?;
+                }
+                self.rename = ctx.expand_str(&self.rename)?;
+                self.replace = ctx.expand_str(&self.replace)?;
+                self.symlinks = ctx.expand_str(&self.symlinks)?;
+                self.preserve = ctx.expand_str(&self.preserve)?;
+                self.sparse = ctx.expand_str(&self.sparse)?;
+                self.mode = ctx.expand_str(&self.mode)?;
             }
             AType::Directory => {
                 self.name = ctx.expand_str(&self.name)?;
             }
             AType::Archive => {
                 self.name = ctx.expand_str(&self.name)?;
+                self.level = ctx.expand_str(&self.level)?;
+                self.threads = ctx.expand_str(&self.threads)?;
+                self.reproducible = ctx.expand_str(&self.reproducible)?;
+                self.mtime = ctx.expand_str(&self.mtime)?;
+                self.split_size = ctx.expand_str(&self.split_size)?;
             }
             AType::Manifest => {
                 self.name = ctx.expand_str(&self.name)?;
                 self.manifest = ctx.expand_str(&self.manifest)?;
+                if !self.generate.is_empty() {
+                    self.context = ctx.variables().clone();
+                }
             }
             AType::Link => {
                 self.name = ctx.expand_str(&self.name)?;
@@ -153,6 +264,9 @@ impl WsArtifactData {
             AType::Conditional => {
                 self.condition = ctx.expand_str(&self.condition)?;
             }
+            AType::Switch => {
+                self.value = ctx.expand_str(&self.value)?;
+            }
             _ => {
                 panic!(
                     "Invalid 'artifact' format in build config. Invalid type '{:?}'",
@@ -183,6 +297,96 @@ impl WsArtifactData {
         &self.manifest
     }
 
+    pub fn generate(&self) -> &Vec<String> {
+        &self.generate
+    }
+
+    pub fn context(&self) -> &IndexMap<String, String> {
+        &self.context
+    }
+
+    pub fn level(&self) -> &str {
+        &self.level
+    }
+
+    pub fn threads(&self) -> &str {
+        &self.threads
+    }
+
+    /*
+     * The exclude patterns, the rename rule and the copy options of a directory
+     * or archive node are applied to all file nodes of the directory or archive.
+     * A rename rule or copy option defined for the file node itself takes
+     * precedence.
+     */
+    pub fn inherit(&mut self, parent: &WsArtifactData) {
+        parent.exclude.iter().for_each(|e| {
+            if !self.exclude.contains(e) {
+                self.exclude.push(e.clone());
+            }
+        });
+        if self.rename.is_empty() {
+            self.rename = parent.rename.clone();
+            self.replace = parent.replace.clone();
+        }
+        if self.symlinks.is_empty() {
+            self.symlinks = parent.symlinks.clone();
+        }
+        if self.preserve.is_empty() {
+            self.preserve = parent.preserve.clone();
+        }
+        if self.sparse.is_empty() {
+            self.sparse = parent.sparse.clone();
+        }
+        if self.mode.is_empty() {
+            self.mode = parent.mode.clone();
+        }
+    }
+
+    pub fn symlinks(&self) -> bool {
+        self.symlinks == "true"
+    }
+
+    pub fn preserve(&self) -> bool {
+        self.preserve == "true"
+    }
+
+    pub fn sparse(&self) -> bool {
+        self.sparse == "true"
+    }
+
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    pub fn exclude(&self) -> &Vec<String> {
+        &self.exclude
+    }
+
+    pub fn rename(&self) -> &str {
+        &self.rename
+    }
+
+    pub fn replace(&self) -> &str {
+        &self.replace
+    }
+
+    pub fn reproducible(&self) -> bool {
+        self.reproducible == "true"
+    }
+
+    pub fn mtime(&self) -> &str {
+        &self.mtime
+    }
+
+    pub fn split_size(&self) -> &str {
+        &self.split_size
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
     pub fn condition(&self) -> bool {
         match self.condition.as_str() {
             "1" | "yes" | "y" | "Y" | "true" | "YES" | "TRUE" | "True" | "Yes" => return true,
@@ -453,6 +657,53 @@ mod tests {
         assert_eq!(data.name(), "link.txt");
     }
 
+    #[test]
+    fn test_ws_artifact_data_archive_compression() {
+        let ctx_variables: IndexMap<String, String> = indexmap! {
+            "THREADS".to_string() => "8".to_string()
+        };
+        let json_artifact_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "test.tar.zst",
+            "level": "19",
+            "threads": "$#[THREADS]"
+        }
+        "#;
+        let context: Context = Context::new(&ctx_variables);
+        let value: Value =
+            Helper::parse(json_artifact_config).expect("Failed to parse artifact config");
+        let mut data: WsArtifactData =
+            WsArtifactData::new(&value).expect("Failed to parse artifact data");
+        data.expand_ctx(&context).unwrap();
+        assert_eq!(data.atype(), &AType::Archive);
+        assert_eq!(data.level(), "19");
+        assert_eq!(data.threads(), "8");
+    }
+
+    #[test]
+    fn test_ws_artifact_data_archive_reproducible() {
+        let ctx_variables: IndexMap<String, String> = indexmap! {
+            "EPOCH".to_string() => "1700000000".to_string()
+        };
+        let json_artifact_config: &str = r#"
+        {
+            "type": "archive",
+            "name": "test.tar.gz",
+            "reproducible": "true",
+            "mtime": "$#[EPOCH]"
+        }
+        "#;
+        let context: Context = Context::new(&ctx_variables);
+        let value: Value =
+            Helper::parse(json_artifact_config).expect("Failed to parse artifact config");
+        let mut data: WsArtifactData =
+            WsArtifactData::new(&value).expect("Failed to parse artifact data");
+        data.expand_ctx(&context).unwrap();
+        assert!(data.reproducible());
+        assert_eq!(data.mtime(), "1700000000");
+    }
+
     #[test]
     fn test_ws_artifact_data_link_ctx() {
         let ctx_variables: IndexMap<String, String> = indexmap! {
@@ -538,6 +789,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ws_artifact_data_error_manifest_section() {
+        let json_artifact_config: &str = r#"
+        {
+            "type": "manifest",
+            "name": "manifest.json",
+            "generate": [
+                "artifacts",
+                "invalid"
+            ]
+        }
+        "#;
+        let value: Value =
+            Helper::parse(json_artifact_config).expect("Failed to parse artifact config");
+        let result: Result<WsArtifactData, BError> = WsArtifactData::new(&value);
+        match result {
+            Ok(_rconfig) => {
+                panic!("We should have recived an error because the section is invalid!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), String::from("Invalid 'artifact' node in build config. Invalid manifest section 'invalid', must be one of artifacts, tasks, docker, revisions, context"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ws_artifact_data_error_no_value() {
+        let json_artifact_config: &str = r#"
+        {
+            "type": "switch"
+        }
+        "#;
+        let value: Value =
+            Helper::parse(json_artifact_config).expect("Failed to parse artifact config");
+        let result: Result<WsArtifactData, BError> = WsArtifactData::new(&value);
+        match result {
+            Ok(_rconfig) => {
+                panic!("We should have recived an error because the value is missing!");
+            }
+            Err(e) => {
+                assert_eq!(e.to_string(), String::from("Invalid 'artifact' node in build config. The 'switch' type requires a 'value'"));
+            }
+        }
+    }
+
     #[test]
     fn test_ws_artifact_condition_true() {
         let json_artifact_config: &str = r#"
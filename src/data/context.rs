@@ -26,6 +26,7 @@ pub const CTX_KEY_ARCHIVER: &str = "YAAB_ARCHIVER";
 pub const CTX_KEY_DEBUG_SYMBOLS: &str = "YAAB_DEBUG_SYMBOLS";
 pub const CTX_KEY_DEVICE: &str = "YAAB_DEVICE";
 pub const CTX_KEY_IMAGE: &str = "YAAB_IMAGE";
+pub const CTX_KEY_DEPLOY_MODE: &str = "YAAB_DEPLOY_MODE";
 pub const CTX_KEY_DATE: &str = "YAAB_DATE";
 pub const CTX_KEY_TIME: &str = "YAAB_TIME";
 // By default all of these are the same unless they
@@ -52,6 +53,7 @@ impl WsContextData {
             | CTX_KEY_ARCHIVER
             | CTX_KEY_DEVICE
             | CTX_KEY_IMAGE
+            | CTX_KEY_DEPLOY_MODE
             | CTX_KEY_DATE
             | CTX_KEY_TIME
             | CTX_KEY_DEBUG_SYMBOLS => true,
@@ -109,6 +111,7 @@ impl WsContextData {
             CTX_KEY_DEBUG_SYMBOLS.to_string() => "".to_string(),
             CTX_KEY_DEVICE.to_string() => "".to_string(),
             CTX_KEY_IMAGE.to_string() => "".to_string(),
+            CTX_KEY_DEPLOY_MODE.to_string() => "".to_string(),
             CTX_KEY_TIME.to_string() => "".to_string(),
             CTX_KEY_DATE.to_string() => "".to_string(),
         };
@@ -19,6 +19,8 @@ pub struct WsBuildData {
     include: WsIncludeData,
     context: WsContextData,
     settings: WsSettingsHandler,
+    artifacts_dir: PathBuf,
+    layout_error: Option<String>,
 }
 
 impl WsBuildData {
@@ -74,14 +76,59 @@ impl WsBuildData {
 
         context.update(&ctx_built_in_variables);
 
-        Ok(WsBuildData {
+        let mut build_data: WsBuildData = WsBuildData {
             data: data.to_owned(),
             config,
             product,
             include,
             context,
             settings: settings.clone(), // for now lets clone it
-        })
+            artifacts_dir: settings.artifacts_dir(),
+            layout_error: None,
+        };
+        build_data.update_artifacts_dir()?;
+        Ok(build_data)
+    }
+
+    /*
+     * If an artifacts layout is defined in the workspace settings the artifacts
+     * of the build are collected to a sub directory of the artifacts directory.
+     * The layout is expanded using the context so it has to be updated every
+     * time the context is updated. Until all context variables used by the
+     * layout have been set the layout has an empty path component and the
+     * artifacts directory of the workspace is used. Collecting artifacts is
+     * then failing instead of collecting them to a directory with a
+     * different depth than the layout.
+     */
+    fn update_artifacts_dir(&mut self) -> Result<(), BError> {
+        let layout: &str = self.settings.artifacts_layout();
+        if layout.is_empty() {
+            return Ok(());
+        }
+
+        let expanded: String = self.context.ctx().expand_str(layout)?;
+        let components: Vec<&str> = expanded.split('/').collect();
+        if components.iter().any(|c| *c == "." || *c == "..") {
+            return Err(BError::ValueError(format!(
+                "Invalid artifacts layout '{}', must be a path inside the artifacts directory!",
+                expanded
+            )));
+        }
+
+        self.layout_error = None;
+        self.artifacts_dir = self.settings.artifacts_dir();
+        if components.iter().any(|c| c.is_empty()) {
+            self.layout_error = Some(format!(
+                "Invalid artifacts layout '{}', expanded to '{}' which has an empty path component. Make sure all context variables used by the layout are set!",
+                layout, expanded
+            ));
+        } else {
+            self.artifacts_dir = self.artifacts_dir.join(components.join("/"));
+        }
+        self.context.update(&indexmap! {
+            context::CTX_KEY_ARTIFACTS_DIR.to_string() => self.artifacts_dir.to_string_lossy().to_string(),
+        });
+        Ok(())
     }
 
     pub fn get_artifacts(
@@ -89,7 +136,20 @@ impl WsBuildData {
         data: &Value,
         task_build_dir: &PathBuf,
     ) -> Result<Vec<WsArtifactsHandler>, BError> {
-        match data.get("artifacts") {
+        self.get_artifacts_node("artifacts", data, task_build_dir)
+    }
+
+    /*
+     * Get the list of artifacts from any array node of artifacts like the
+     * 'else' node of a conditional or a case of a switch.
+     */
+    pub fn get_artifacts_node(
+        &self,
+        node: &str,
+        data: &Value,
+        task_build_dir: &PathBuf,
+    ) -> Result<Vec<WsArtifactsHandler>, BError> {
+        match data.get(node) {
             Some(value) => {
                 if value.is_array() {
                     if let Some(artifact_vec) = value.as_array() {
@@ -101,13 +161,15 @@ impl WsBuildData {
                         }
                         return Ok(artifacts);
                     }
-                    return Err(BError::ParseArtifactsError(
-                        "Invalid 'artifacts' node in build config".to_string(),
-                    ));
+                    Err(BError::ParseArtifactsError(format!(
+                        "Invalid '{}' node in build config",
+                        node
+                    )))
                 } else {
-                    return Err(BError::ParseArtifactsError(
-                        "No 'artifacts' array node found in build config".to_string(),
-                    ));
+                    Err(BError::ParseArtifactsError(format!(
+                        "No '{}' array node found in build config",
+                        node
+                    )))
                 }
             }
             None => {
@@ -191,6 +253,21 @@ impl WsBuildData {
         &self.settings
     }
 
+    /*
+     * The artifacts directory of the build which is the artifacts directory of
+     * the workspace unless an artifacts layout is used.
+     */
+    pub fn artifacts_dir(&self) -> PathBuf {
+        self.artifacts_dir.clone()
+    }
+
+    pub fn verify_artifacts_dir(&self) -> Result<(), BError> {
+        match &self.layout_error {
+            Some(error) => Err(BError::ValueError(error.clone())),
+            None => Ok(()),
+        }
+    }
+
     pub fn context(&self) -> &WsContextData {
         &self.context
     }
@@ -200,6 +277,7 @@ impl WsBuildData {
     }
 
     pub fn expand_ctx(&mut self) -> Result<(), BError> {
+        self.update_artifacts_dir()?;
         self.config.expand_ctx(self.context.ctx())?;
         Ok(())
     }
@@ -212,15 +290,16 @@ impl WsBuildData {
 #[cfg(test)]
 mod tests {
     use chrono;
-    use indexmap::IndexMap;
+    use indexmap::{indexmap, IndexMap};
     use serde_json::Value;
     use std::path::PathBuf;
 
+    use crate::configs::Context;
     use crate::data::{AType, WsBuildData};
     use crate::error::BError;
     use crate::fs::ConfigFileReader;
     use crate::helper::Helper;
-    use crate::workspace::{WsArtifactsHandler, WsTaskHandler};
+    use crate::workspace::{WsArtifactsHandler, WsSettingsHandler, WsTaskHandler};
 
     #[test]
     fn test_ws_build_data_default() {
@@ -528,6 +607,72 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_ws_build_data_artifacts_layout() {
+        let json_settings: &str = r#"
+        {
+            "version": "5",
+            "workspace": {
+                "artifactslayout": "$#[YAAB_PLATFORM_RELEASE]/$#[YAAB_BUILD_VARIANT]"
+            }
+        }"#;
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let mut data: WsBuildData = Helper::setup_build_data(&work_dir, None, Some(json_settings));
+        assert_eq!(data.artifacts_dir(), PathBuf::from("/workspace/artifacts"));
+        match data.verify_artifacts_dir() {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid artifacts layout '$#[YAAB_PLATFORM_RELEASE]/$#[YAAB_BUILD_VARIANT]', expanded to '/dev' which has an empty path component. Make sure all context variables used by the layout are set!"
+                );
+            }
+        }
+        data.update_ctx(&Context::new(&indexmap! {
+            "YAAB_PLATFORM_RELEASE".to_string() => "1.0.0-20".to_string(),
+            "YAAB_BUILD_VARIANT".to_string() => "userdebug".to_string(),
+        }));
+        data.expand_ctx().expect("Failed to expand context");
+        assert_eq!(
+            data.artifacts_dir(),
+            PathBuf::from("/workspace/artifacts/1.0.0-20/userdebug")
+        );
+        assert!(data.verify_artifacts_dir().is_ok());
+        assert_eq!(
+            data.context().ctx().value("YAAB_ARTIFACTS_DIR"),
+            "/workspace/artifacts/1.0.0-20/userdebug"
+        );
+    }
+
+    #[test]
+    fn test_ws_build_data_artifacts_layout_error() {
+        let json_settings: &str = r#"
+        {
+            "version": "5",
+            "workspace": {
+                "artifactslayout": "../$#[YAAB_BUILD_VARIANT]"
+            }
+        }"#;
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let settings: WsSettingsHandler = WsSettingsHandler::from_str(&work_dir, json_settings)
+            .expect("Failed to parse settings");
+        let result: Result<WsBuildData, BError> =
+            WsBuildData::from_str(r#"{"version": "5"}"#, &settings);
+        match result {
+            Ok(_data) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid artifacts layout '../dev', must be a path inside the artifacts directory!"
+                );
+            }
+        }
+    }
+
     /*
     #[test]
     fn test_ws_build_data_built_in_ctx() {
@@ -0,0 +1,390 @@
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::fmt;
+
+use crate::configs::Config;
+use crate::configs::Context;
+use crate::data::context::CTX_KEY_DEPLOY_MODE;
+use crate::error::BError;
+
+pub const SLOTS: [&str; 4] = ["", "a", "b", "all"];
+
+#[derive(Clone, Eq, Hash, PartialEq, Debug)]
+pub enum DType {
+    Cmd,
+    Fastboot,
+    Adb,
+}
+
+impl fmt::Display for DType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dtype_str = match self {
+            DType::Cmd => "cmd",
+            DType::Fastboot => "fastboot",
+            DType::Adb => "adb",
+        };
+        write!(f, "{}", dtype_str)
+    }
+}
+
+pub struct WsDeployData {
+    mode: String, // The type of the deploy node, empty if the mode is picked from the context
+    dtype: DType, // The deploy mode, the default 'cmd' is running the cmd of the deploy node
+    device: String, // The serial of the device to deploy to default is the device from the cli
+    dir: String,  // The directory where images are looked up first default is the artifacts dir
+    product_out: String, // The product out directory where images are looked up if not in dir
+    images: IndexMap<String, String>, // The partitions and the images flashed to them in order
+    slot: String, // The A/B slot to flash, empty if the device is not using A/B slots
+    wipe: String, // Wipe userdata and cache before flashing
+    erase: Vec<String>, // The partitions erased before flashing
+    sync: Vec<String>, // The partitions synced from the product out dir only used by adb
+    apks: Vec<String>, // The apks installed only used by adb
+    reboot: String, // Reboot the device after deploying only used by adb
+}
+
+impl Config for WsDeployData {}
+
+impl WsDeployData {
+    #[cfg(test)]
+    pub fn from_str(json_string: &str) -> Result<Self, BError> {
+        let data: Value = Self::parse(json_string)?;
+        Self::from_value(&data)
+    }
+
+    pub fn from_value(data: &Value) -> Result<Self, BError> {
+        /*
+         * If there is no deploy node in the build config none of the keys
+         * should be read from the build config itself since the name sync is
+         * also used by the sync sub-command node of the build config.
+         */
+        let mut deploy_data: &Value = data;
+        if let Some(value) = deploy_data.get("deploy") {
+            deploy_data = value;
+        } else if deploy_data.get("version").is_some() {
+            deploy_data = &Value::Null;
+        }
+        Self::new(deploy_data)
+    }
+
+    pub fn new(data: &Value) -> Result<Self, BError> {
+        let mode: String = Self::get_str_value("type", data, Some(String::from("")))?;
+        let device: String =
+            Self::get_str_value("device", data, Some(String::from("$#[YAAB_DEVICE]")))?;
+        let dir: String =
+            Self::get_str_value("dir", data, Some(String::from("$#[YAAB_ARTIFACTS_DIR]")))?;
+        let product_out: String = Self::get_str_value(
+            "productout",
+            data,
+            Some(String::from(
+                "$#[YAAB_WORKSPACE_DIR]/out/target/product/$#[YAAB_PRODUCT_NAME]",
+            )),
+        )?;
+        let images: IndexMap<String, String> = Self::get_hashmap_value("images", data)?;
+        let slot: String = Self::get_str_value("slot", data, Some(String::from("")))?;
+        let wipe: String = Self::get_str_value("wipe", data, Some(String::from("false")))?;
+        let erase: Vec<String> = Self::get_array_value("erase", data, Some(vec![]))?;
+        let sync: Vec<String> = Self::get_array_value("sync", data, Some(vec![]))?;
+        let apks: Vec<String> = Self::get_array_value("apks", data, Some(vec![]))?;
+        let reboot: String = Self::get_str_value("reboot", data, Some(String::from("false")))?;
+
+        let dtype: DType = match mode.is_empty() {
+            true => DType::Cmd,
+            false => Self::parse_dtype(&mode)?,
+        };
+
+        let deploy_data: WsDeployData = WsDeployData {
+            mode,
+            dtype,
+            device,
+            dir,
+            product_out,
+            images,
+            slot,
+            wipe,
+            erase,
+            sync,
+            apks,
+            reboot,
+        };
+        deploy_data.validate()?;
+        Ok(deploy_data)
+    }
+
+    pub fn parse_dtype(dtype: &str) -> Result<DType, BError> {
+        match dtype {
+            "cmd" => Ok(DType::Cmd),
+            "fastboot" => Ok(DType::Fastboot),
+            "adb" => Ok(DType::Adb),
+            _ => Err(BError::ParseError(format!(
+                "Invalid 'deploy' node in build config. Invalid type '{}'",
+                dtype
+            ))),
+        }
+    }
+
+    fn validate(&self) -> Result<(), BError> {
+        if self.dtype == DType::Fastboot && self.images.is_empty() {
+            return Err(BError::ParseError(
+                "Invalid 'deploy' node in build config. The 'fastboot' type requires 'images'"
+                    .to_string(),
+            ));
+        }
+
+        if self.dtype == DType::Adb && self.sync.is_empty() && self.apks.is_empty() {
+            return Err(BError::ParseError(
+                "Invalid 'deploy' node in build config. The 'adb' type requires 'sync' or 'apks'"
+                    .to_string(),
+            ));
+        }
+
+        if !SLOTS.contains(&self.slot.as_str()) {
+            return Err(BError::ParseError(format!(
+                "Invalid 'deploy' node in build config. Invalid slot '{}', must be one of a, b, all",
+                self.slot
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn expand_ctx(&mut self, ctx: &Context) -> Result<(), BError> {
+        /*
+         * A deploy node without a type is using the deploy mode from the
+         * context which is set from the device inventory when deploying to
+         * a named device.
+         */
+        if self.mode.is_empty() {
+            let mode: String = ctx.value(CTX_KEY_DEPLOY_MODE);
+            if !mode.is_empty() {
+                self.dtype = Self::parse_dtype(&mode)?;
+                self.validate()?;
+            }
+        }
+        self.device = ctx.expand_str(&self.device)?;
+        self.dir = ctx.expand_str(&self.dir)?;
+        self.product_out = ctx.expand_str(&self.product_out)?;
+        for image in self.images.values_mut() {
+            *image = ctx.expand_str(image)?;
+        }
+        for apk in self.apks.iter_mut() {
+            *apk = ctx.expand_str(apk)?;
+        }
+        Ok(())
+    }
+
+    pub fn mode(&self) -> &String {
+        &self.mode
+    }
+
+    pub fn dtype(&self) -> &DType {
+        &self.dtype
+    }
+
+    pub fn device(&self) -> &String {
+        &self.device
+    }
+
+    pub fn dir(&self) -> &String {
+        &self.dir
+    }
+
+    pub fn product_out(&self) -> &String {
+        &self.product_out
+    }
+
+    pub fn images(&self) -> &IndexMap<String, String> {
+        &self.images
+    }
+
+    pub fn slot(&self) -> &String {
+        &self.slot
+    }
+
+    pub fn wipe(&self) -> bool {
+        self.wipe == "true"
+    }
+
+    pub fn erase(&self) -> &Vec<String> {
+        &self.erase
+    }
+
+    pub fn sync(&self) -> &Vec<String> {
+        &self.sync
+    }
+
+    pub fn apks(&self) -> &Vec<String> {
+        &self.apks
+    }
+
+    pub fn reboot(&self) -> bool {
+        self.reboot == "true"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::Context;
+    use crate::data::{DType, WsDeployData};
+    use indexmap::{indexmap, IndexMap};
+
+    #[test]
+    fn test_ws_deploy_data_default() {
+        let json_build_config = r#"
+        {
+            "cmd": "deploy.sh"
+        }"#;
+        let data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        assert_eq!(data.dtype(), &DType::Cmd);
+        assert_eq!(data.device(), "$#[YAAB_DEVICE]");
+        assert!(data.images().is_empty());
+        assert_eq!(data.slot(), "");
+        assert!(!data.wipe());
+    }
+
+    #[test]
+    fn test_ws_deploy_data_fastboot() {
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_WORKSPACE_DIR".to_string() => "/path/to/workspace".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_DEVICE".to_string() => "serial1".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        let json_build_config = r#"
+        {
+            "type": "fastboot",
+            "images": [
+                "boot=boot.img",
+                "system=$#[YAAB_PRODUCT_NAME]-system.img"
+            ],
+            "slot": "all",
+            "wipe": "true",
+            "erase": [
+                "misc"
+            ]
+        }"#;
+        let mut data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        data.expand_ctx(&ctx).unwrap();
+        assert_eq!(data.dtype(), &DType::Fastboot);
+        assert_eq!(data.device(), "serial1");
+        assert_eq!(data.dir(), "/path/to/artifacts");
+        assert_eq!(
+            data.product_out(),
+            "/path/to/workspace/out/target/product/product"
+        );
+        assert_eq!(
+            data.images(),
+            &indexmap! {
+                "boot".to_string() => "boot.img".to_string(),
+                "system".to_string() => "product-system.img".to_string(),
+            }
+        );
+        assert_eq!(data.slot(), "all");
+        assert!(data.wipe());
+        assert_eq!(data.erase(), &vec![String::from("misc")]);
+    }
+
+    #[test]
+    fn test_ws_deploy_data_invalid() {
+        let json_build_config = r#"
+        {
+            "type": "fastboot"
+        }"#;
+        assert!(WsDeployData::from_str(json_build_config).is_err());
+        let json_build_config = r#"
+        {
+            "type": "fastboot",
+            "images": [
+                "boot=boot.img"
+            ],
+            "slot": "c"
+        }"#;
+        assert!(WsDeployData::from_str(json_build_config).is_err());
+        let json_build_config = r#"
+        {
+            "type": "adb"
+        }"#;
+        assert!(WsDeployData::from_str(json_build_config).is_err());
+    }
+
+    #[test]
+    fn test_ws_deploy_data_adb() {
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_WORKSPACE_DIR".to_string() => "/path/to/workspace".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_DEVICE".to_string() => "serial1".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        let json_build_config = r#"
+        {
+            "type": "adb",
+            "sync": [
+                "system",
+                "vendor"
+            ],
+            "apks": [
+                "system/app/$#[YAAB_PRODUCT_NAME]/$#[YAAB_PRODUCT_NAME].apk"
+            ],
+            "reboot": "true"
+        }"#;
+        let mut data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        data.expand_ctx(&ctx).unwrap();
+        assert_eq!(data.dtype(), &DType::Adb);
+        assert_eq!(data.device(), "serial1");
+        assert_eq!(
+            data.sync(),
+            &vec![String::from("system"), String::from("vendor")]
+        );
+        assert_eq!(
+            data.apks(),
+            &vec![String::from("system/app/product/product.apk")]
+        );
+        assert!(data.reboot());
+    }
+
+    #[test]
+    fn test_ws_deploy_data_context_mode() {
+        let json_build_config = r#"
+        {
+            "images": [
+                "boot=boot.img"
+            ]
+        }"#;
+        let mut data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        assert_eq!(data.mode(), "");
+        assert_eq!(data.dtype(), &DType::Cmd);
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_WORKSPACE_DIR".to_string() => "/path/to/workspace".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_DEVICE".to_string() => "serial1".to_string(),
+            "YAAB_DEPLOY_MODE".to_string() => "fastboot".to_string(),
+        };
+        data.expand_ctx(&Context::new(&variables)).unwrap();
+        assert_eq!(data.dtype(), &DType::Fastboot);
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_WORKSPACE_DIR".to_string() => "/path/to/workspace".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_DEVICE".to_string() => "serial1".to_string(),
+            "YAAB_DEPLOY_MODE".to_string() => "adb".to_string(),
+        };
+        let mut data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        assert!(data.expand_ctx(&Context::new(&variables)).is_err());
+        let json_build_config = r#"
+        {
+            "type": "cmd",
+            "cmd": "deploy.sh"
+        }"#;
+        let mut data: WsDeployData =
+            WsDeployData::from_str(json_build_config).expect("Failed to parse deploy data");
+        data.expand_ctx(&Context::new(&variables)).unwrap();
+        assert_eq!(data.dtype(), &DType::Cmd);
+    }
+}
@@ -0,0 +1,136 @@
+use serde_json::Value;
+
+use crate::configs::Config;
+use crate::data::{DType, WsDeployData};
+use crate::error::BError;
+
+pub const TRANSPORTS: [&str; 2] = ["usb", "tcp"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WsDeviceData {
+    name: String,      // The name used to select the device with '--device'
+    serial: String,    // The adb serial of the device or host:port for the tcp transport
+    product: String,   // The product the device is running, empty if any product is accepted
+    transport: String, // How the device is connected either usb or tcp
+    mode: String,      // The deploy mode used if the deploy node is not setting a type
+}
+
+impl Config for WsDeviceData {}
+
+impl WsDeviceData {
+    #[cfg(test)]
+    pub fn from_str(json_string: &str) -> Result<Self, BError> {
+        let data: Value = Self::parse(json_string)?;
+        Self::new(&data)
+    }
+
+    pub fn new(data: &Value) -> Result<Self, BError> {
+        let name: String = Self::get_str_value("name", data, Some(String::from("")))?;
+        let serial: String = Self::get_str_value("serial", data, Some(String::from("")))?;
+        let product: String = Self::get_str_value("product", data, Some(String::from("")))?;
+        let transport: String = Self::get_str_value("transport", data, Some(String::from("usb")))?;
+        let mode: String = Self::get_str_value("deploy", data, Some(String::from("")))?;
+
+        if name.is_empty() || serial.is_empty() {
+            return Err(BError::ParseError(format!(
+                "Invalid 'devices' node in workspace config. The device '{}' requires a 'name' and 'serial'",
+                name
+            )));
+        }
+
+        if !TRANSPORTS.contains(&transport.as_str()) {
+            return Err(BError::ParseError(format!(
+                "Invalid 'devices' node in workspace config. Invalid transport '{}' for device '{}', must be one of usb, tcp",
+                transport, name
+            )));
+        }
+
+        if !mode.is_empty() {
+            WsDeployData::parse_dtype(&mode)?;
+        }
+
+        Ok(WsDeviceData {
+            name,
+            serial,
+            product,
+            transport,
+            mode,
+        })
+    }
+
+    /*
+     * The serial passed to the deploy tool. Fastboot over the network
+     * requires the serial to be prefixed with the protocol while adb is
+     * using the host:port as is once connected.
+     */
+    pub fn target(&self, dtype: &DType) -> String {
+        match (dtype, self.transport.as_str()) {
+            (DType::Fastboot, "tcp") => format!("tcp:{}", self.serial),
+            _ => self.serial.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn serial(&self) -> &String {
+        &self.serial
+    }
+
+    pub fn product(&self) -> &String {
+        &self.product
+    }
+
+    pub fn transport(&self) -> &String {
+        &self.transport
+    }
+
+    pub fn mode(&self) -> &String {
+        &self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{DType, WsDeviceData};
+
+    #[test]
+    fn test_ws_device_data() {
+        let json_device = r#"
+        {
+            "name": "lab-pixel-3",
+            "serial": "192.168.1.10:5555",
+            "product": "blueline",
+            "transport": "tcp",
+            "deploy": "fastboot"
+        }"#;
+        let data: WsDeviceData =
+            WsDeviceData::from_str(json_device).expect("Failed to parse device data");
+        assert_eq!(data.name(), "lab-pixel-3");
+        assert_eq!(data.serial(), "192.168.1.10:5555");
+        assert_eq!(data.product(), "blueline");
+        assert_eq!(data.transport(), "tcp");
+        assert_eq!(data.mode(), "fastboot");
+        assert_eq!(data.target(&DType::Fastboot), "tcp:192.168.1.10:5555");
+        assert_eq!(data.target(&DType::Adb), "192.168.1.10:5555");
+        let data: WsDeviceData = WsDeviceData::from_str(r#"{"name": "dev1", "serial": "serial1"}"#)
+            .expect("Failed to parse device data");
+        assert_eq!(data.transport(), "usb");
+        assert_eq!(data.mode(), "");
+        assert_eq!(data.target(&DType::Fastboot), "serial1");
+    }
+
+    #[test]
+    fn test_ws_device_data_invalid() {
+        assert!(WsDeviceData::from_str(r#"{"name": "dev1"}"#).is_err());
+        assert!(WsDeviceData::from_str(
+            r#"{"name": "dev1", "serial": "serial1", "transport": "serial"}"#
+        )
+        .is_err());
+        assert!(WsDeviceData::from_str(
+            r#"{"name": "dev1", "serial": "serial1", "deploy": "flash"}"#
+        )
+        .is_err());
+    }
+}
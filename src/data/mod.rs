@@ -3,15 +3,23 @@ pub mod config;
 pub mod context;
 pub mod customsubcmd;
 pub mod data;
+pub mod deploy;
+pub mod device;
 pub mod include;
 pub mod product;
 pub mod task;
+pub mod upload;
 
 pub use artifact::{AType, WsArtifactData};
 pub use config::WsConfigData;
-pub use context::{WsContextData, CTX_KEY_DEVICE, CTX_KEY_IMAGE};
+pub use context::{
+    WsContextData, CTX_KEY_DEPLOY_MODE, CTX_KEY_DEVICE, CTX_KEY_IMAGE, CTX_KEY_PRODUCT_NAME,
+};
 pub use customsubcmd::WsCustomSubCmdData;
 pub use data::WsBuildData;
+pub use deploy::{DType, WsDeployData};
+pub use device::WsDeviceData;
 pub use include::WsIncludeData;
 pub use product::WsProductData;
 pub use task::{TType, WsTaskData};
+pub use upload::{UType, WsUploadData};
@@ -6,6 +6,7 @@ use crate::configs::Config;
 use crate::configs::Context;
 use crate::data::WsBuildData;
 use crate::error::BError;
+use crate::executers::DockerResources;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum TType {
@@ -26,6 +27,7 @@ pub struct WsTaskData {
     init_env: PathBuf,
     build: String,
     docker: String,
+    resources: DockerResources,
     condition: String,
     clean: String,
     description: String,
@@ -51,6 +53,7 @@ impl WsTaskData {
         let disabled: String = Self::get_str_value("disabled", &data, Some(String::from("false")))?;
         let build_dir: String = Self::get_str_value("builddir", &data, Some(String::from("")))?;
         let docker: String = Self::get_str_value("docker", data, Some(String::from("")))?;
+        let resources: DockerResources = DockerResources::from_value(data)?;
         let condition: String = Self::get_str_value("condition", data, Some(String::from("true")))?;
         let build: String = Self::get_str_value("build", &data, Some(String::from("")))?;
         let clean: String = Self::get_str_value("clean", &data, Some(String::from("")))?;
@@ -93,6 +96,7 @@ impl WsTaskData {
             ttype: enum_ttype,
             disabled,
             docker,
+            resources,
             condition,
             build_dir: build_dir_path,
             build,
@@ -112,6 +116,7 @@ impl WsTaskData {
         self.condition = ctx.expand_str(&self.condition)?;
         self.disabled = ctx.expand_str(&self.disabled)?;
         self.description = ctx.expand_str(&self.description)?;
+        self.resources.expand_ctx(ctx)?;
         for (_key, value) in self.env.iter_mut() {
             *value = ctx.expand_str(value)?;
         }
@@ -145,6 +150,10 @@ impl WsTaskData {
         &self.docker
     }
 
+    pub fn docker_resources(&self) -> &DockerResources {
+        &self.resources
+    }
+
     pub fn condition(&self) -> bool {
         let condition: &str = &self.condition;
 
@@ -472,4 +481,32 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_ws_task_data_resources() {
+        let json_task_config: &str = r#"
+        {
+            "index": "0",
+            "name": "task1-name",
+            "docker": "test-registry/test-image:0.1",
+            "resources": {
+                "memory": "64g",
+                "cpus": "$#[CPUS]",
+                "devices": [
+                    "/dev/bus/usb"
+                ]
+            }
+        }"#;
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let data: Value = Helper::parse(json_task_config).expect("Failed to parse task config");
+        let mut task: WsTaskData = WsTaskData::new(&data, &work_dir).expect("Failed to parse task");
+        let variables: IndexMap<String, String> = indexmap! {
+            "CPUS".to_string() => "16".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        task.expand_ctx(&ctx).expect("Failed to expand context");
+        assert_eq!(task.docker_resources().memory, "64g");
+        assert_eq!(task.docker_resources().cpus, "16");
+        assert_eq!(task.docker_resources().devices, vec!["/dev/bus/usb"]);
+    }
 }
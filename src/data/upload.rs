@@ -0,0 +1,439 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::configs::Config;
+use crate::configs::Context;
+use crate::error::BError;
+
+pub const CHECKSUMS: [&str; 3] = ["sha256", "sha1", "md5"];
+
+#[derive(Clone, Eq, Hash, PartialEq, Debug)]
+pub enum UType {
+    Cmd,
+    Http,
+    S3,
+    Oci,
+}
+
+impl fmt::Display for UType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let utype_str = match self {
+            UType::Cmd => "cmd",
+            UType::Http => "http",
+            UType::S3 => "s3",
+            UType::Oci => "oci",
+        };
+        write!(f, "{}", utype_str)
+    }
+}
+
+pub struct WsUploadData {
+    utype: UType, // The upload backend, the default 'cmd' is running the cmd of the upload node
+    url: String, // The base url of the server, the endpoint if the type is s3 or the registry if the type is oci
+    path: String, // The path template added to the base url or the key prefix if the type is s3
+    dir: String, // The directory to upload default is the artifacts dir
+    checksums: Vec<String>, // The checksums sent as checksum headers for every uploaded file
+    retries: u32, // The number of retries before giving up on a file
+    backoff: u32, // The initial backoff in seconds doubled for every retry
+    user_env: String, // The env variable holding the user for basic auth
+    password_env: String, // The env variable holding the password for basic auth
+    token_env: String, // The env variable holding a bearer token
+    netrc: String, // The netrc file used if no credentials are found in the env
+    bucket: String, // The bucket only used if the type is s3
+    region: String, // The region only used if the type is s3
+    part_size: String, // The part size of multipart uploads only used if the type is s3
+    access_key_env: String, // The env variable holding the access key only used if the type is s3
+    secret_key_env: String, // The env variable holding the secret key only used if the type is s3
+    repository: String, // The repository only used if the type is oci
+    tags: Vec<String>, // The tags pushed only used if the type is oci
+    artifact_type: String, // The artifact type of the manifest only used if the type is oci
+    docker_config: String, // The docker config file holding the registry credentials only used if the type is oci
+}
+
+impl Config for WsUploadData {}
+
+impl WsUploadData {
+    #[cfg(test)]
+    pub fn from_str(json_string: &str) -> Result<Self, BError> {
+        let data: Value = Self::parse(json_string)?;
+        Self::from_value(&data)
+    }
+
+    pub fn from_value(data: &Value) -> Result<Self, BError> {
+        let mut upload_data: &Value = data;
+        if let Some(value) = upload_data.get("upload") {
+            upload_data = value;
+        }
+        Self::new(upload_data)
+    }
+
+    pub fn new(data: &Value) -> Result<Self, BError> {
+        let utype: String = Self::get_str_value("type", data, Some(String::from("cmd")))?;
+        let url: String = Self::get_str_value("url", data, Some(String::from("")))?;
+        let path: String = Self::get_str_value("path", data, Some(String::from("")))?;
+        let dir: String =
+            Self::get_str_value("dir", data, Some(String::from("$#[YAAB_ARTIFACTS_DIR]")))?;
+        let checksums: Vec<String> =
+            Self::get_array_value("checksums", data, Some(vec![String::from("sha256")]))?;
+        let retries: u32 = Self::get_u32_value("retries", data, Some(3))?;
+        let backoff: u32 = Self::get_u32_value("backoff", data, Some(1))?;
+        let bucket: String = Self::get_str_value("bucket", data, Some(String::from("")))?;
+        let region: String = Self::get_str_value("region", data, Some(String::from("us-east-1")))?;
+        let part_size: String = Self::get_str_value("partsize", data, Some(String::from("64M")))?;
+        let repository: String = Self::get_str_value("repository", data, Some(String::from("")))?;
+        let tags: Vec<String> =
+            Self::get_array_value("tags", data, Some(vec![String::from("latest")]))?;
+        let artifact_type: String = Self::get_str_value(
+            "artifacttype",
+            data,
+            Some(String::from("application/vnd.yaab.build.v1")),
+        )?;
+        let mut user_env: String = String::new();
+        let mut password_env: String = String::new();
+        let mut token_env: String = String::new();
+        let mut netrc: String = String::new();
+        let mut docker_config: String = String::new();
+        let mut access_key_env: String = String::from("AWS_ACCESS_KEY_ID");
+        let mut secret_key_env: String = String::from("AWS_SECRET_ACCESS_KEY");
+        if let Ok(auth_data) = Self::get_value("auth", data) {
+            user_env = Self::get_str_value("userenv", auth_data, Some(String::from("")))?;
+            password_env = Self::get_str_value("passwordenv", auth_data, Some(String::from("")))?;
+            token_env = Self::get_str_value("tokenenv", auth_data, Some(String::from("")))?;
+            netrc = Self::get_str_value("netrc", auth_data, Some(String::from("")))?;
+            access_key_env = Self::get_str_value("accesskeyenv", auth_data, Some(access_key_env))?;
+            secret_key_env = Self::get_str_value("secretkeyenv", auth_data, Some(secret_key_env))?;
+            docker_config = Self::get_str_value("dockerconfig", auth_data, Some(String::from("")))?;
+        }
+
+        let enum_utype: UType = match utype.as_str() {
+            "cmd" => UType::Cmd,
+            "http" => UType::Http,
+            "s3" => UType::S3,
+            "oci" => UType::Oci,
+            _ => {
+                return Err(BError::ParseError(format!(
+                    "Invalid 'upload' node in build config. Invalid type '{}'",
+                    utype
+                )));
+            }
+        };
+
+        if enum_utype == UType::Http && url.is_empty() {
+            return Err(BError::ParseError(
+                "Invalid 'upload' node in build config. The 'http' type requires a 'url'"
+                    .to_string(),
+            ));
+        }
+
+        if enum_utype == UType::S3 && (url.is_empty() || bucket.is_empty()) {
+            return Err(BError::ParseError(
+                "Invalid 'upload' node in build config. The 's3' type requires a 'url' and 'bucket'"
+                    .to_string(),
+            ));
+        }
+
+        if enum_utype == UType::S3 && checksums.iter().any(|c| c == "sha1") {
+            return Err(BError::ParseError(
+                "Invalid 'upload' node in build config. The 's3' type only supports the checksums md5 and sha256"
+                    .to_string(),
+            ));
+        }
+
+        if enum_utype == UType::Oci && (url.is_empty() || repository.is_empty()) {
+            return Err(BError::ParseError(
+                "Invalid 'upload' node in build config. The 'oci' type requires a 'url' and 'repository'"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(checksum) = checksums.iter().find(|c| !CHECKSUMS.contains(&c.as_str())) {
+            return Err(BError::ParseError(format!(
+                "Invalid 'upload' node in build config. Invalid checksum '{}', must be one of {}",
+                checksum,
+                CHECKSUMS.join(", ")
+            )));
+        }
+
+        Ok(WsUploadData {
+            utype: enum_utype,
+            url,
+            path,
+            dir,
+            checksums,
+            retries,
+            backoff,
+            user_env,
+            password_env,
+            token_env,
+            netrc,
+            bucket,
+            region,
+            part_size,
+            access_key_env,
+            secret_key_env,
+            repository,
+            tags,
+            artifact_type,
+            docker_config,
+        })
+    }
+
+    pub fn expand_ctx(&mut self, ctx: &Context) -> Result<(), BError> {
+        self.url = ctx.expand_str(&self.url)?;
+        self.path = ctx.expand_str(&self.path)?;
+        self.dir = ctx.expand_str(&self.dir)?;
+        self.netrc = ctx.expand_str(&self.netrc)?;
+        self.bucket = ctx.expand_str(&self.bucket)?;
+        self.region = ctx.expand_str(&self.region)?;
+        self.part_size = ctx.expand_str(&self.part_size)?;
+        self.repository = ctx.expand_str(&self.repository)?;
+        self.docker_config = ctx.expand_str(&self.docker_config)?;
+        self.tags = self
+            .tags
+            .iter()
+            .map(|tag| ctx.expand_str(tag))
+            .collect::<Result<Vec<String>, BError>>()?;
+        Ok(())
+    }
+
+    pub fn utype(&self) -> &UType {
+        &self.utype
+    }
+
+    pub fn url(&self) -> &String {
+        &self.url
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
+    pub fn dir(&self) -> &String {
+        &self.dir
+    }
+
+    pub fn checksums(&self) -> &Vec<String> {
+        &self.checksums
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn backoff(&self) -> u32 {
+        self.backoff
+    }
+
+    pub fn user_env(&self) -> &String {
+        &self.user_env
+    }
+
+    pub fn password_env(&self) -> &String {
+        &self.password_env
+    }
+
+    pub fn token_env(&self) -> &String {
+        &self.token_env
+    }
+
+    pub fn netrc(&self) -> &String {
+        &self.netrc
+    }
+
+    pub fn bucket(&self) -> &String {
+        &self.bucket
+    }
+
+    pub fn region(&self) -> &String {
+        &self.region
+    }
+
+    pub fn part_size(&self) -> &String {
+        &self.part_size
+    }
+
+    pub fn access_key_env(&self) -> &String {
+        &self.access_key_env
+    }
+
+    pub fn secret_key_env(&self) -> &String {
+        &self.secret_key_env
+    }
+
+    pub fn repository(&self) -> &String {
+        &self.repository
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn artifact_type(&self) -> &String {
+        &self.artifact_type
+    }
+
+    pub fn docker_config(&self) -> &String {
+        &self.docker_config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::Context;
+    use crate::data::{UType, WsUploadData};
+    use indexmap::{indexmap, IndexMap};
+
+    #[test]
+    fn test_ws_upload_data_default() {
+        let json_build_config = r#"
+        {
+            "cmd": "upload.sh"
+        }"#;
+        let data: WsUploadData =
+            WsUploadData::from_str(json_build_config).expect("Failed to parse upload data");
+        assert_eq!(data.utype(), &UType::Cmd);
+        assert_eq!(data.dir(), "$#[YAAB_ARTIFACTS_DIR]");
+        assert_eq!(data.checksums(), &vec![String::from("sha256")]);
+        assert_eq!(data.retries(), 3);
+        assert_eq!(data.backoff(), 1);
+    }
+
+    #[test]
+    fn test_ws_upload_data_http() {
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_BUILD_ID".to_string() => "42".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        let json_build_config = r#"
+        {
+            "type": "http",
+            "url": "https://artifactory.example.com/artifactory/builds",
+            "path": "$#[YAAB_PRODUCT_NAME]/$#[YAAB_BUILD_ID]",
+            "checksums": [
+                "sha256",
+                "md5"
+            ],
+            "retries": "5",
+            "backoff": "2",
+            "auth": {
+                "userenv": "UPLOAD_USER",
+                "passwordenv": "UPLOAD_PASSWORD",
+                "netrc": "/path/to/netrc"
+            }
+        }"#;
+        let mut data: WsUploadData =
+            WsUploadData::from_str(json_build_config).expect("Failed to parse upload data");
+        data.expand_ctx(&ctx).unwrap();
+        assert_eq!(data.utype(), &UType::Http);
+        assert_eq!(
+            data.url(),
+            "https://artifactory.example.com/artifactory/builds"
+        );
+        assert_eq!(data.path(), "product/42");
+        assert_eq!(data.dir(), "/path/to/artifacts");
+        assert_eq!(
+            data.checksums(),
+            &vec![String::from("sha256"), String::from("md5")]
+        );
+        assert_eq!(data.retries(), 5);
+        assert_eq!(data.backoff(), 2);
+        assert_eq!(data.user_env(), "UPLOAD_USER");
+        assert_eq!(data.password_env(), "UPLOAD_PASSWORD");
+        assert_eq!(data.token_env(), "");
+        assert_eq!(data.netrc(), "/path/to/netrc");
+    }
+
+    #[test]
+    fn test_ws_upload_data_invalid() {
+        let json_build_config = r#"
+        {
+            "type": "http"
+        }"#;
+        assert!(WsUploadData::from_str(json_build_config).is_err());
+        let json_build_config = r#"
+        {
+            "type": "http",
+            "url": "http://localhost",
+            "checksums": [
+                "crc32"
+            ]
+        }"#;
+        assert!(WsUploadData::from_str(json_build_config).is_err());
+    }
+
+    #[test]
+    fn test_ws_upload_data_s3() {
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        let json_build_config = r#"
+        {
+            "type": "s3",
+            "url": "http://minio.example.com:9000",
+            "bucket": "builds",
+            "path": "$#[YAAB_PRODUCT_NAME]",
+            "partsize": "16M",
+            "auth": {
+                "accesskeyenv": "MINIO_ACCESS_KEY"
+            }
+        }"#;
+        let mut data: WsUploadData =
+            WsUploadData::from_str(json_build_config).expect("Failed to parse upload data");
+        data.expand_ctx(&ctx).unwrap();
+        assert_eq!(data.utype(), &UType::S3);
+        assert_eq!(data.bucket(), "builds");
+        assert_eq!(data.path(), "product");
+        assert_eq!(data.region(), "us-east-1");
+        assert_eq!(data.part_size(), "16M");
+        assert_eq!(data.access_key_env(), "MINIO_ACCESS_KEY");
+        assert_eq!(data.secret_key_env(), "AWS_SECRET_ACCESS_KEY");
+        let json_build_config = r#"
+        {
+            "type": "s3",
+            "url": "http://minio.example.com:9000"
+        }"#;
+        assert!(WsUploadData::from_str(json_build_config).is_err());
+    }
+
+    #[test]
+    fn test_ws_upload_data_oci() {
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => "/path/to/artifacts".to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_PLATFORM_RELEASE".to_string() => "1.0.0-20".to_string(),
+        };
+        let ctx: Context = Context::new(&variables);
+        let json_build_config = r#"
+        {
+            "type": "oci",
+            "url": "http://localhost:5000",
+            "repository": "builds/$#[YAAB_PRODUCT_NAME]",
+            "tags": [
+                "$#[YAAB_PLATFORM_RELEASE]",
+                "latest"
+            ]
+        }"#;
+        let mut data: WsUploadData =
+            WsUploadData::from_str(json_build_config).expect("Failed to parse upload data");
+        data.expand_ctx(&ctx).unwrap();
+        assert_eq!(data.utype(), &UType::Oci);
+        assert_eq!(data.url(), "http://localhost:5000");
+        assert_eq!(data.repository(), "builds/product");
+        assert_eq!(
+            data.tags(),
+            &vec![String::from("1.0.0-20"), String::from("latest")]
+        );
+        assert_eq!(data.artifact_type(), "application/vnd.yaab.build.v1");
+        assert_eq!(data.docker_config(), "");
+        let json_build_config = r#"
+        {
+            "type": "oci",
+            "url": "http://localhost:5000"
+        }"#;
+        assert!(WsUploadData::from_str(json_build_config).is_err());
+    }
+}
@@ -37,6 +37,18 @@ pub enum BError {
     DockerError(),
     #[error("{0}")]
     DockerImageError(String),
+    #[error("Apptainer is enabled for the workspace but cannot locate '{0}'")]
+    ApptainerError(String),
+    #[error("{0}")]
+    DockerMountError(String),
+    #[error("Interrupted")]
+    Interrupted,
+    #[error("{0}")]
+    HandshakeError(String),
+    #[error("{0}")]
+    UploadError(String),
+    #[error("{0}")]
+    DeployError(String),
 }
 
 impl std::convert::From<serde_json::Error> for BError {
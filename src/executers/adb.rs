@@ -0,0 +1,336 @@
+use crate::cli::Cli;
+use crate::data::WsDeployData;
+use crate::error::BError;
+use crate::executers::fastboot::resolve_images;
+use crate::executers::TaskExecuter;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub struct AdbDeployExecuter<'a> {
+    cli: &'a Cli,
+    data: &'a WsDeployData,
+}
+
+impl<'a> TaskExecuter for AdbDeployExecuter<'a> {
+    fn exec(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dry_run: bool,
+        _interactive: bool,
+    ) -> Result<(), BError> {
+        let apk: String = String::from("apk");
+        let apks: Vec<(String, PathBuf)> =
+            resolve_images(self.data, self.data.apks().iter().map(|a| (&apk, a)))?;
+        let product_out: PathBuf = PathBuf::from(self.data.product_out());
+        if !self.data.sync().is_empty() && !product_out.is_dir() {
+            return Err(BError::DeployError(format!(
+                "Product out directory '{}' does not exist",
+                product_out.display()
+            )));
+        }
+
+        if dry_run {
+            for partition in self.data.sync().iter() {
+                self.cli.info(format!(
+                    "Dry run. Skipping sync of partition '{}' from '{}'",
+                    partition,
+                    product_out.display()
+                ));
+            }
+            for (_, path) in apks.iter() {
+                self.cli
+                    .info(format!("Dry run. Skipping install of '{}'", path.display()));
+            }
+            return Ok(());
+        }
+
+        self.verify_attached(env_variables)?;
+
+        /*
+         * Syncing requires adbd running as root and the partitions remounted
+         * read-write which is only possible on userdebug and eng builds.
+         */
+        if !self.data.sync().is_empty() {
+            self.verify_debuggable(env_variables)?;
+            self.adb(&["root"], env_variables)?;
+            self.adb(&["wait-for-device"], env_variables)?;
+            self.verify_rooted(env_variables)?;
+            self.adb(&["remount"], env_variables)?;
+
+            let mut env: HashMap<String, String> = env_variables.clone();
+            env.insert(
+                String::from("ANDROID_PRODUCT_OUT"),
+                product_out.to_string_lossy().to_string(),
+            );
+            for partition in self.data.sync().iter() {
+                self.adb(&["sync", partition], &env).map_err(|err| {
+                    BError::DeployError(format!("Failed to sync '{}': {}", partition, err))
+                })?;
+                self.cli.info(format!("{}: synced", partition));
+            }
+        }
+
+        for (_, path) in apks.iter() {
+            let apk: String = path.to_string_lossy().to_string();
+            self.adb(&["install", "-r", &apk], env_variables)
+                .map_err(|err| {
+                    BError::DeployError(format!("Failed to install '{}': {}", apk, err))
+                })?;
+            self.cli.info(format!("{}: installed", apk));
+        }
+
+        if self.data.reboot() {
+            self.adb(&["reboot"], env_variables)?;
+        }
+
+        self.cli.info(format!(
+            "Deployed {} partition(s) and {} apk(s) to '{}'",
+            self.data.sync().len(),
+            apks.len(),
+            self.device()
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> AdbDeployExecuter<'a> {
+    pub fn new(cli: &'a Cli, data: &'a WsDeployData) -> Self {
+        AdbDeployExecuter { cli, data }
+    }
+
+    fn device(&self) -> &str {
+        match self.data.device().is_empty() {
+            true => "default device",
+            false => self.data.device(),
+        }
+    }
+
+    fn cmd(&self, args: &[&str]) -> Vec<String> {
+        let mut cmd: Vec<String> = vec![String::from("adb")];
+        if !self.data.device().is_empty() {
+            cmd.push(String::from("-s"));
+            cmd.push(self.data.device().clone());
+        }
+        cmd.extend(args.iter().map(|a| a.to_string()));
+        cmd
+    }
+
+    /*
+     * Run adb with the serial of the device if one is selected. If no device
+     * is selected adb will pick the only device attached.
+     */
+    fn adb(&self, args: &[&str], env_variables: &HashMap<String, String>) -> Result<(), BError> {
+        self.cli.check_call(&self.cmd(args), env_variables, true)
+    }
+
+    /*
+     * Run adb capturing the output which is needed to query the state of
+     * the device. If adb fails the error is returned as the output so it can
+     * be reported together with the state that was expected.
+     */
+    fn adb_output(
+        &self,
+        args: &[&str],
+        env_variables: &HashMap<String, String>,
+    ) -> Result<(bool, String), BError> {
+        match self.cli.check_output(&self.cmd(args), env_variables, true) {
+            Ok(output) => Ok((true, output.trim().to_string())),
+            Err(BError::Interrupted) => Err(BError::Interrupted),
+            Err(err) => Ok((false, err.to_string())),
+        }
+    }
+
+    fn verify_attached(&self, env_variables: &HashMap<String, String>) -> Result<(), BError> {
+        let (success, state) = self.adb_output(&["get-state"], env_variables)?;
+        if !success || state != "device" {
+            return Err(BError::DeployError(format!(
+                "Device '{}' is not attached ({}), check 'adb devices'",
+                self.device(),
+                state
+            )));
+        }
+        Ok(())
+    }
+
+    /*
+     * A user build is not debuggable and 'adb root' is then not restarting
+     * adbd as root so the device is checked before trying.
+     */
+    fn verify_debuggable(&self, env_variables: &HashMap<String, String>) -> Result<(), BError> {
+        let (success, debuggable) =
+            self.adb_output(&["shell", "getprop", "ro.debuggable"], env_variables)?;
+        if !success || debuggable != "1" {
+            return Err(BError::DeployError(format!(
+                "Device '{}' is not rooted, it is running a user build (ro.debuggable={}) and 'adb root' is only supported on userdebug and eng builds",
+                self.device(),
+                debuggable
+            )));
+        }
+        Ok(())
+    }
+
+    fn verify_rooted(&self, env_variables: &HashMap<String, String>) -> Result<(), BError> {
+        let (success, uid) = self.adb_output(&["shell", "id", "-u"], env_variables)?;
+        if !success || uid != "0" {
+            return Err(BError::DeployError(format!(
+                "Device '{}' is not rooted, 'adb root' is only supported on userdebug and eng builds",
+                self.device()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use crate::data::WsDeployData;
+    use crate::executers::{AdbDeployExecuter, TaskExecuter};
+    use crate::helper::Helper;
+
+    use std::collections::HashMap;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use tempdir::TempDir;
+
+    /*
+     * Fake adb logging every call to a file next to it. The state of the
+     * device is controlled by ADB_STATE, ADB_DEBUGGABLE and ADB_UID.
+     */
+    fn helper_fake_adb(bin_dir: &Path) -> PathBuf {
+        let log: PathBuf = bin_dir.join("adb.log");
+        let adb: PathBuf = bin_dir.join("adb");
+        std::fs::create_dir_all(bin_dir).expect("Failed to create dir");
+        std::fs::write(
+            &adb,
+            format!(
+                r#"#!/bin/sh
+echo "$@" >> {log}
+case "$*" in
+    *get-state*)
+        [ -n "$ADB_STATE" ] || {{ echo "error: no devices/emulators found" >&2; exit 1; }}
+        echo "$ADB_STATE";;
+    *"shell getprop ro.debuggable"*)
+        echo "${{ADB_DEBUGGABLE:-1}}";;
+    *"shell id -u"*)
+        echo "$ADB_UID";;
+    *" sync "*)
+        echo "ANDROID_PRODUCT_OUT=$ANDROID_PRODUCT_OUT" >> {log};;
+esac
+"#,
+                log = log.display()
+            ),
+        )
+        .expect("Failed to write file");
+        std::fs::set_permissions(&adb, std::fs::Permissions::from_mode(0o755))
+            .expect("Failed to set permissions");
+        log
+    }
+
+    fn helper_adb_data(work_dir: &Path) -> WsDeployData {
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "adb",
+            "device": "serial1",
+            "dir": "{}",
+            "productout": "{}",
+            "sync": [
+                "system",
+                "vendor"
+            ],
+            "apks": [
+                "system/app/Test/Test.apk"
+            ],
+            "reboot": "true"
+        }}"#,
+            work_dir.join("artifacts").display(),
+            work_dir.join("out").display()
+        );
+        WsDeployData::from_str(&json_build_config).expect("Failed to parse deploy data")
+    }
+
+    #[test]
+    fn test_adb_deploy_executer() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        Helper::create_test_files(&vec![work_dir.join("out/system/app/Test/Test.apk")]);
+        let log: PathBuf = helper_fake_adb(&work_dir.join("bin"));
+        let data: WsDeployData = helper_adb_data(&work_dir);
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(BSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let env: HashMap<String, String> = HashMap::from([
+            (
+                String::from("PATH"),
+                format!("{}:/usr/bin:/bin", work_dir.join("bin").display()),
+            ),
+            (String::from("ADB_STATE"), String::from("device")),
+            (String::from("ADB_UID"), String::from("0")),
+        ]);
+        let executer: AdbDeployExecuter = AdbDeployExecuter::new(&cli, &data);
+        executer.exec(&env, false, true).expect("Failed to deploy");
+        let out: String = work_dir.join("out").display().to_string();
+        assert_eq!(
+            std::fs::read_to_string(&log).expect("Failed to read file"),
+            format!(
+                "-s serial1 get-state\n-s serial1 shell getprop ro.debuggable\n-s serial1 root\n-s serial1 wait-for-device\n-s serial1 shell id -u\n-s serial1 remount\n-s serial1 sync system\nANDROID_PRODUCT_OUT={}\n-s serial1 sync vendor\nANDROID_PRODUCT_OUT={}\n-s serial1 install -r {}/system/app/Test/Test.apk\n-s serial1 reboot\n",
+                out, out, out
+            )
+        );
+    }
+
+    #[test]
+    fn test_adb_deploy_executer_not_attached() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        Helper::create_test_files(&vec![work_dir.join("out/system/app/Test/Test.apk")]);
+        let log: PathBuf = helper_fake_adb(&work_dir.join("bin"));
+        let data: WsDeployData = helper_adb_data(&work_dir);
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(BSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let mut env: HashMap<String, String> = HashMap::from([(
+            String::from("PATH"),
+            format!("{}:/usr/bin:/bin", work_dir.join("bin").display()),
+        )]);
+        let executer: AdbDeployExecuter = AdbDeployExecuter::new(&cli, &data);
+        match executer.exec(&env, false, true) {
+            Ok(_) => panic!("We should have recived an error because the device is not attached"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Device 'serial1' is not attached (exit status: 1: error: no devices/emulators found), check 'adb devices'"
+            ),
+        }
+        env.insert(String::from("ADB_STATE"), String::from("device"));
+        env.insert(String::from("ADB_DEBUGGABLE"), String::from("0"));
+        match executer.exec(&env, false, true) {
+            Ok(_) => panic!("We should have recived an error because the device is a user build"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Device 'serial1' is not rooted, it is running a user build (ro.debuggable=0) and 'adb root' is only supported on userdebug and eng builds"
+            ),
+        }
+        assert!(!std::fs::read_to_string(&log)
+            .expect("Failed to read file")
+            .contains("root"));
+        env.insert(String::from("ADB_DEBUGGABLE"), String::from("1"));
+        env.insert(String::from("ADB_UID"), String::from("2000"));
+        match executer.exec(&env, false, true) {
+            Ok(_) => panic!("We should have recived an error because the device is not rooted"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Device 'serial1' is not rooted, 'adb root' is only supported on userdebug and eng builds"
+            ),
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+use crate::cli::Cli;
+use crate::error::BError;
+use crate::executers::{BootstrapParams, ContainerExecuter, DockerMounts};
+
+/*
+ * Apptainer is already binding the home directory, /tmp and the current
+ * directory into the container and is running as the user so there are
+ * no default mounts needed. Any mount added in the workspace config is
+ * still honored.
+ */
+pub const APPTAINER_MOUNTS: [&str; 0] = [];
+
+/*
+ * Container executer using Apptainer, previously known as Singularity, for
+ * hosts where docker is not available. The image can either be a SIF file
+ * or any reference supported by apptainer like a docker:// reference.
+ */
+pub struct Apptainer {
+    runtime: String,
+    image: String,
+    _interactive: bool,
+    mounts: DockerMounts,
+}
+
+impl Apptainer {
+    fn env_home(&self) -> String {
+        std::env::var("HOME").unwrap_or_default()
+    }
+
+    fn ssh_auth_sock(&self) -> String {
+        std::env::var("SSH_AUTH_SOCK").unwrap_or_default()
+    }
+
+    fn binds(&self) -> Vec<String> {
+        /*
+         * The mounts and the forwarded sockets are verified before the
+         * container is started so any error here would already have been
+         * reported
+         */
+        let mut args: Vec<String> = self
+            .mounts
+            .resolve(&APPTAINER_MOUNTS, &self.env_home())
+            .unwrap_or_default();
+        args.append(
+            &mut self
+                .mounts
+                .forward(&self.env_home(), &self.ssh_auth_sock())
+                .unwrap_or_default(),
+        );
+        Self::apptainer_args(&args)
+    }
+
+    /*
+     * Translate the docker volume and env args from the mounts into the
+     * apptainer args. No tmpfs masks are created since there are no default
+     * apptainer mounts that could be a parent of ~/.ssh.
+     */
+    fn apptainer_args(args: &[String]) -> Vec<String> {
+        args.chunks(2)
+            .filter(|a| a.len() == 2)
+            .flat_map(|a| match a[0].as_str() {
+                "-v" => vec![String::from("--bind"), a[1].clone()],
+                "-e" => vec![String::from("--env"), a[1].clone()],
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    pub fn verify_mounts(&self) -> Result<(), BError> {
+        self.mounts.resolve(&APPTAINER_MOUNTS, &self.env_home())?;
+        self.mounts
+            .forward(&self.env_home(), &self.ssh_auth_sock())?;
+        Ok(())
+    }
+
+    fn volumes(&self, volumes: &[String]) -> Vec<String> {
+        let mut v: Vec<String> = Vec::new();
+        volumes.iter().for_each(|e| {
+            v.append(&mut vec![String::from("--bind"), e.to_string()]);
+        });
+        v.append(&mut self.binds());
+        v
+    }
+
+    fn work_dir(&self, dir: &Path) -> Vec<String> {
+        vec![String::from("--pwd"), format!("{}", dir.display())]
+    }
+
+    fn top_dir(&self, dir: &Path) -> Vec<String> {
+        vec![
+            String::from("--bind"),
+            format!("{}:{}", dir.display(), dir.display()),
+        ]
+    }
+
+    fn env_file(&self, env_file: &Path) -> Vec<String> {
+        vec![
+            String::from("--env-file"),
+            env_file.to_string_lossy().to_string(),
+        ]
+    }
+
+    fn find_runtime(&self) -> Result<PathBuf, BError> {
+        let path: String = std::env::var("PATH").unwrap_or_default();
+        for dir in path.split(':') {
+            let runtime: PathBuf = PathBuf::from(dir).join(&self.runtime);
+            if runtime.exists() {
+                return Ok(runtime);
+            }
+        }
+        Err(BError::ApptainerError(self.runtime.clone()))
+    }
+
+    pub fn inside_apptainer() -> bool {
+        std::env::var_os("APPTAINER_CONTAINER").is_some()
+            || std::env::var_os("SINGULARITY_CONTAINER").is_some()
+    }
+
+    pub fn new(runtime: &str, image: &str, interactive: bool) -> Self {
+        Apptainer {
+            runtime: runtime.to_string(),
+            image: image.to_string(),
+            _interactive: interactive,
+            mounts: DockerMounts::default(),
+        }
+    }
+
+    pub fn image(&self) -> &str {
+        &self.image
+    }
+
+    pub fn set_mounts(&mut self, mounts: DockerMounts) {
+        self.mounts = mounts;
+    }
+
+    pub fn bootstrap_cmd_line(
+        &self,
+        cmd_line: &[String],
+        top_dir: &Path,
+        work_dir: &Path,
+        volumes: &[String],
+    ) -> Vec<String> {
+        let mut apptainer_cmd: Vec<String> = vec![self.runtime.clone(), String::from("exec")];
+        apptainer_cmd.append(&mut self.volumes(volumes));
+        apptainer_cmd.append(&mut self.top_dir(top_dir));
+        apptainer_cmd.append(&mut self.work_dir(work_dir));
+        apptainer_cmd.push(self.image.clone());
+        apptainer_cmd.extend_from_slice(cmd_line);
+        apptainer_cmd
+    }
+
+    pub fn cmd_line(&self, cmd_line: &[String], env_file: &Path, dir: &Path) -> Vec<String> {
+        let mut apptainer_cmd: Vec<String> = vec![
+            self.runtime.clone(),
+            String::from("exec"),
+            String::from("--cleanenv"),
+        ];
+        apptainer_cmd.append(&mut self.env_file(env_file));
+        apptainer_cmd.append(&mut self.binds());
+        apptainer_cmd.append(&mut self.work_dir(dir));
+        apptainer_cmd.push(self.image.clone());
+        apptainer_cmd.extend_from_slice(cmd_line);
+        apptainer_cmd
+    }
+
+    /*
+     * The apptainer env file is sourced by a shell inside the container so
+     * unlike the docker env file the values has to be quoted.
+     */
+    pub fn setup_env_file(
+        &self,
+        temp_dir: &Path,
+        env: &HashMap<String, String>,
+    ) -> Result<PathBuf, BError> {
+        let env_file_path: PathBuf = PathBuf::from(temp_dir).join("yaab-apptainer.env");
+        let mut env_file: File = File::create(env_file_path.clone())?;
+
+        for (key, value) in env.iter() {
+            writeln!(env_file, "{}='{}'", key, value.replace('\'', "'\\''"))?;
+        }
+
+        Ok(env_file_path)
+    }
+}
+
+impl ContainerExecuter for Apptainer {
+    fn bootstrap_yaab(&self, params: &BootstrapParams, cli: &Cli) -> Result<(), BError> {
+        self.find_runtime()?;
+        self.verify_mounts()?;
+        cli.check_call(
+            &self.bootstrap_cmd_line(
+                &params.cmd_line,
+                &params.docker_top_dir,
+                &params.work_dir,
+                &params.volumes,
+            ),
+            &params.env,
+            true,
+        )
+    }
+
+    fn run_cmd(
+        &self,
+        cmd_line: &[String],
+        env: &HashMap<String, String>,
+        exec_dir: &Path,
+        cli: &Cli,
+    ) -> Result<(), BError> {
+        self.find_runtime()?;
+        self.verify_mounts()?;
+        let temp_dir: TempDir = TempDir::new("yaab")?;
+        let env_file_path: PathBuf = self.setup_env_file(temp_dir.path(), env)?;
+        cli.check_call(
+            &self.cmd_line(cmd_line, &env_file_path, exec_dir),
+            &HashMap::new(),
+            true,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::executers::{Apptainer, DockerMounts};
+
+    #[test]
+    fn test_apptainer_bootstrap_cmdline() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        std::fs::create_dir_all(work_dir.join("tools")).expect("Failed to create dir");
+        let mut apptainer: Apptainer =
+            Apptainer::new("apptainer", "docker://ghcr.io/test/image:0.1", true);
+        apptainer.set_mounts(DockerMounts {
+            add: vec![format!("{}/tools:/opt/tools:ro", work_dir.display())],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: true,
+            ssh_agent: false,
+            credential_sock: String::new(),
+        });
+        let cmd_line: Vec<String> = apptainer.bootstrap_cmd_line(
+            &[String::from("yaab"), String::from("build")],
+            &work_dir,
+            &work_dir,
+            &[String::from("/test/dir:/test/dir")],
+        );
+        assert_eq!(
+            cmd_line.join(" "),
+            format!(
+                "apptainer exec --bind /test/dir:/test/dir --bind {dir}/tools:/opt/tools:ro --bind {dir}:{dir} --pwd {dir} docker://ghcr.io/test/image:0.1 yaab build",
+                dir = work_dir.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_apptainer_cmdline() {
+        let work_dir: PathBuf = PathBuf::from("/workspace/build");
+        let env_file: PathBuf = PathBuf::from("/tmp/yaab-apptainer.env");
+        let apptainer: Apptainer = Apptainer::new("singularity", "/images/test.sif", false);
+        let cmd_line: Vec<String> =
+            apptainer.cmd_line(&[String::from("make")], &env_file, &work_dir);
+        assert_eq!(
+            cmd_line.join(" "),
+            "singularity exec --cleanenv --env-file /tmp/yaab-apptainer.env --pwd /workspace/build /images/test.sif make"
+        );
+    }
+
+    #[test]
+    fn test_apptainer_forward_cmdline() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let sock: PathBuf = work_dir.join("git-credential.sock");
+        std::fs::write(&sock, "").expect("Failed to create socket");
+        let mut apptainer: Apptainer = Apptainer::new("apptainer", "/images/test.sif", false);
+        apptainer.set_mounts(DockerMounts {
+            add: vec![],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: false,
+            ssh_agent: false,
+            credential_sock: sock.to_string_lossy().to_string(),
+        });
+        apptainer.verify_mounts().expect("Failed to verify mounts");
+        let cmd_line: Vec<String> = apptainer.cmd_line(
+            &[String::from("make")],
+            &PathBuf::from("/tmp/yaab-apptainer.env"),
+            &PathBuf::from("/workspace/build"),
+        );
+        assert_eq!(
+            cmd_line.join(" "),
+            format!(
+                "apptainer exec --cleanenv --env-file /tmp/yaab-apptainer.env --bind {sock}:{sock} --pwd /workspace/build /images/test.sif make",
+                sock = sock.display()
+            )
+        );
+        assert_eq!(
+            Apptainer::apptainer_args(&[
+                String::from("-v"),
+                String::from("/run/user/1000/ssh.sock:/run/user/1000/ssh.sock"),
+                String::from("-e"),
+                String::from("SSH_AUTH_SOCK=/run/user/1000/ssh.sock"),
+            ])
+            .join(" "),
+            "--bind /run/user/1000/ssh.sock:/run/user/1000/ssh.sock --env SSH_AUTH_SOCK=/run/user/1000/ssh.sock"
+        );
+    }
+
+    #[test]
+    fn test_apptainer_env_file() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let apptainer: Apptainer = Apptainer::new("apptainer", "/images/test.sif", false);
+        let mut env: HashMap<String, String> = HashMap::new();
+        env.insert(String::from("KEY"), String::from("it's a value"));
+        let env_file: PathBuf = apptainer
+            .setup_env_file(temp_dir.path(), &env)
+            .expect("Failed to setup env file");
+        let content: String = std::fs::read_to_string(env_file).expect("Failed to read env file");
+        assert_eq!(content, "KEY='it'\\''s a value'\n");
+    }
+}
@@ -1,17 +1,47 @@
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fmt;
 use std::fs::File;
 use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use tempdir::TempDir;
 use users::Groups;
 
-use crate::cli::Cli;
+use crate::cli::{handshake, signal, Cli};
 use crate::error::BError;
+use crate::executers::upload::hex;
+use crate::executers::{BootstrapParams, ContainerExecuter, DockerMounts, DockerResources};
+
+pub const DOCKER_DIGEST_ENV: &str = "YAAB_DOCKER_DIGEST";
+
+/*
+ * The default mounts used when bootstrapping yaab into docker and when running
+ * a task in docker. They can be adjusted using the mounts node in the docker
+ * section of the workspace config.
+ */
+pub const BOOTSTRAP_MOUNTS: [&str; 7] = [
+    "/etc/passwd:/etc/passwd:ro",
+    "/etc/group:/etc/group:ro",
+    "~/.gitconfig:~/.gitconfig:rw",
+    "~/.ssh:~/.ssh:rw",
+    "~/.docker:~/.docker",
+    "~/.yaab:~/.yaab",
+    "/var/run/docker.sock:/var/run/docker.sock",
+];
+pub const TASK_MOUNTS: [&str; 3] = [
+    "/etc/passwd:/etc/passwd:ro",
+    "/etc/group:/etc/group:ro",
+    "~:~",
+];
 
 pub struct Docker {
     image: DockerImage,
     _interactive: bool,
+    container: Option<String>,
+    workspace_id: String,
+    mounts: DockerMounts,
+    resources: DockerResources,
 }
 
 #[derive(Clone)]
@@ -88,50 +118,32 @@ impl Docker {
         ]
     }
 
-    fn etc_files(&self) -> Vec<String> {
-        vec![
-            String::from("-v"),
-            String::from("/etc/passwd:/etc/passwd:ro"),
-            String::from("-v"),
-            String::from("/etc/group:/etc/group:ro"),
-        ]
-    }
-
-    fn hidden_home_files(&self) -> Vec<String> {
-        vec![
-            String::from("-v"),
-            format!(
-                "{}/.gitconfig:{}/.gitconfig:rw",
-                self.env_home(),
-                self.env_home()
-            ),
-            String::from("-v"),
-            format!("{}/.ssh:{}/.ssh:rw", self.env_home(), self.env_home()),
-            String::from("-v"),
-            format!("{}/.docker:{}/.docker", self.env_home(), self.env_home()),
-            String::from("-v"),
-            format!("{}/.yaab:{}/.yaab", self.env_home(), self.env_home()),
-        ]
+    fn mounts(&self, defaults: &[&str]) -> Vec<String> {
+        /*
+         * The mounts are verified before the container is started so any error
+         * here would already have been reported
+         */
+        let mut mounts: Vec<String> = self
+            .mounts
+            .resolve(defaults, &self.env_home())
+            .unwrap_or_default();
+        mounts.append(
+            &mut self
+                .mounts
+                .forward(&self.env_home(), &self.ssh_auth_sock())
+                .unwrap_or_default(),
+        );
+        mounts
     }
 
-    fn home_dir(&self) -> Vec<String> {
-        vec![
-            String::from("-v"),
-            format!("{}:{}", self.env_home(), self.env_home()),
-        ]
+    fn ssh_auth_sock(&self) -> String {
+        std::env::var("SSH_AUTH_SOCK").unwrap_or_default()
     }
 
     fn work_dir(&self, dir: &PathBuf) -> Vec<String> {
         vec![String::from("-w"), format!("{}", dir.display())]
     }
 
-    fn docker_sock(&self) -> Vec<String> {
-        vec![
-            String::from("-v"),
-            String::from("/var/run/docker.sock:/var/run/docker.sock"),
-        ]
-    }
-
     fn group(&self) -> Vec<String> {
         let cache: users::UsersCache = users::UsersCache::new();
         vec![
@@ -152,9 +164,7 @@ impl Docker {
         volumes.iter().for_each(|e| {
             v.append(&mut vec![String::from("-v"), e.to_string()]);
         });
-        v.append(&mut self.etc_files());
-        v.append(&mut self.hidden_home_files());
-        v.append(&mut self.docker_sock());
+        v.append(&mut self.mounts(&BOOTSTRAP_MOUNTS));
         v
     }
 
@@ -165,6 +175,13 @@ impl Docker {
         ]
     }
 
+    fn session_label(&self) -> Vec<String> {
+        vec![
+            String::from("--label"),
+            format!("yaab.session={}", signal::session()),
+        ]
+    }
+
     fn top_dir(&self, dir: &PathBuf) -> Vec<String> {
         vec![
             String::from("-v"),
@@ -172,6 +189,152 @@ impl Docker {
         ]
     }
 
+    fn label(&self) -> Vec<String> {
+        vec![
+            String::from("--label"),
+            format!("yaab.workspace={}", self.workspace_id),
+        ]
+    }
+
+    /*
+     * The hash of everything used when creating a persistent container. It
+     * is stored as a label on the container so that the container can be
+     * recreated if the mounts, resources or image of the workspace config
+     * have changed since it was created. The hash must be stable between
+     * builds of yaab or existing containers would no longer be recognized.
+     */
+    fn config_hash(&self, run_args: &[String]) -> String {
+        let mut hasher: Sha256 = Sha256::new();
+        hasher.update(self.image.to_string().as_bytes());
+        for arg in run_args {
+            hasher.update([0]);
+            hasher.update(arg.as_bytes());
+        }
+        hex(&hasher.finalize())[..16].to_string()
+    }
+
+    /*
+     * A persistent container is started detached with a command that never exits
+     * so that yaab can run all following commands inside of it using docker exec.
+     * The container is reused if it was created from the same image and with the
+     * same config, otherwise it is removed and created again. The container is
+     * also created again if it cannot be started.
+     */
+    fn persistent_start_cmd_line(&self, name: &str, run_args: &[String]) -> Vec<String> {
+        let config: String = self.config_hash(run_args);
+        let mut docker_cmd: Vec<String> = vec![
+            "id=$(docker".to_string(),
+            "image".to_string(),
+            "inspect".to_string(),
+            "--format".to_string(),
+            "'{{.Id}}'".to_string(),
+            self.image.to_string(),
+            "2>/dev/null)".to_string(),
+            ";".to_string(),
+            "[".to_string(),
+            "\"$(docker".to_string(),
+            "container".to_string(),
+            "inspect".to_string(),
+            "--format".to_string(),
+            "'{{index .Config.Labels \"yaab.config\"}}/{{index .Config.Labels \"yaab.image\"}}'"
+                .to_string(),
+            name.to_string(),
+            "2>/dev/null)\"".to_string(),
+            "=".to_string(),
+            format!("\"{}/$id\"", config),
+            "]".to_string(),
+            "&&".to_string(),
+            "docker".to_string(),
+            "start".to_string(),
+            name.to_string(),
+            ">".to_string(),
+            "/dev/null".to_string(),
+            "2>&1".to_string(),
+            "||".to_string(),
+            "{".to_string(),
+            "docker".to_string(),
+            "rm".to_string(),
+            "-f".to_string(),
+            name.to_string(),
+            ">".to_string(),
+            "/dev/null".to_string(),
+            "2>&1".to_string(),
+            ";".to_string(),
+            "docker".to_string(),
+            "run".to_string(),
+            "-d".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+        ];
+        docker_cmd.append(&mut self.label());
+        docker_cmd.append(&mut vec![
+            "--label".to_string(),
+            format!("yaab.config={}", config),
+            "--label".to_string(),
+            "yaab.image=$id".to_string(),
+        ]);
+        docker_cmd.extend_from_slice(run_args);
+        docker_cmd.push(self.image.to_string());
+        docker_cmd.append(&mut vec![
+            "sleep".to_string(),
+            "infinity".to_string(),
+            ">".to_string(),
+            "/dev/null".to_string(),
+            ";".to_string(),
+            "}".to_string(),
+        ]);
+        docker_cmd
+    }
+
+    fn persistent_exec_cmd_line(
+        &self,
+        name: &str,
+        cmd_line: &[String],
+        exec_args: &mut Vec<String>,
+    ) -> Vec<String> {
+        let mut docker_cmd: Vec<String> = vec!["docker".to_string(), "exec".to_string()];
+        docker_cmd.append(&mut self.user());
+        docker_cmd.append(exec_args);
+        docker_cmd.push("-t".to_string());
+        if self._interactive {
+            docker_cmd.push("-i".to_string());
+        }
+        docker_cmd.push(name.to_string());
+        docker_cmd.extend_from_slice(cmd_line);
+        docker_cmd
+    }
+
+    /*
+     * The workspace id is used to label and name all persistent containers
+     * started for a workspace. The hash of the full path makes sure that two
+     * workspaces with the same directory name are not sharing containers.
+     */
+    pub fn workspace_id(work_dir: &Path) -> String {
+        let digest: String = hex(&Sha256::digest(work_dir.as_os_str().as_bytes()));
+        let name: String = work_dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        format!("yaab-{}-{}", Self::sanitize(&name), &digest[..8])
+    }
+
+    fn sanitize(name: &str) -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect()
+    }
+
+    pub fn container(&self) -> Option<&String> {
+        self.container.as_ref()
+    }
+
     pub fn inside_docker() -> bool {
         let path: PathBuf = PathBuf::from("/.dockerenv");
         // Potentially it would be better to use try_exists
@@ -183,13 +346,77 @@ impl Docker {
         &self.image
     }
 
+    /*
+     * The digest of the image as reported by docker. Inside the container the
+     * docker cli is normally not available so the digest can be provided by
+     * the host using the YAAB_DOCKER_DIGEST env variable. If the digest cannot
+     * be resolved an empty string is returned.
+     */
+    pub fn digest(cli: &Cli, image: &str) -> String {
+        if let Ok(digest) = std::env::var(DOCKER_DIGEST_ENV) {
+            if !digest.is_empty() {
+                return digest;
+            }
+        }
+
+        let cmd_line: Vec<String> = vec![
+            String::from("docker"),
+            String::from("image"),
+            String::from("inspect"),
+            String::from("--format"),
+            String::from("'{{index .RepoDigests 0}}'"),
+            image.to_string(),
+        ];
+        match cli.check_output(&cmd_line, &cli.env(), true) {
+            Ok(output) => output.trim().to_string(),
+            Err(_e) => String::new(),
+        }
+    }
+
     pub fn new(image: DockerImage, interactive: bool) -> Self {
         Docker {
             image,
             _interactive: interactive,
+            container: None,
+            workspace_id: String::new(),
+            mounts: DockerMounts::default(),
+            resources: DockerResources::default(),
+        }
+    }
+
+    pub fn persistent(image: DockerImage, interactive: bool, work_dir: &Path, name: &str) -> Self {
+        let workspace_id: String = Self::workspace_id(work_dir);
+        Docker {
+            image,
+            _interactive: interactive,
+            container: Some(format!("{}-{}", workspace_id, Self::sanitize(name))),
+            workspace_id,
+            mounts: DockerMounts::default(),
+            resources: DockerResources::default(),
         }
     }
 
+    pub fn set_mounts(&mut self, mounts: DockerMounts) {
+        self.mounts = mounts;
+    }
+
+    pub fn set_resources(&mut self, resources: DockerResources) {
+        self.resources = resources;
+    }
+
+    /*
+     * Make sure that all mounts added in the workspace config and any socket
+     * forwarded into the container exists before starting the container.
+     * Docker would otherwise silently create any missing source directory
+     * owned by root.
+     */
+    pub fn verify_mounts(&self, defaults: &[&str]) -> Result<(), BError> {
+        self.mounts.resolve(defaults, &self.env_home())?;
+        self.mounts
+            .forward(&self.env_home(), &self.ssh_auth_sock())?;
+        Ok(())
+    }
+
     pub fn bootstrap_cmd_line(
         &self,
         cmd_line: &Vec<String>,
@@ -200,6 +427,7 @@ impl Docker {
     ) -> Vec<String> {
         let mut docker_cmd: Vec<String> = vec!["docker".to_string(), "run".to_string()];
         docker_cmd.append(&mut self.container_name("yaab-workspace"));
+        docker_cmd.append(&mut self.session_label());
         docker_cmd.append(&mut vec!["-t".to_string(), "--rm".to_string()]);
         if self._interactive {
             docker_cmd.push("-i".to_string());
@@ -209,6 +437,8 @@ impl Docker {
         docker_cmd.append(&mut self.user());
         docker_cmd.append(&mut self.top_dir(docker_top_dir));
         docker_cmd.append(&mut self.work_dir(work_dir));
+        docker_cmd.append(&mut handshake::args());
+        docker_cmd.append(&mut self.resources.args());
         if !docker_args.is_empty() {
             docker_cmd.append(&mut docker_args.clone());
         }
@@ -217,6 +447,53 @@ impl Docker {
         docker_cmd
     }
 
+    pub fn bootstrap_persistent_cmd_line(
+        &self,
+        name: &str,
+        cmd_line: &[String],
+        docker_top_dir: &PathBuf,
+        work_dir: &PathBuf,
+        docker_args: &[String],
+        volumes: &Vec<String>,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut run_args: Vec<String> = vec![];
+        run_args.append(&mut self.group());
+        run_args.append(&mut self.volumes(volumes));
+        run_args.append(&mut self.user());
+        run_args.append(&mut self.top_dir(docker_top_dir));
+        run_args.append(&mut self.work_dir(work_dir));
+        run_args.append(&mut self.resources.args());
+        if !docker_args.is_empty() {
+            run_args.extend_from_slice(docker_args);
+        }
+        let mut exec_args: Vec<String> = self.work_dir(work_dir);
+        exec_args.append(&mut handshake::args());
+        (
+            self.persistent_start_cmd_line(name, &run_args),
+            self.persistent_exec_cmd_line(name, cmd_line, &mut exec_args),
+        )
+    }
+
+    pub fn persistent_cmd_line(
+        &self,
+        name: &str,
+        cmd_line: &[String],
+        env_file: &PathBuf,
+        dir: &PathBuf,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut run_args: Vec<String> = vec![];
+        run_args.append(&mut self.user());
+        run_args.append(&mut self.mounts(&TASK_MOUNTS));
+        run_args.append(&mut self.group());
+        run_args.append(&mut self.resources.args());
+        let mut exec_args: Vec<String> = self.work_dir(dir);
+        exec_args.append(&mut self.env_file(env_file));
+        (
+            self.persistent_start_cmd_line(name, &run_args),
+            self.persistent_exec_cmd_line(name, cmd_line, &mut exec_args),
+        )
+    }
+
     pub fn cmd_line(
         &self,
         cmd_line: &Vec<String>,
@@ -224,15 +501,16 @@ impl Docker {
         dir: &PathBuf,
     ) -> Vec<String> {
         let mut docker_cmd: Vec<String> = vec!["docker".to_string(), "run".to_string()];
+        docker_cmd.append(&mut self.session_label());
         docker_cmd.append(&mut self.user());
-        docker_cmd.append(&mut self.etc_files());
-        docker_cmd.append(&mut self.home_dir());
+        docker_cmd.append(&mut self.mounts(&TASK_MOUNTS));
         docker_cmd.append(&mut self.work_dir(dir));
         docker_cmd.append(&mut vec!["-t".to_string(), "--rm".to_string()]);
         if self._interactive {
             docker_cmd.push("-i".to_string());
         }
         docker_cmd.append(&mut self.group());
+        docker_cmd.append(&mut self.resources.args());
         docker_cmd.append(&mut self.env_file(env_file));
         docker_cmd.push(format!("{}", self.image));
         docker_cmd.append(&mut cmd_line.clone());
@@ -264,35 +542,173 @@ impl Docker {
         Ok(())
     }
 
-    pub fn bootstrap_yaab(
-        &self,
-        cmd_line: &Vec<String>,
-        cli: &Cli,
-        docker_top_dir: &PathBuf,
-        work_dir: &PathBuf,
-        docker_args: &Vec<String>,
-        volumes: &Vec<String>,
-        env: &HashMap<String, String>,
-    ) -> Result<(), BError> {
+    fn persistent_filters(work_dir: &Path, config: &str) -> Vec<String> {
+        let workspace_id: String = Self::workspace_id(work_dir);
+        let mut filters: Vec<String> = vec![
+            String::from("--filter"),
+            format!("label=yaab.workspace={}", workspace_id),
+        ];
+        if !config.is_empty() {
+            filters.append(&mut vec![
+                String::from("--filter"),
+                format!("name={}-{}", workspace_id, Self::sanitize(config)),
+            ]);
+        }
+        filters
+    }
+
+    pub fn persistent_status_cmd_line(work_dir: &Path, config: &str) -> Vec<String> {
+        let mut cmd_line: Vec<String> = vec![
+            String::from("docker"),
+            String::from("ps"),
+            String::from("-a"),
+        ];
+        cmd_line.append(&mut Self::persistent_filters(work_dir, config));
+        cmd_line.append(&mut vec![
+            String::from("--format"),
+            String::from("\"table {{.Names}}\t{{.Status}}\t{{.Image}}\""),
+        ]);
+        cmd_line
+    }
+
+    pub fn persistent_stop_cmd_line(work_dir: &Path, config: &str) -> Vec<String> {
+        let mut cmd_line: Vec<String> = vec![
+            String::from("docker"),
+            String::from("ps"),
+            String::from("-q"),
+        ];
+        cmd_line.append(&mut Self::persistent_filters(work_dir, config));
+        cmd_line.append(&mut vec![
+            String::from("|"),
+            String::from("xargs"),
+            String::from("-r"),
+            String::from("docker"),
+            String::from("stop"),
+        ]);
+        cmd_line
+    }
+
+    pub fn persistent_prune_cmd_line(work_dir: &Path, config: &str) -> Vec<String> {
+        let mut cmd_line: Vec<String> = vec![
+            String::from("docker"),
+            String::from("ps"),
+            String::from("-aq"),
+        ];
+        cmd_line.append(&mut Self::persistent_filters(work_dir, config));
+        cmd_line.append(&mut vec![
+            String::from("|"),
+            String::from("xargs"),
+            String::from("-r"),
+            String::from("docker"),
+            String::from("rm"),
+            String::from("-f"),
+        ]);
+        cmd_line
+    }
+
+    /*
+     * Lookup the docker client in the PATH the same way as the shell running
+     * the docker commands is doing it.
+     */
+    pub fn find_runtime() -> Option<PathBuf> {
+        let path: String = std::env::var("PATH").unwrap_or_default();
+        path.split(':')
+            .map(|dir| PathBuf::from(dir).join("docker"))
+            .find(|runtime| runtime.exists())
+    }
+
+    /*
+     * Used when yaab is interrupted to remove all containers started by this
+     * run of yaab and to stop any persistent container it has been using.
+     */
+    pub fn cleanup_cmd_line(session: &str, containers: &[String]) -> Vec<String> {
+        let mut cmd_line: Vec<String> = vec![
+            String::from("docker"),
+            String::from("ps"),
+            String::from("-aq"),
+            String::from("--filter"),
+            format!("label=yaab.session={}", session),
+            String::from("|"),
+            String::from("xargs"),
+            String::from("-r"),
+            String::from("docker"),
+            String::from("rm"),
+            String::from("-f"),
+            String::from(">"),
+            String::from("/dev/null"),
+        ];
+        if !containers.is_empty() {
+            cmd_line.append(&mut vec![
+                String::from(";"),
+                String::from("docker"),
+                String::from("stop"),
+            ]);
+            cmd_line.extend_from_slice(containers);
+            cmd_line.append(&mut vec![
+                String::from(">"),
+                String::from("/dev/null"),
+                String::from("2>&1"),
+            ]);
+        }
+        cmd_line
+    }
+}
+
+impl ContainerExecuter for Docker {
+    fn bootstrap_yaab(&self, params: &BootstrapParams, cli: &Cli) -> Result<(), BError> {
+        self.verify_mounts(&BOOTSTRAP_MOUNTS)?;
+        if let Some(name) = self.container() {
+            signal::register_container(name);
+            let (start, exec): (Vec<String>, Vec<String>) = self.bootstrap_persistent_cmd_line(
+                name,
+                &params.cmd_line,
+                &params.docker_top_dir,
+                &params.work_dir,
+                &params.docker_args,
+                &params.volumes,
+            );
+            cli.check_call(&start, &params.env, true)?;
+            cli.check_call(&exec, &params.env, true)?;
+            return Ok(());
+        }
+
         cli.check_call(
-            &self.bootstrap_cmd_line(cmd_line, docker_top_dir, work_dir, docker_args, volumes),
-            &env,
+            &self.bootstrap_cmd_line(
+                &params.cmd_line,
+                &params.docker_top_dir,
+                &params.work_dir,
+                &params.docker_args,
+                &params.volumes,
+            ),
+            &params.env,
             true,
         )?;
         Ok(())
     }
 
-    pub fn run_cmd(
+    fn run_cmd(
         &self,
-        cmd_line: &Vec<String>,
+        cmd_line: &[String],
         env: &HashMap<String, String>,
-        exec_dir: &PathBuf,
+        exec_dir: &Path,
         cli: &Cli,
     ) -> Result<(), BError> {
+        let cmd_line: Vec<String> = cmd_line.to_vec();
+        let exec_dir: PathBuf = exec_dir.to_path_buf();
+        self.verify_mounts(&TASK_MOUNTS)?;
         let temp_dir: TempDir = TempDir::new("yaab")?;
         let env_file_path: PathBuf = self.setup_env_file(temp_dir.path(), env)?;
+        if let Some(name) = self.container() {
+            signal::register_container(name);
+            let (start, exec): (Vec<String>, Vec<String>) =
+                self.persistent_cmd_line(name, &cmd_line, &env_file_path, &exec_dir);
+            cli.check_call(&start, &HashMap::new(), true)?;
+            cli.check_call(&exec, &HashMap::new(), true)?;
+            return Ok(());
+        }
+
         cli.check_call(
-            &self.cmd_line(cmd_line, &env_file_path, exec_dir),
+            &self.cmd_line(&cmd_line, &env_file_path, &exec_dir),
             &HashMap::new(),
             true,
         )?;
@@ -305,10 +721,13 @@ mod tests {
     use std::collections::HashMap;
     use std::fs::File;
     use std::io::Read;
-    use std::path::PathBuf;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
     use tempdir::TempDir;
 
-    use crate::executers::{Docker, DockerImage};
+    use crate::cli::{BLogger, BSystem, CallParams, Cli, MockSystem};
+    use crate::error::BError;
+    use crate::executers::{Docker, DockerImage, DockerResources};
     use crate::helper::Helper;
 
     #[test]
@@ -583,6 +1002,41 @@ TEST_KEY1=TEST_VALUE1
         assert_eq!(result, cmd_line);
     }
 
+    #[test]
+    fn test_docker_cmdline_resources() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let env_file: PathBuf = work_dir.clone().join("test-docker.env");
+        let test_cmd: Vec<String> = vec![String::from("test")];
+        let image: DockerImage =
+            DockerImage::new("test-registry/test-image:0.1").expect("Invalid docker image format");
+        let mut docker: Docker = Docker::new(image.clone(), false);
+        docker.set_resources(DockerResources {
+            memory: String::from("32g"),
+            cpus: String::new(),
+            shm_size: String::from("2g"),
+            ulimits: vec![],
+            devices: vec![String::from("/dev/bus/usb")],
+        });
+        let result: Vec<String> = docker.cmd_line(&test_cmd, &env_file, &work_dir);
+        let mut cmd_line: Vec<String> =
+            Helper::docker_cmdline_string(false, &work_dir, &image, &test_cmd, &env_file);
+        let index: usize = cmd_line.iter().position(|a| a == "--env-file").unwrap();
+        cmd_line.splice(
+            index..index,
+            vec![
+                String::from("--memory"),
+                String::from("32g"),
+                String::from("--shm-size"),
+                String::from("2g"),
+                String::from("--device"),
+                String::from("/dev/bus/usb"),
+            ],
+        );
+        assert_eq!(result, cmd_line);
+    }
+
     #[test]
     fn test_docker_cmdline_interactive() {
         let temp_dir: TempDir =
@@ -605,4 +1059,278 @@ TEST_KEY1=TEST_VALUE1
             Helper::docker_cmdline_string(interactive, &work_dir, &image, &test_cmd, &env_file);
         assert_eq!(result, cmd_line);
     }
+
+    #[test]
+    fn test_docker_workspace_id() {
+        let id1: String = Docker::workspace_id(&PathBuf::from("/workspace1/aosp"));
+        let id2: String = Docker::workspace_id(&PathBuf::from("/workspace2/aosp"));
+        assert!(id1.starts_with("yaab-aosp-"));
+        assert!(id2.starts_with("yaab-aosp-"));
+        assert_ne!(id1, id2);
+        assert_eq!(
+            id1,
+            Docker::workspace_id(&PathBuf::from("/workspace1/aosp"))
+        );
+        assert_eq!(
+            Docker::workspace_id(&PathBuf::from("/workspace")),
+            "yaab-workspace-c52ddf65"
+        );
+    }
+
+    #[test]
+    fn test_docker_persistent_cmdline() {
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let env_file: PathBuf = work_dir.clone().join("test-docker.env");
+        let test_cmd: Vec<String> = vec![String::from("test")];
+        let image: DockerImage =
+            DockerImage::new("test-registry/test-image:0.1").expect("Invalid docker image format");
+        let docker: Docker = Docker::persistent(image, false, &work_dir, "default-task");
+        let name: String = format!("{}-default-task", Docker::workspace_id(&work_dir));
+        assert_eq!(docker.container(), Some(&name));
+        let (start, exec): (Vec<String>, Vec<String>) =
+            docker.persistent_cmd_line(&name, &test_cmd, &env_file, &work_dir);
+        assert_eq!(
+            start[..7],
+            [
+                "id=$(docker",
+                "image",
+                "inspect",
+                "--format",
+                "'{{.Id}}'",
+                "test-registry/test-image:0.1",
+                "2>/dev/null)"
+            ]
+        );
+        assert!(start.contains(&String::from("run")));
+        assert!(start.contains(&String::from("yaab.image=$id")));
+        assert!(start.contains(&format!(
+            "yaab.workspace={}",
+            Docker::workspace_id(&work_dir)
+        )));
+        assert_eq!(
+            start[start.len() - 7..],
+            [
+                "test-registry/test-image:0.1",
+                "sleep",
+                "infinity",
+                ">",
+                "/dev/null",
+                ";",
+                "}"
+            ]
+        );
+        assert_eq!(
+            exec,
+            vec![
+                String::from("docker"),
+                String::from("exec"),
+                String::from("-u"),
+                format!("{}:{}", users::get_current_uid(), users::get_current_gid()),
+                String::from("-w"),
+                String::from("/workspace"),
+                String::from("--env-file"),
+                String::from("/workspace/test-docker.env"),
+                String::from("-t"),
+                name.clone(),
+                String::from("test"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_docker_digest() {
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system.expect_env().returning(HashMap::new);
+        mocked_system
+            .expect_check_output()
+            .with(mockall::predicate::eq(CallParams {
+                cmd_line: [
+                    "docker",
+                    "image",
+                    "inspect",
+                    "--format",
+                    "'{{index .RepoDigests 0}}'",
+                    "test-registry/test-image:0.1",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+                env: HashMap::new(),
+                shell: true,
+            }))
+            .once()
+            .returning(|_x| Ok(String::from("test-registry/test-image@sha256:1234\n")));
+        mocked_system
+            .expect_check_output()
+            .withf(|params: &CallParams| {
+                params
+                    .cmd_line
+                    .contains(&String::from("test-registry/missing:0.1"))
+            })
+            .once()
+            .returning(|_x| Err(BError::CliError(String::from("exit status: 1: error"))));
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        assert_eq!(
+            Docker::digest(&cli, "test-registry/test-image:0.1"),
+            "test-registry/test-image@sha256:1234"
+        );
+        assert_eq!(Docker::digest(&cli, "test-registry/missing:0.1"), "");
+    }
+
+    #[test]
+    fn test_docker_cleanup_cmdline() {
+        let cmd_line: Vec<String> =
+            Docker::cleanup_cmd_line("1234-0000abcd", &[String::from("yaab-ws-default")]);
+        assert_eq!(
+            cmd_line.join(" "),
+            "docker ps -aq --filter label=yaab.session=1234-0000abcd | xargs -r docker rm -f > /dev/null ; docker stop yaab-ws-default > /dev/null 2>&1"
+        );
+        let cmd_line: Vec<String> = Docker::cleanup_cmd_line("1234-0000abcd", &[]);
+        assert_eq!(
+            cmd_line.join(" "),
+            "docker ps -aq --filter label=yaab.session=1234-0000abcd | xargs -r docker rm -f > /dev/null"
+        );
+    }
+
+    #[test]
+    fn test_docker_persistent_prune_cmdline() {
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let id: String = Docker::workspace_id(&work_dir);
+        assert_eq!(
+            Docker::persistent_prune_cmd_line(&work_dir, "default"),
+            vec![
+                String::from("docker"),
+                String::from("ps"),
+                String::from("-aq"),
+                String::from("--filter"),
+                format!("label=yaab.workspace={}", id),
+                String::from("--filter"),
+                format!("name={}-default", id),
+                String::from("|"),
+                String::from("xargs"),
+                String::from("-r"),
+                String::from("docker"),
+                String::from("rm"),
+                String::from("-f"),
+            ]
+        );
+    }
+
+    /*
+     * Run the start command line of a persistent container using a fake
+     * docker reporting the labels of an existing container from the
+     * CONTAINER_LABELS env variable and failing 'docker start' if
+     * START_FAILS is set.
+     */
+    fn helper_persistent_start(
+        docker: &Docker,
+        name: &str,
+        work_dir: &Path,
+        labels: &str,
+        start_fails: bool,
+    ) -> String {
+        let bin_dir: PathBuf = work_dir.join("bin");
+        let log: PathBuf = bin_dir.join("docker.log");
+        std::fs::create_dir_all(&bin_dir).expect("Failed to create dir");
+        std::fs::write(
+            bin_dir.join("docker"),
+            format!(
+                r#"#!/bin/sh
+echo "$1 $2" >> {log}
+case "$1 $2" in
+    "image inspect") echo "sha256:1234";;
+    "container inspect") [ -n "$CONTAINER_LABELS" ] || exit 1; echo "$CONTAINER_LABELS";;
+    "start "*) [ -z "$START_FAILS" ] || exit 1;;
+esac
+"#,
+                log = log.display()
+            ),
+        )
+        .expect("Failed to write file");
+        std::fs::set_permissions(
+            bin_dir.join("docker"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .expect("Failed to set permissions");
+        let _ = std::fs::remove_file(&log);
+        let mut env: HashMap<String, String> = HashMap::from([
+            (
+                String::from("PATH"),
+                format!("{}:/usr/bin:/bin", bin_dir.display()),
+            ),
+            (String::from("CONTAINER_LABELS"), labels.to_string()),
+        ]);
+        if start_fails {
+            env.insert(String::from("START_FAILS"), String::from("1"));
+        }
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(BSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let (start, _exec): (Vec<String>, Vec<String>) = docker.persistent_cmd_line(
+            name,
+            &[String::from("test")],
+            &work_dir.join("test-docker.env"),
+            &work_dir.to_path_buf(),
+        );
+        cli.check_call(&start, &env, true)
+            .expect("Failed to start container");
+        std::fs::read_to_string(&log).expect("Failed to read file")
+    }
+
+    #[test]
+    fn test_docker_persistent_recreate() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let image: DockerImage =
+            DockerImage::new("test-registry/test-image:0.1").expect("Invalid docker image format");
+        let docker: Docker = Docker::persistent(image, false, &work_dir, "default");
+        let name: String = docker.container().unwrap().clone();
+        let (start, _exec): (Vec<String>, Vec<String>) = docker.persistent_cmd_line(
+            &name,
+            &[String::from("test")],
+            &work_dir.join("test-docker.env"),
+            &work_dir,
+        );
+        let config: String = start
+            .iter()
+            .find_map(|a| a.strip_prefix("yaab.config="))
+            .unwrap()
+            .to_string();
+
+        /*
+         * The container is only started if it was created with the same
+         * config from the same image
+         */
+        let labels: String = format!("{}/sha256:1234", config);
+        assert_eq!(
+            helper_persistent_start(&docker, &name, &work_dir, &labels, false),
+            format!("image inspect\ncontainer inspect\nstart {}\n", name)
+        );
+        for labels in [
+            String::from(""),
+            format!("{}/sha256:5678", config),
+            String::from("0000000000000000/sha256:1234"),
+        ] {
+            assert_eq!(
+                helper_persistent_start(&docker, &name, &work_dir, &labels, false),
+                "image inspect\ncontainer inspect\nrm -f\nrun -d\n"
+            );
+        }
+        assert_eq!(
+            helper_persistent_start(&docker, &name, &work_dir, &labels, true),
+            format!(
+                "image inspect\ncontainer inspect\nstart {}\nrm -f\nrun -d\n",
+                name
+            )
+        );
+    }
 }
@@ -1,12 +1,13 @@
 use crate::cli::Cli;
-use crate::data::WsTaskData;
+use crate::data::{WsBuildData, WsTaskData};
 use crate::error::BError;
-use crate::executers::{Docker, DockerImage, TaskExecuter};
+use crate::executers::{container_executer, ContainerExecuter, Docker, DockerImage, TaskExecuter};
 
 use std::collections::HashMap;
 
 pub struct CleanExecuter<'a> {
     task_data: &'a WsTaskData,
+    build_data: &'a WsBuildData,
     cli: &'a Cli,
 }
 
@@ -38,14 +39,21 @@ impl<'a> TaskExecuter for CleanExecuter<'a> {
 
         if !docker_str.is_empty() {
             let image: DockerImage = DockerImage::new(docker_str)?;
-            let docker: Docker = Docker::new(image, interactive);
-            docker.run_cmd(
-                &mut cmd_line,
+            let executer: Box<dyn ContainerExecuter> = task_container(
+                self.cli,
+                self.build_data,
+                self.task_data,
+                image,
+                interactive,
+            );
+            executer.run_cmd(
+                &cmd_line,
                 args_env_variables,
                 self.task_data.build_dir(),
                 &self.cli,
             )?;
         } else {
+            ignored_resources(self.cli, self.task_data, "the task has no docker image");
             self.cli.check_call(&cmd_line, args_env_variables, true)?;
         }
 
@@ -54,14 +62,19 @@ impl<'a> TaskExecuter for CleanExecuter<'a> {
 }
 
 impl<'a> CleanExecuter<'a> {
-    pub fn new(cli: &'a Cli, task_data: &'a WsTaskData) -> Self {
-        CleanExecuter { cli, task_data }
+    pub fn new(cli: &'a Cli, task_data: &'a WsTaskData, build_data: &'a WsBuildData) -> Self {
+        CleanExecuter {
+            cli,
+            task_data,
+            build_data,
+        }
     }
 }
 
 pub struct BuildExecuter<'a> {
     cli: &'a Cli,
     task_data: &'a WsTaskData,
+    build_data: &'a WsBuildData,
 }
 
 impl<'a> TaskExecuter for BuildExecuter<'a> {
@@ -92,10 +105,21 @@ impl<'a> TaskExecuter for BuildExecuter<'a> {
 
         if !self.task_data.docker_image().is_empty() && self.task_data.docker_image() != "NA" {
             let image: DockerImage = DockerImage::new(self.task_data.docker_image())?;
-            let docker: Docker = Docker::new(image, interactive);
-            docker.run_cmd(&mut cmd_line, env_variables, exec_dir, &self.cli)?;
+            let executer: Box<dyn ContainerExecuter> = task_container(
+                self.cli,
+                self.build_data,
+                self.task_data,
+                image,
+                interactive,
+            );
+            executer.run_cmd(&cmd_line, env_variables, exec_dir, self.cli)?;
         } else {
-            let env: HashMap<String, String> = env_variables.clone().into_iter().chain(self.cli.env().into_iter()).collect();
+            ignored_resources(self.cli, self.task_data, "the task has no docker image");
+            let env: HashMap<String, String> = env_variables
+                .clone()
+                .into_iter()
+                .chain(self.cli.env())
+                .collect();
             self.cli.check_call(&cmd_line, &env, true)?;
         }
         Ok(())
@@ -103,8 +127,67 @@ impl<'a> TaskExecuter for BuildExecuter<'a> {
 }
 
 impl<'a> BuildExecuter<'a> {
-    pub fn new(cli: &'a Cli, task_data: &'a WsTaskData) -> Self {
-        BuildExecuter { cli, task_data }
+    pub fn new(cli: &'a Cli, task_data: &'a WsTaskData, build_data: &'a WsBuildData) -> Self {
+        BuildExecuter {
+            cli,
+            task_data,
+            build_data,
+        }
+    }
+}
+
+/*
+ * If persistent containers are enabled in the workspace settings every task
+ * with a docker image gets a long-lived container named after the build config
+ * and the task instead of a fresh container for each run. The mounts from
+ * the workspace settings are applied to the task container as well and the
+ * resources of the task are merged on top of the workspace resources. If the
+ * workspace is using apptainer the task image is run using apptainer instead
+ * and the resources of the task are ignored.
+ */
+fn task_container(
+    cli: &Cli,
+    build_data: &WsBuildData,
+    task_data: &WsTaskData,
+    image: DockerImage,
+    interactive: bool,
+) -> Box<dyn ContainerExecuter> {
+    if build_data.settings().apptainer_enabled() {
+        ignored_resources(cli, task_data, "apptainer is not supporting them");
+        return container_executer(build_data.settings(), image, interactive);
+    }
+    let mut docker: Docker = if build_data.settings().docker_persistent() {
+        Docker::persistent(
+            image,
+            interactive,
+            &build_data.settings().work_dir(),
+            &format!("{}-{}", build_data.name(), task_data.name()),
+        )
+    } else {
+        Docker::new(image, interactive)
+    };
+    docker.set_mounts(build_data.settings().docker_mounts());
+    docker.set_resources(
+        build_data
+            .settings()
+            .docker_resources()
+            .merge(task_data.docker_resources()),
+    );
+    Box::new(docker)
+}
+
+/*
+ * The resources of a task can only be applied when the task is running in a
+ * docker container of its own. Tasks running directly in the bootstrap
+ * container or on the host are sharing the resources of the yaab process.
+ */
+fn ignored_resources(cli: &Cli, task_data: &WsTaskData, reason: &str) {
+    if !task_data.docker_resources().is_empty() {
+        cli.warn(format!(
+            "The resources of task '{}' are ignored, {}",
+            task_data.name(),
+            reason
+        ));
     }
 }
 
@@ -169,7 +252,7 @@ mod tests {
             clap::Command::new("yaab"),
             Some(vec!["yaab"]),
         );
-        let executer: BuildExecuter = BuildExecuter::new(&cli, &task_data);
+        let executer: BuildExecuter = BuildExecuter::new(&cli, &task_data, &data);
         executer
             .exec(&env_variables, false, true)
             .expect("Failed to execute task");
@@ -213,7 +296,7 @@ mod tests {
             clap::Command::new("yaab"),
             Some(vec!["yaab"]),
         );
-        let executer: BuildExecuter = BuildExecuter::new(&cli, &task_data);
+        let executer: BuildExecuter = BuildExecuter::new(&cli, &task_data, &data);
         executer
             .exec(&env_variables, true, true)
             .expect("Failed to execute task");
@@ -320,7 +403,60 @@ mod tests {
             clap::Command::new("yaab"),
             Some(vec!["yaab"]),
         );
-        let executer: CleanExecuter = CleanExecuter::new(&cli, &task_data);
+        let executer: CleanExecuter = CleanExecuter::new(&cli, &task_data, &data);
+        executer
+            .exec(&env_variables, false, true)
+            .expect("Failed to execute task");
+    }
+
+    #[test]
+    fn test_yaab_executer_ignored_resources() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let env_variables: HashMap<String, String> = HashMap::new();
+        let json_build_config: &str = r#"
+        {
+            "version": "4",
+            "name": "default",
+            "description": "Test Description",
+            "arch": "test-arch"
+        }"#;
+        let json_task_config: &str = r#"
+        {
+            "index": "1",
+            "name": "task-name",
+            "builddir": "test-dir",
+            "build": "test.sh",
+            "clean": "rm -rf test-dir",
+            "resources": {
+                "memory": "8g"
+            }
+        }"#;
+        let data: WsBuildData = Helper::setup_build_data(&work_dir, Some(json_build_config), None);
+        let task_data: WsTaskData =
+            WsTaskData::from_str(json_task_config, &data).expect("Failed to parse task config");
+        let mut mocked_system: MockSystem = MockSystem::new();
+        mocked_system
+            .expect_check_call()
+            .once()
+            .returning(|_x| Ok(()));
+        mocked_system.expect_env().returning(HashMap::new);
+        let mut mocked_logger: MockLogger = MockLogger::new();
+        mocked_logger
+            .expect_warn()
+            .with(mockall::predicate::eq(String::from(
+                "The resources of task 'task-name' are ignored, the task has no docker image",
+            )))
+            .once()
+            .returning(|_x| ());
+        let cli: Cli = Cli::new(
+            Box::new(mocked_logger),
+            Box::new(mocked_system),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let executer: BuildExecuter = BuildExecuter::new(&cli, &task_data, &data);
         executer
             .exec(&env_variables, false, true)
             .expect("Failed to execute task");
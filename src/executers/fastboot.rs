@@ -0,0 +1,324 @@
+use crate::cli::Cli;
+use crate::data::WsDeployData;
+use crate::error::BError;
+use crate::executers::TaskExecuter;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/*
+ * An image path that is not absolute is looked up in the deploy directory
+ * first which by default is the artifacts directory and then in the product
+ * out directory.
+ */
+pub fn resolve_path(data: &WsDeployData, image: &str) -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = match PathBuf::from(image).is_absolute() {
+        true => vec![PathBuf::from(image)],
+        false => vec![
+            PathBuf::from(data.dir()).join(image),
+            PathBuf::from(data.product_out()).join(image),
+        ],
+    };
+    candidates.into_iter().find(|c| c.is_file())
+}
+
+/*
+ * Resolve the image of every partition. All missing images are reported at
+ * once so nothing is deployed unless every image exists.
+ */
+pub fn resolve_images<'b>(
+    data: &WsDeployData,
+    images: impl Iterator<Item = (&'b String, &'b String)>,
+) -> Result<Vec<(String, PathBuf)>, BError> {
+    let mut resolved: Vec<(String, PathBuf)> = vec![];
+    let mut missing: Vec<String> = vec![];
+
+    for (name, image) in images {
+        match resolve_path(data, image) {
+            Some(path) => resolved.push((name.clone(), path)),
+            None => missing.push(format!("{} '{}'", name, image)),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(BError::DeployError(format!(
+            "Missing image(s) for {}, looked in '{}' and '{}'",
+            missing.join(", "),
+            data.dir(),
+            data.product_out()
+        )));
+    }
+
+    Ok(resolved)
+}
+
+pub struct FastbootDeployExecuter<'a> {
+    cli: &'a Cli,
+    data: &'a WsDeployData,
+}
+
+impl<'a> TaskExecuter for FastbootDeployExecuter<'a> {
+    fn exec(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dry_run: bool,
+        _interactive: bool,
+    ) -> Result<(), BError> {
+        let images: Vec<(String, PathBuf)> = resolve_images(self.data, self.data.images().iter())?;
+
+        if dry_run {
+            for (partition, path) in images.iter() {
+                self.cli.info(format!(
+                    "Dry run. Skipping flash of partition '{}' with '{}'",
+                    partition,
+                    path.display()
+                ));
+            }
+            return Ok(());
+        }
+
+        self.fastboot(&["reboot-bootloader"], env_variables)?;
+
+        for partition in self.data.erase().iter() {
+            self.fastboot(&["erase", partition], env_variables)?;
+        }
+
+        if self.data.wipe() {
+            self.fastboot(&["-w"], env_variables)?;
+        }
+
+        /*
+         * Flashing is stopped on the first partition failing since the device
+         * would be left with a mix of old and new images anyway. The result of
+         * every partition is logged so it is clear what was flashed.
+         */
+        let mut results: Vec<(String, &str)> = vec![];
+        let mut error: Option<BError> = None;
+        for (partition, path) in images.iter() {
+            if error.is_some() {
+                results.push((partition.clone(), "skipped"));
+                continue;
+            }
+
+            let mut args: Vec<&str> = vec![];
+            if !self.data.slot().is_empty() {
+                args.extend(["--slot", self.data.slot()]);
+            }
+            let image: String = path.to_string_lossy().to_string();
+            args.extend(["flash", partition, &image]);
+            match self.fastboot(&args, env_variables) {
+                Ok(()) => results.push((partition.clone(), "flashed")),
+                Err(err) => {
+                    results.push((partition.clone(), "failed"));
+                    error = Some(err);
+                }
+            }
+        }
+
+        for (partition, result) in results.iter() {
+            match *result {
+                "failed" => self.cli.error(format!("{}: {}", partition, result)),
+                _ => self.cli.info(format!("{}: {}", partition, result)),
+            }
+        }
+
+        if let Some(err) = error {
+            return Err(BError::DeployError(format!(
+                "Failed to flash '{}': {}",
+                results
+                    .iter()
+                    .find(|(_, r)| *r == "failed")
+                    .map(|(p, _)| p.as_str())
+                    .unwrap_or_default(),
+                err
+            )));
+        }
+
+        if self.data.slot() == "a" || self.data.slot() == "b" {
+            let active: String = format!("--set-active={}", self.data.slot());
+            self.fastboot(&[&active], env_variables)?;
+        }
+
+        self.fastboot(&["reboot"], env_variables)?;
+        self.cli.info(format!(
+            "Flashed {} partition(s) on '{}'",
+            images.len(),
+            self.device()
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> FastbootDeployExecuter<'a> {
+    pub fn new(cli: &'a Cli, data: &'a WsDeployData) -> Self {
+        FastbootDeployExecuter { cli, data }
+    }
+
+    fn device(&self) -> &str {
+        match self.data.device().is_empty() {
+            true => "default device",
+            false => self.data.device(),
+        }
+    }
+
+    /*
+     * Run fastboot with the serial of the device if one is selected. If no
+     * device is selected fastboot will pick the only device connected.
+     */
+    fn fastboot(
+        &self,
+        args: &[&str],
+        env_variables: &HashMap<String, String>,
+    ) -> Result<(), BError> {
+        let mut cmd: Vec<String> = vec![String::from("fastboot")];
+        if !self.data.device().is_empty() {
+            cmd.push(String::from("-s"));
+            cmd.push(self.data.device().clone());
+        }
+        cmd.extend(args.iter().map(|a| a.to_string()));
+        self.cli.check_call(&cmd, env_variables, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use crate::data::WsDeployData;
+    use crate::executers::{FastbootDeployExecuter, TaskExecuter};
+    use crate::helper::Helper;
+
+    use std::collections::HashMap;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    /*
+     * Fake fastboot logging every call to a file next to it. Flashing the
+     * partition passed as FAIL_PARTITION is failing.
+     */
+    fn helper_fake_fastboot(bin_dir: &PathBuf) -> PathBuf {
+        let log: PathBuf = bin_dir.join("fastboot.log");
+        let fastboot: PathBuf = bin_dir.join("fastboot");
+        std::fs::create_dir_all(bin_dir).expect("Failed to create dir");
+        std::fs::write(
+            &fastboot,
+            format!(
+                "#!/bin/sh\necho \"$@\" >> {}\ncase \" $@ \" in *\" flash $FAIL_PARTITION \"*) exit 1;; esac\n",
+                log.display()
+            ),
+        )
+        .expect("Failed to write file");
+        std::fs::set_permissions(&fastboot, std::fs::Permissions::from_mode(0o755))
+            .expect("Failed to set permissions");
+        log
+    }
+
+    #[test]
+    fn test_fastboot_deploy_executer() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let product_out: PathBuf = work_dir.join("out/target/product/product");
+        Helper::create_test_files(&vec![
+            artifacts_dir.join("boot.img"),
+            product_out.join("boot.img"),
+            product_out.join("system.img"),
+        ]);
+        let log: PathBuf = helper_fake_fastboot(&work_dir.join("bin"));
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "fastboot",
+            "device": "serial1",
+            "dir": "{}",
+            "productout": "{}",
+            "images": [
+                "boot=boot.img",
+                "system=system.img"
+            ],
+            "slot": "a",
+            "wipe": "true"
+        }}"#,
+            artifacts_dir.display(),
+            product_out.display()
+        );
+        let data: WsDeployData =
+            WsDeployData::from_str(&json_build_config).expect("Failed to parse deploy data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(BSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let mut env: HashMap<String, String> = HashMap::from([(
+            String::from("PATH"),
+            format!("{}:/usr/bin:/bin", work_dir.join("bin").display()),
+        )]);
+        let executer: FastbootDeployExecuter = FastbootDeployExecuter::new(&cli, &data);
+        executer.exec(&env, false, true).expect("Failed to deploy");
+        assert_eq!(
+            std::fs::read_to_string(&log).expect("Failed to read file"),
+            format!(
+                "-s serial1 reboot-bootloader\n-s serial1 -w\n-s serial1 --slot a flash boot {}\n-s serial1 --slot a flash system {}\n-s serial1 --set-active=a\n-s serial1 reboot\n",
+                artifacts_dir.join("boot.img").display(),
+                product_out.join("system.img").display()
+            )
+        );
+        std::fs::remove_file(&log).expect("Failed to remove file");
+        env.insert(String::from("FAIL_PARTITION"), String::from("boot"));
+        let result = executer.exec(&env, false, true);
+        assert!(result.is_err());
+        assert_eq!(
+            std::fs::read_to_string(&log)
+                .expect("Failed to read file")
+                .lines()
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_fastboot_deploy_executer_missing_image() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        Helper::create_test_files(&vec![work_dir.join("artifacts/boot.img")]);
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "fastboot",
+            "device": "",
+            "dir": "{}",
+            "productout": "{}",
+            "images": [
+                "boot=boot.img",
+                "system=system.img",
+                "vendor=vendor.img"
+            ]
+        }}"#,
+            work_dir.join("artifacts").display(),
+            work_dir.join("out").display()
+        );
+        let data: WsDeployData =
+            WsDeployData::from_str(&json_build_config).expect("Failed to parse deploy data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let executer: FastbootDeployExecuter = FastbootDeployExecuter::new(&cli, &data);
+        match executer.exec(&HashMap::new(), false, true) {
+            Ok(_) => panic!("We should have recived an error because images are missing"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                format!(
+                    "Missing image(s) for system 'system.img', vendor 'vendor.img', looked in '{}' and '{}'",
+                    work_dir.join("artifacts").display(),
+                    work_dir.join("out").display()
+                )
+            ),
+        }
+    }
+}
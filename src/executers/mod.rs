@@ -1,15 +1,34 @@
+pub mod adb;
+pub mod apptainer;
 pub mod customsubcmd;
 pub mod docker;
 pub mod executer;
+pub mod fastboot;
+pub mod mounts;
+pub mod oci;
+pub mod resources;
+pub mod s3;
+pub mod upload;
 
+pub use adb::AdbDeployExecuter;
+pub use apptainer::Apptainer;
 pub use customsubcmd::CustomSubCmdExecuter;
 pub use docker::Docker;
 pub use docker::DockerImage;
 pub use executer::{BuildExecuter, CleanExecuter};
+pub use fastboot::FastbootDeployExecuter;
+pub use mounts::DockerMounts;
+pub use oci::OciUploadExecuter;
+pub use resources::DockerResources;
+pub use s3::S3UploadExecuter;
+pub use upload::HttpUploadExecuter;
 
+use crate::cli::Cli;
 use crate::error::BError;
+use crate::workspace::WsSettingsHandler;
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 pub trait TaskExecuter {
     fn exec(
@@ -21,3 +40,60 @@ pub trait TaskExecuter {
         Ok(())
     }
 }
+
+/*
+ * The params used when bootstrapping yaab into a container. Same as for the
+ * CallParams they are wrapped in a struct to keep the contract short.
+ */
+#[derive(Debug, PartialEq)]
+pub struct BootstrapParams {
+    pub cmd_line: Vec<String>,
+    pub docker_top_dir: PathBuf,
+    pub work_dir: PathBuf,
+    pub docker_args: Vec<String>,
+    pub volumes: Vec<String>,
+    pub env: HashMap<String, String>,
+}
+
+/*
+ * The contract for bootstrapping yaab into a container or running a command
+ * in a container independent of the container runtime used by the workspace.
+ */
+pub trait ContainerExecuter {
+    fn bootstrap_yaab(&self, params: &BootstrapParams, cli: &Cli) -> Result<(), BError>;
+
+    fn run_cmd(
+        &self,
+        cmd_line: &[String],
+        env: &HashMap<String, String>,
+        exec_dir: &Path,
+        cli: &Cli,
+    ) -> Result<(), BError>;
+}
+
+/*
+ * Create the container executer for running a command in an image using the
+ * container runtime selected in the workspace settings.
+ */
+pub fn container_executer(
+    settings: &WsSettingsHandler,
+    image: DockerImage,
+    interactive: bool,
+) -> Box<dyn ContainerExecuter> {
+    if settings.apptainer_enabled() {
+        let mut apptainer: Apptainer = Apptainer::new(
+            settings.container_runtime(),
+            &format!("docker://{}", image),
+            interactive,
+        );
+        apptainer.set_mounts(settings.docker_mounts());
+        return Box::new(apptainer);
+    }
+    let mut docker: Docker = Docker::new(image, interactive);
+    docker.set_mounts(settings.docker_mounts());
+    Box::new(docker)
+}
+
+pub fn inside_container() -> bool {
+    Docker::inside_docker() || Apptainer::inside_apptainer()
+}
@@ -0,0 +1,408 @@
+use std::path::PathBuf;
+
+use crate::error::BError;
+
+pub const DOCKER_SOCK: &str = "/var/run/docker.sock";
+/*
+ * When the ssh agent is forwarded the private keys are no longer mounted into
+ * the container only the files needed to connect to a host.
+ */
+pub const SSH_AGENT_MOUNTS: [&str; 2] = [
+    "~/.ssh/known_hosts:~/.ssh/known_hosts:ro",
+    "~/.ssh/config:~/.ssh/config:ro",
+];
+
+/*
+ * A bind mount in the format used by docker 'src:dest[:mode]'. The source can
+ * start with '~' or '$HOME' which will be expanded to the home directory of the
+ * user running yaab.
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mount {
+    pub src: String,
+    pub dest: String,
+    pub mode: String,
+}
+
+impl Mount {
+    pub fn new(mount: &str, home: &str) -> Result<Self, BError> {
+        let parts: Vec<&str> = mount.split(':').collect();
+        if parts.is_empty() || parts.len() > 3 || parts[0].is_empty() {
+            return Err(BError::DockerMountError(format!(
+                "Invalid mount format '{}'",
+                mount
+            )));
+        }
+
+        let src: String = Self::expand_home(parts[0], home);
+        let mut dest: String = src.clone();
+        let mut mode: String = String::new();
+        if parts.len() > 1 && !parts[1].is_empty() {
+            dest = Self::expand_home(parts[1], home);
+        }
+        if parts.len() > 2 {
+            mode = parts[2].to_string();
+        }
+
+        Ok(Mount { src, dest, mode })
+    }
+
+    pub fn expand_home(path: &str, home: &str) -> String {
+        for prefix in ["~", "${HOME}", "$HOME"] {
+            if let Some(rest) = path.strip_prefix(prefix) {
+                return format!("{}{}", home, rest);
+            }
+        }
+        path.to_string()
+    }
+
+    pub fn exists(&self) -> bool {
+        PathBuf::from(&self.src).exists()
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        let mut volume: String = format!("{}:{}", self.src, self.dest);
+        if !self.mode.is_empty() {
+            volume.push_str(&format!(":{}", self.mode));
+        }
+        vec![String::from("-v"), volume]
+    }
+}
+
+/*
+ * The mounts that yaab adds to the container on top of the workspace. The
+ * default mounts are defined by yaab and can be adjusted in the workspace
+ * config by adding and removing mounts or forcing mounts to be read-only.
+ * Instead of mounting ~/.ssh the ssh agent socket and a git credential
+ * helper socket can be forwarded into the container.
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct DockerMounts {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+    pub readonly: Vec<String>,
+    pub docker_sock: bool,
+    pub ssh_agent: bool,
+    pub credential_sock: String,
+}
+
+impl Default for DockerMounts {
+    fn default() -> Self {
+        DockerMounts {
+            add: vec![],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: true,
+            ssh_agent: false,
+            credential_sock: String::new(),
+        }
+    }
+}
+
+impl DockerMounts {
+    fn removed(&self, mount: &Mount, home: &str) -> bool {
+        self.remove.iter().any(|r| {
+            let path: String = Mount::expand_home(r, home);
+            path == mount.src || path == mount.dest
+        })
+    }
+
+    fn readonly(&self, mount: &mut Mount, home: &str) {
+        if self.readonly.iter().any(|r| {
+            let path: String = Mount::expand_home(r, home);
+            path == mount.src || path == mount.dest
+        }) {
+            mount.mode = String::from("ro");
+        }
+    }
+
+    /*
+     * Resolve the final list of docker volume args from the default mounts.
+     * A default mount that dosen't exist on the host is skipped since docker
+     * would otherwise create it as an empty directory owned by root. A mount
+     * added in the workspace config must exist or the container will not
+     * be started.
+     */
+    pub fn resolve(&self, defaults: &[&str], home: &str) -> Result<Vec<String>, BError> {
+        let mut args: Vec<String> = vec![];
+
+        let ssh_dir: String = format!("{}/.ssh", home);
+        let mut defaults: Vec<&str> = defaults.to_vec();
+        if self.ssh_agent {
+            defaults.extend_from_slice(&SSH_AGENT_MOUNTS);
+        }
+
+        /*
+         * When the ssh agent is forwarded ~/.ssh must not be reachable in the
+         * container through a mount of any parent directory like the home
+         * directory mounted into a task container. It is masked by an empty
+         * tmpfs, the files in SSH_AGENT_MOUNTS are mounted on top of it.
+         */
+        let mut masks: Vec<String> = vec![];
+        for d in defaults.iter() {
+            let mut mount: Mount = Mount::new(d, home)?;
+            if (mount.src == DOCKER_SOCK && !self.docker_sock)
+                || (mount.src == ssh_dir && self.ssh_agent)
+                || self.removed(&mount, home)
+            {
+                continue;
+            }
+            if !mount.exists() {
+                continue;
+            }
+            if self.ssh_agent && ssh_dir.starts_with(&format!("{}/", mount.src)) {
+                masks.push(format!("{}{}", mount.dest, &ssh_dir[mount.src.len()..]));
+            }
+            self.readonly(&mut mount, home);
+            args.append(&mut mount.args());
+        }
+
+        for mask in masks.iter() {
+            args.append(&mut vec![
+                String::from("--mount"),
+                format!("type=tmpfs,destination={},tmpfs-mode=0755", mask),
+            ]);
+        }
+
+        for a in self.add.iter() {
+            let mut mount: Mount = Mount::new(a, home)?;
+            if !mount.exists() {
+                return Err(BError::DockerMountError(format!(
+                    "Mount source '{}' does not exist",
+                    mount.src
+                )));
+            }
+            self.readonly(&mut mount, home);
+            args.append(&mut mount.args());
+        }
+
+        Ok(args)
+    }
+
+    /*
+     * The sockets are mounted using the same path inside the container as on
+     * the host so that any nested container started from inside the container
+     * using the docker socket of the host can forward them as well.
+     */
+    pub fn forward(&self, home: &str, ssh_auth_sock: &str) -> Result<Vec<String>, BError> {
+        let mut args: Vec<String> = vec![];
+
+        if self.ssh_agent {
+            if ssh_auth_sock.is_empty() {
+                return Err(BError::DockerMountError(String::from(
+                    "SSH agent forwarding is enabled but SSH_AUTH_SOCK is not set",
+                )));
+            }
+            let mount: Mount = Mount::new(&format!("{}:{}", ssh_auth_sock, ssh_auth_sock), home)?;
+            if !mount.exists() {
+                return Err(BError::DockerMountError(format!(
+                    "SSH agent socket '{}' does not exist",
+                    mount.src
+                )));
+            }
+            args.append(&mut mount.args());
+            args.append(&mut vec![
+                String::from("-e"),
+                format!("SSH_AUTH_SOCK={}", ssh_auth_sock),
+            ]);
+        }
+
+        if !self.credential_sock.is_empty() {
+            let sock: String = Mount::expand_home(&self.credential_sock, home);
+            let mount: Mount = Mount::new(&format!("{}:{}", sock, sock), home)?;
+            if !mount.exists() {
+                return Err(BError::DockerMountError(format!(
+                    "Git credential socket '{}' does not exist",
+                    mount.src
+                )));
+            }
+            args.append(&mut mount.args());
+        }
+
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    use crate::error::BError;
+    use crate::executers::mounts::Mount;
+    use crate::executers::DockerMounts;
+
+    #[test]
+    fn test_mount_home() {
+        let mount: Mount = Mount::new("~/.gitconfig:~/.gitconfig:ro", "/home/user")
+            .expect("Failed to parse mount");
+        assert_eq!(mount.src, "/home/user/.gitconfig");
+        assert_eq!(mount.dest, "/home/user/.gitconfig");
+        assert_eq!(mount.mode, "ro");
+        let mount: Mount = Mount::new("$HOME/.ssh", "/home/user").expect("Failed to parse mount");
+        assert_eq!(
+            mount.args(),
+            vec![
+                String::from("-v"),
+                String::from("/home/user/.ssh:/home/user/.ssh")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mounts_resolve() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let home: PathBuf = PathBuf::from(temp_dir.path());
+        std::fs::create_dir_all(home.join(".ssh")).expect("Failed to create dir");
+        std::fs::create_dir_all(home.join(".docker")).expect("Failed to create dir");
+        std::fs::create_dir_all(home.join("tools")).expect("Failed to create dir");
+        let home_str: String = home.to_string_lossy().to_string();
+        let mounts: DockerMounts = DockerMounts {
+            add: vec![format!("{}/tools:/opt/tools", home_str)],
+            remove: vec![String::from("~/.docker")],
+            readonly: vec![String::from("~/.ssh")],
+            docker_sock: false,
+            ssh_agent: false,
+            credential_sock: String::new(),
+        };
+        let args: Vec<String> = mounts
+            .resolve(
+                &[
+                    "~/.gitconfig:~/.gitconfig:rw",
+                    "~/.ssh:~/.ssh:rw",
+                    "~/.docker:~/.docker",
+                    "/var/run/docker.sock:/var/run/docker.sock",
+                ],
+                &home_str,
+            )
+            .expect("Failed to resolve mounts");
+        assert_eq!(
+            args,
+            vec![
+                String::from("-v"),
+                format!("{}/.ssh:{}/.ssh:ro", home_str, home_str),
+                String::from("-v"),
+                format!("{}/tools:/opt/tools", home_str),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mounts_resolve_missing() {
+        let mounts: DockerMounts = DockerMounts {
+            add: vec![String::from("/does/not/exist:/opt/tools")],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: true,
+            ssh_agent: false,
+            credential_sock: String::new(),
+        };
+        let result: Result<Vec<String>, BError> = mounts.resolve(&[], "/home/user");
+        match result {
+            Ok(_args) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Mount source '/does/not/exist' does not exist"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mounts_ssh_agent() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let home: PathBuf = PathBuf::from(temp_dir.path());
+        std::fs::create_dir_all(home.join(".ssh")).expect("Failed to create dir");
+        std::fs::write(home.join(".ssh/known_hosts"), "").expect("Failed to create file");
+        std::fs::write(home.join("agent.sock"), "").expect("Failed to create file");
+        std::fs::write(home.join("credential.sock"), "").expect("Failed to create file");
+        let home_str: String = home.to_string_lossy().to_string();
+        let mounts: DockerMounts = DockerMounts {
+            add: vec![],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: true,
+            ssh_agent: true,
+            credential_sock: String::from("~/credential.sock"),
+        };
+        let args: Vec<String> = mounts
+            .resolve(&["~/.ssh:~/.ssh:rw"], &home_str)
+            .expect("Failed to resolve mounts");
+        assert_eq!(
+            args,
+            vec![
+                String::from("-v"),
+                format!(
+                    "{}/.ssh/known_hosts:{}/.ssh/known_hosts:ro",
+                    home_str, home_str
+                ),
+            ]
+        );
+
+        /*
+         * The home directory mounted into a task container is not allowed
+         * to expose the private keys in ~/.ssh
+         */
+        let args: Vec<String> = mounts
+            .resolve(&["~:~"], &home_str)
+            .expect("Failed to resolve mounts");
+        assert_eq!(
+            args,
+            vec![
+                String::from("-v"),
+                format!("{}:{}", home_str, home_str),
+                String::from("-v"),
+                format!(
+                    "{}/.ssh/known_hosts:{}/.ssh/known_hosts:ro",
+                    home_str, home_str
+                ),
+                String::from("--mount"),
+                format!("type=tmpfs,destination={}/.ssh,tmpfs-mode=0755", home_str),
+            ]
+        );
+        let sock: String = format!("{}/agent.sock", home_str);
+        let args: Vec<String> = mounts
+            .forward(&home_str, &sock)
+            .expect("Failed to forward sockets");
+        assert_eq!(
+            args,
+            vec![
+                String::from("-v"),
+                format!("{}:{}", sock, sock),
+                String::from("-e"),
+                format!("SSH_AUTH_SOCK={}", sock),
+                String::from("-v"),
+                format!("{}/credential.sock:{}/credential.sock", home_str, home_str),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mounts_ssh_agent_no_sock() {
+        let mounts: DockerMounts = DockerMounts {
+            add: vec![],
+            remove: vec![],
+            readonly: vec![],
+            docker_sock: true,
+            ssh_agent: true,
+            credential_sock: String::new(),
+        };
+        let result: Result<Vec<String>, BError> = mounts.forward("/home/user", "");
+        match result {
+            Ok(_args) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "SSH agent forwarding is enabled but SSH_AUTH_SOCK is not set"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,766 @@
+use crate::cli::Cli;
+use crate::data::WsUploadData;
+use crate::error::BError;
+use crate::executers::upload::{
+    agent, digest, download, fetch_path, file_digest, hex, request_error, retry, upload_files,
+};
+use crate::executers::TaskExecuter;
+use crate::fs::index::ARTIFACTS_INDEX;
+use crate::fs::DockerConfig;
+
+use base64::Engine;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+pub const OCI_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+pub const OCI_EMPTY: &str = "application/vnd.oci.empty.v1+json";
+pub const OCI_LAYER: &str = "application/vnd.oci.image.layer.v1.tar";
+pub const OCI_TITLE: &str = "org.opencontainers.image.title";
+pub const OCI_CREATED: &str = "org.opencontainers.image.created";
+const EMPTY_CONFIG: &[u8] = b"{}";
+
+/*
+ * A blob is either a file from the upload directory or data generated by
+ * yaab like the empty config of the artifact.
+ */
+pub enum Blob<'b> {
+    File(&'b Path),
+    Data(&'b [u8]),
+}
+
+/*
+ * Minimal client for the OCI distribution API. Only the requests needed to
+ * push and pull an artifact are supported. The registry is authenticated
+ * once when the client is created using the credentials for the registry
+ * in the docker config file.
+ */
+pub struct OciClient<'a> {
+    cli: &'a Cli,
+    data: &'a WsUploadData,
+    agent: ureq::Agent,
+    authorization: Option<String>,
+}
+
+impl<'a> OciClient<'a> {
+    pub fn new(
+        cli: &'a Cli,
+        data: &'a WsUploadData,
+        env_variables: &HashMap<String, String>,
+    ) -> Result<Self, BError> {
+        let mut client: OciClient = OciClient {
+            cli,
+            data,
+            agent: agent(),
+            authorization: None,
+        };
+        let credentials: Option<(String, String)> = client.credentials(env_variables)?;
+        client.authorization = client.authenticate(&credentials)?;
+        Ok(client)
+    }
+
+    /*
+     * The registry is the url of the upload node without the scheme. If the
+     * url has no scheme https is used.
+     */
+    pub fn registry(&self) -> &str {
+        let url: &str = self.data.url().trim_end_matches('/');
+        url.split_once("://").map(|(_, r)| r).unwrap_or(url)
+    }
+
+    pub fn reference(&self, tag: &str) -> String {
+        format!("{}/{}:{}", self.registry(), self.data.repository(), tag)
+    }
+
+    fn base(&self) -> String {
+        let url: &str = self.data.url().trim_end_matches('/');
+        if url.contains("://") {
+            return url.to_string();
+        }
+        format!("https://{}", url)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v2/{}/{}", self.base(), self.data.repository(), path)
+    }
+
+    /*
+     * The docker config file is by default read from $DOCKER_CONFIG or
+     * $HOME/.docker which is also mounted when building in docker.
+     */
+    fn credentials(
+        &self,
+        env_variables: &HashMap<String, String>,
+    ) -> Result<Option<(String, String)>, BError> {
+        let mut config: PathBuf = PathBuf::from(self.data.docker_config());
+        if self.data.docker_config().is_empty() {
+            if let Some(dir) = env_variables.get("DOCKER_CONFIG") {
+                config = PathBuf::from(dir).join("config.json");
+            } else if let Some(home) = env_variables.get("HOME") {
+                config = PathBuf::from(home).join(".docker/config.json");
+            } else {
+                return Ok(None);
+            }
+        }
+
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        DockerConfig::from_file(&config)?.credentials(self.registry())
+    }
+
+    fn basic(credentials: &Option<(String, String)>) -> Option<String> {
+        credentials.as_ref().map(|(user, password)| {
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password))
+            )
+        })
+    }
+
+    /*
+     * The registry is telling how to authenticate in the challenge of an
+     * unauthorized request. A registry using basic auth is sent the
+     * credentials with every request while a registry using token auth is
+     * first sent the credentials to the token server to get a token with
+     * pull and push access to the repository.
+     */
+    fn authenticate(
+        &self,
+        credentials: &Option<(String, String)>,
+    ) -> Result<Option<String>, BError> {
+        let ping: String = format!("{}/v2/", self.base());
+        let challenge: Option<String> = retry(
+            self.cli,
+            self.data,
+            &format!("connect to '{}'", ping),
+            || match self.agent.get(&ping).call() {
+                Ok(_) => Ok(None),
+                Err(ureq::Error::Status(401, response)) => Ok(Some(
+                    response
+                        .header("www-authenticate")
+                        .unwrap_or_default()
+                        .to_string(),
+                )),
+                Err(err) => Err(request_error(err)),
+            },
+        )?;
+
+        let challenge: String = match challenge {
+            Some(challenge) => challenge,
+            None => return Ok(None),
+        };
+
+        if challenge.to_lowercase().starts_with("basic") {
+            if credentials.is_none() {
+                return Err(BError::UploadError(format!(
+                    "Registry '{}' requires credentials, run 'docker login {}'",
+                    self.registry(),
+                    self.registry()
+                )));
+            }
+            return Ok(Self::basic(credentials));
+        }
+
+        let params: HashMap<String, String> = Regex::new(r#"(\w+)="([^"]*)""#)
+            .unwrap()
+            .captures_iter(&challenge)
+            .map(|c| (c[1].to_string(), c[2].to_string()))
+            .collect();
+        let realm: &str = params.get("realm").map(|r| r.as_str()).unwrap_or_default();
+        if realm.is_empty() {
+            return Err(BError::UploadError(format!(
+                "Unsupported authentication challenge '{}' from registry '{}'",
+                challenge,
+                self.registry()
+            )));
+        }
+
+        let scope: String = format!("repository:{}:pull,push", self.data.repository());
+        let token: Value = retry(
+            self.cli,
+            self.data,
+            &format!("get token from '{}'", realm),
+            || {
+                let mut request: ureq::Request = self.agent.get(realm).query("scope", &scope);
+                if let Some(service) = params.get("service") {
+                    request = request.query("service", service);
+                }
+                if let Some(auth) = Self::basic(credentials) {
+                    request = request.set("Authorization", &auth);
+                }
+                request
+                    .call()
+                    .map_err(request_error)?
+                    .into_string()
+                    .map_err(|err| (true, err.to_string()))
+                    .and_then(|body| {
+                        serde_json::from_str::<Value>(&body).map_err(|err| (false, err.to_string()))
+                    })
+            },
+        )?;
+
+        match token
+            .get("token")
+            .or_else(|| token.get("access_token"))
+            .and_then(|t| t.as_str())
+        {
+            Some(token) => Ok(Some(format!("Bearer {}", token))),
+            None => Err(BError::UploadError(format!(
+                "No token in response from '{}'",
+                realm
+            ))),
+        }
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let mut request: ureq::Request = self.agent.request(method, url);
+        if let Some(auth) = &self.authorization {
+            request = request.set("Authorization", auth);
+        }
+        request
+    }
+
+    pub fn blob_exists(&self, digest: &str) -> Result<bool, BError> {
+        let url: String = self.url(&format!("blobs/{}", digest));
+        retry(
+            self.cli,
+            self.data,
+            &format!("check '{}'", url),
+            || match self.request("HEAD", &url).call() {
+                Ok(_) => Ok(true),
+                Err(ureq::Error::Status(404, _)) => Ok(false),
+                Err(err) => Err(request_error(err)),
+            },
+        )
+    }
+
+    /*
+     * Push a blob using a monolithic upload. The upload session is started
+     * with a POST and the location returned is used to PUT the content of
+     * the blob together with the digest which is verified by the registry.
+     */
+    pub fn push_blob(&self, digest: &str, size: u64, blob: Blob) -> Result<(), BError> {
+        let url: String = self.url("blobs/uploads/");
+        retry(
+            self.cli,
+            self.data,
+            &format!("push blob '{}'", digest),
+            || {
+                let response: ureq::Response = self
+                    .request("POST", &url)
+                    .send_bytes(&[])
+                    .map_err(request_error)?;
+                let location: String = response.header("location").unwrap_or_default().to_string();
+                if location.is_empty() {
+                    return Err((false, String::from("no upload location from registry")));
+                }
+                let mut upload: String = location.clone();
+                if location.starts_with('/') {
+                    upload = format!("{}{}", self.base(), location);
+                }
+                let separator: char = if upload.contains('?') { '&' } else { '?' };
+                let upload: String = format!("{}{}digest={}", upload, separator, digest);
+                let request: ureq::Request = self
+                    .request("PUT", &upload)
+                    .set("Content-Type", "application/octet-stream")
+                    .set("Content-Length", &size.to_string());
+                match blob {
+                    Blob::File(path) => {
+                        let file: File =
+                            File::open(path).map_err(|err| (false, err.to_string()))?;
+                        request.send(file)
+                    }
+                    Blob::Data(data) => request.send_bytes(data),
+                }
+                .map_err(request_error)?;
+                Ok(())
+            },
+        )
+    }
+
+    pub fn put_manifest(&self, tag: &str, manifest: &[u8]) -> Result<(), BError> {
+        let url: String = self.url(&format!("manifests/{}", tag));
+        retry(
+            self.cli,
+            self.data,
+            &format!("push '{}'", self.reference(tag)),
+            || {
+                self.request("PUT", &url)
+                    .set("Content-Type", OCI_MANIFEST)
+                    .send_bytes(manifest)
+                    .map_err(request_error)?;
+                Ok(())
+            },
+        )
+    }
+
+    pub fn get_manifest(&self, tag: &str) -> Result<Value, BError> {
+        let url: String = self.url(&format!("manifests/{}", tag));
+        retry(
+            self.cli,
+            self.data,
+            &format!("pull '{}'", self.reference(tag)),
+            || {
+                self.request("GET", &url)
+                    .set("Accept", OCI_MANIFEST)
+                    .call()
+                    .map_err(request_error)?
+                    .into_string()
+                    .map_err(|err| (true, err.to_string()))
+                    .and_then(|body| {
+                        serde_json::from_str::<Value>(&body).map_err(|err| (false, err.to_string()))
+                    })
+            },
+        )
+    }
+
+    pub fn get_blob(&self, digest: &str, path: &Path) -> Result<(), BError> {
+        let url: String = self.url(&format!("blobs/{}", digest));
+        retry(
+            self.cli,
+            self.data,
+            &format!("pull blob '{}'", digest),
+            || {
+                let response: ureq::Response =
+                    self.request("GET", &url).call().map_err(request_error)?;
+                let checksum: String =
+                    download(response, path).map_err(|err| (false, err.to_string()))?;
+                if format!("sha256:{}", checksum) != digest {
+                    return Err((true, format!("digest mismatch for '{}'", path.display())));
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct OciUploadExecuter<'a> {
+    cli: &'a Cli,
+    data: &'a WsUploadData,
+}
+
+impl<'a> TaskExecuter for OciUploadExecuter<'a> {
+    fn exec(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dry_run: bool,
+        _interactive: bool,
+    ) -> Result<(), BError> {
+        let dir: PathBuf = PathBuf::from(self.data.dir());
+        let files: Vec<PathBuf> = upload_files(&dir)?;
+        let valid_tag: Regex = Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9_.-]{0,127}$").unwrap();
+        if let Some(tag) = self.data.tags().iter().find(|t| !valid_tag.is_match(t)) {
+            return Err(BError::UploadError(format!("Invalid tag '{}'", tag)));
+        }
+
+        if dry_run {
+            for tag in self.data.tags().iter() {
+                self.cli.info(format!(
+                    "Dry run. Skipping push of {} file(s) from '{}' to '{}/{}:{}'",
+                    files.len(),
+                    dir.display(),
+                    self.data.url(),
+                    self.data.repository(),
+                    tag
+                ));
+            }
+            return Ok(());
+        }
+
+        let client: OciClient = OciClient::new(self.cli, self.data, env_variables)?;
+        let mut layers: Vec<Value> = vec![];
+        for file in files.iter() {
+            let digest: String = format!("sha256:{}", hex(&file_digest::<Sha256>(file)?));
+            let size: u64 = std::fs::metadata(file)?.len();
+            if client.blob_exists(&digest)? {
+                self.cli.info(format!(
+                    "Blob '{}' for '{}' already exists",
+                    digest,
+                    file.display()
+                ));
+            } else {
+                client.push_blob(&digest, size, Blob::File(file))?;
+                self.cli
+                    .info(format!("Pushed '{}' as '{}'", file.display(), digest));
+            }
+            layers.push(json!({
+                "mediaType": OCI_LAYER,
+                "digest": digest,
+                "size": size,
+                "annotations": {
+                    OCI_TITLE: file.strip_prefix(&dir)?.to_string_lossy()
+                }
+            }));
+        }
+
+        let config: String = format!("sha256:{}", hex(&digest::<Sha256>(EMPTY_CONFIG)));
+        if !client.blob_exists(&config)? {
+            client.push_blob(&config, EMPTY_CONFIG.len() as u64, Blob::Data(EMPTY_CONFIG))?;
+        }
+
+        let manifest: Value = json!({
+            "schemaVersion": 2,
+            "mediaType": OCI_MANIFEST,
+            "artifactType": self.data.artifact_type(),
+            "config": {
+                "mediaType": OCI_EMPTY,
+                "digest": config,
+                "size": EMPTY_CONFIG.len()
+            },
+            "layers": layers,
+            "annotations": self.annotations(&dir)?
+        });
+        let manifest: Vec<u8> = serde_json::to_vec(&manifest)?;
+        for tag in self.data.tags().iter() {
+            client.put_manifest(tag, &manifest)?;
+            self.cli.info(format!("Pushed '{}'", client.reference(tag)));
+        }
+
+        self.cli.info(format!(
+            "Pushed {} file(s) to '{}/{}'",
+            files.len(),
+            client.registry(),
+            self.data.repository()
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> OciUploadExecuter<'a> {
+    pub fn new(cli: &'a Cli, data: &'a WsUploadData) -> Self {
+        OciUploadExecuter { cli, data }
+    }
+
+    fn flatten(prefix: &str, value: &Value, annotations: &mut Map<String, Value>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter() {
+                    let key: String = match prefix.is_empty() {
+                        true => key.clone(),
+                        false => format!("{}.{}", prefix, key),
+                    };
+                    Self::flatten(&key, value, annotations);
+                }
+            }
+            Value::String(s) => {
+                annotations.insert(prefix.to_string(), Value::String(s.clone()));
+            }
+            _ => {
+                annotations.insert(prefix.to_string(), Value::String(value.to_string()));
+            }
+        }
+    }
+
+    /*
+     * The content of every manifest artifact in the artifacts index is added
+     * to the annotations of the artifact. Annotations can only be strings so
+     * nested objects are flattened into keys separated by a dot and any other
+     * value is added as json.
+     */
+    fn annotations(&self, dir: &Path) -> Result<Map<String, Value>, BError> {
+        let mut annotations: Map<String, Value> = Map::new();
+        annotations.insert(
+            OCI_CREATED.to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        );
+
+        let index: PathBuf = dir.join(ARTIFACTS_INDEX);
+        if !index.is_file() {
+            return Ok(annotations);
+        }
+
+        let index: Value = serde_json::from_str(&std::fs::read_to_string(&index)?)?;
+        let manifests: Vec<&str> = index
+            .get("artifacts")
+            .and_then(|a| a.as_array())
+            .map(|artifacts| {
+                artifacts
+                    .iter()
+                    .filter(|a| a["type"] == "manifest")
+                    .filter_map(|a| a["path"].as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for manifest in manifests.iter() {
+            let content: Value =
+                serde_json::from_str(&std::fs::read_to_string(dir.join(manifest))?)?;
+            Self::flatten("", &content, &mut annotations);
+        }
+        Ok(annotations)
+    }
+
+    /*
+     * Pull the artifact tagged with the first tag. Every layer is written to
+     * the path in its title annotation and verified against its digest.
+     */
+    pub fn fetch(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dest: &Path,
+    ) -> Result<(), BError> {
+        let client: OciClient = OciClient::new(self.cli, self.data, env_variables)?;
+        let tag: &str = self
+            .data
+            .tags()
+            .first()
+            .map(|t| t.as_str())
+            .unwrap_or("latest");
+        let manifest: Value = client.get_manifest(tag)?;
+        let layers: Vec<Value> = manifest
+            .get("layers")
+            .and_then(|l| l.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut fetch: Vec<(&str, PathBuf, &str)> = vec![];
+        for layer in layers.iter() {
+            let title: &str = layer["annotations"][OCI_TITLE].as_str().unwrap_or_default();
+            fetch.push((
+                title,
+                fetch_path(dest, title, &client.reference(tag))?,
+                layer["digest"].as_str().unwrap_or_default(),
+            ));
+        }
+
+        for (title, path, digest) in fetch.iter() {
+            client.get_blob(digest, path)?;
+            self.cli
+                .info(format!("Fetched '{}' to '{}'", title, path.display()));
+        }
+
+        self.cli.info(format!(
+            "Fetched {} file(s) from '{}'",
+            layers.len(),
+            client.reference(tag)
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use crate::data::WsUploadData;
+    use crate::executers::oci::{OCI_MANIFEST, OCI_TITLE};
+    use crate::executers::upload::{digest, hex};
+    use crate::executers::{OciUploadExecuter, TaskExecuter};
+    use crate::helper::Helper;
+
+    use serde_json::Value;
+    use sha2::Sha256;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::thread::JoinHandle;
+    use tempdir::TempDir;
+
+    type Registry = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /*
+     * Local stand-in for a registry like registry:2 using token auth. It is
+     * keeping the blobs and manifests in memory and is verifying the digest
+     * of every blob pushed.
+     */
+    fn helper_registry(registry: Registry) -> (String, Arc<tiny_http::Server>, JoinHandle<()>) {
+        let server: Arc<tiny_http::Server> =
+            Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("Failed to start server"));
+        let url: String = format!("http://{}", server.server_addr());
+        let realm: String = format!("{}/token", url);
+        let s: Arc<tiny_http::Server> = server.clone();
+        let handle = std::thread::spawn(move || {
+            for mut request in s.incoming_requests() {
+                let auth: String = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.to_string().eq_ignore_ascii_case("Authorization"))
+                    .map(|h| h.value.to_string())
+                    .unwrap_or_default();
+                let mut body: Vec<u8> = vec![];
+                request
+                    .as_reader()
+                    .read_to_end(&mut body)
+                    .expect("Failed to read body");
+                let url: String = request.url().to_string();
+                let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+                let method: String = request.method().to_string();
+                let mut status: u16 = 200;
+                let mut content: Vec<u8> = vec![];
+                let mut headers: Vec<tiny_http::Header> = vec![];
+                if path == "/token" {
+                    if auth == "Basic dXNlcjpzZWNyZXQ="
+                        && query.contains("scope=repository%3Abuilds%2Fproduct%3Apull%2Cpush")
+                    {
+                        content = br#"{"token": "token1"}"#.to_vec();
+                    } else {
+                        status = 401;
+                    }
+                } else if auth != "Bearer token1" {
+                    status = 401;
+                    headers.push(
+                        tiny_http::Header::from_bytes(
+                            "WWW-Authenticate",
+                            format!(r#"Bearer realm="{}",service="registry""#, realm),
+                        )
+                        .unwrap(),
+                    );
+                } else if path == "/v2/" {
+                } else if method == "POST" && path == "/v2/builds/product/blobs/uploads/" {
+                    status = 202;
+                    headers.push(
+                        tiny_http::Header::from_bytes(
+                            "Location",
+                            "/v2/builds/product/blobs/uploads/upload1?state=1",
+                        )
+                        .unwrap(),
+                    );
+                } else if method == "PUT" && path.starts_with("/v2/builds/product/blobs/uploads/") {
+                    let expected: String = query
+                        .rsplit("digest=")
+                        .next()
+                        .unwrap_or_default()
+                        .replace("%3A", ":");
+                    if expected == format!("sha256:{}", hex(&digest::<Sha256>(&body))) {
+                        status = 201;
+                        registry.lock().unwrap().insert(expected, body);
+                    } else {
+                        status = 400;
+                    }
+                } else if let Some(reference) = path.strip_prefix("/v2/builds/product/manifests/") {
+                    if method == "PUT" {
+                        status = 201;
+                        registry.lock().unwrap().insert(reference.to_string(), body);
+                    } else {
+                        content = registry.lock().unwrap()[reference].clone();
+                    }
+                } else if let Some(digest) = path.strip_prefix("/v2/builds/product/blobs/") {
+                    match registry.lock().unwrap().get(digest) {
+                        Some(blob) if method == "GET" => content = blob.clone(),
+                        Some(_) => {}
+                        None => status = 404,
+                    }
+                } else {
+                    status = 404;
+                }
+                let mut reply = tiny_http::Response::from_data(content).with_status_code(status);
+                for header in headers.into_iter() {
+                    reply = reply.with_header(header);
+                }
+                request.respond(reply).expect("Failed to respond");
+            }
+        });
+        (url, server, handle)
+    }
+
+    #[test]
+    fn test_oci_upload_executer() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let fetch_dir: PathBuf = work_dir.join("fetch");
+        let files: Vec<PathBuf> = vec![
+            artifacts_dir.join("dir/file2.txt"),
+            artifacts_dir.join("file1.txt"),
+        ];
+        Helper::create_test_files(&files);
+        std::fs::write(
+            artifacts_dir.join("manifest.json"),
+            r#"{"machine": "qemu", "build": {"id": 42, "variant": "release"}}"#,
+        )
+        .expect("Failed to write file");
+        std::fs::write(
+            artifacts_dir.join("artifacts.json"),
+            r#"{"artifacts": [
+                {"path": "dir/file2.txt", "type": "file"},
+                {"path": "file1.txt", "type": "file"},
+                {"path": "manifest.json", "type": "manifest"}
+            ]}"#,
+        )
+        .expect("Failed to write file");
+        std::fs::create_dir_all(work_dir.join(".docker")).expect("Failed to create dir");
+        let registry: Registry = Arc::new(Mutex::new(HashMap::new()));
+        let (url, server, handle) = helper_registry(registry.clone());
+        std::fs::write(
+            work_dir.join(".docker/config.json"),
+            format!(
+                r#"{{"auths": {{"{}": {{"auth": "dXNlcjpzZWNyZXQ="}}}}}}"#,
+                url.trim_start_matches("http://")
+            ),
+        )
+        .expect("Failed to write file");
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "oci",
+            "url": "{}",
+            "repository": "builds/product",
+            "dir": "{}",
+            "tags": [
+                "1.0.0-20",
+                "latest"
+            ]
+        }}"#,
+            url,
+            artifacts_dir.display()
+        );
+        let data: WsUploadData =
+            WsUploadData::from_str(&json_build_config).expect("Failed to parse upload data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let env: HashMap<String, String> =
+            HashMap::from([(String::from("HOME"), work_dir.to_string_lossy().to_string())]);
+        let executer: OciUploadExecuter = OciUploadExecuter::new(&cli, &data);
+        executer
+            .exec(&env, false, false)
+            .expect("Failed to push artifacts");
+        {
+            let stored = registry.lock().unwrap();
+            assert_eq!(stored["1.0.0-20"], stored["latest"]);
+            let manifest: Value =
+                serde_json::from_slice(&stored["latest"]).expect("Failed to parse manifest");
+            assert_eq!(manifest["mediaType"], OCI_MANIFEST);
+            assert_eq!(manifest["annotations"]["machine"], "qemu");
+            assert_eq!(manifest["annotations"]["build.id"], "42");
+            assert_eq!(manifest["annotations"]["build.variant"], "release");
+            let titles: Vec<&str> = manifest["layers"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| l["annotations"][OCI_TITLE].as_str().unwrap())
+                .collect();
+            assert_eq!(
+                titles,
+                vec![
+                    "artifacts.json",
+                    "dir/file2.txt",
+                    "file1.txt",
+                    "manifest.json"
+                ]
+            );
+            assert!(stored.contains_key(manifest["config"]["digest"].as_str().unwrap()));
+        }
+        executer
+            .fetch(&env, &fetch_dir)
+            .expect("Failed to fetch artifacts");
+        for file in files.iter() {
+            assert_eq!(
+                std::fs::read(fetch_dir.join(file.strip_prefix(&artifacts_dir).unwrap()))
+                    .expect("Failed to read file"),
+                std::fs::read(file).expect("Failed to read file")
+            );
+        }
+        assert!(executer.exec(&HashMap::new(), false, false).is_err());
+        server.unblock();
+        handle.join().expect("Failed to join server");
+    }
+}
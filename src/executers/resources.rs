@@ -0,0 +1,199 @@
+use serde_json::Value;
+
+use crate::configs::{Config, Context};
+use crate::error::BError;
+
+/*
+ * The resources available to a container. The resources can be set for the
+ * workspace in the docker node of the workspace config and for a task in the
+ * task node of the build config. Any resource set for a task is overriding
+ * the resource set for the workspace.
+ */
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DockerResources {
+    pub memory: String,
+    pub cpus: String,
+    pub shm_size: String,
+    pub ulimits: Vec<String>,
+    pub devices: Vec<String>,
+}
+
+impl Config for DockerResources {}
+
+impl DockerResources {
+    pub fn from_value(data: &Value) -> Result<Self, BError> {
+        match Self::get_value("resources", data) {
+            Ok(resources_data) => Self::new(resources_data),
+            Err(_err) => Ok(DockerResources::default()),
+        }
+    }
+
+    pub fn new(data: &Value) -> Result<Self, BError> {
+        let memory: String = Self::get_str_value("memory", data, Some(String::from("")))?;
+        let cpus: String = Self::get_str_value("cpus", data, Some(String::from("")))?;
+        let shm_size: String = Self::get_str_value("shmsize", data, Some(String::from("")))?;
+        let ulimits: Vec<String> = Self::get_array_value("ulimits", data, Some(vec![]))?;
+        let devices: Vec<String> = Self::get_array_value("devices", data, Some(vec![]))?;
+
+        Ok(DockerResources {
+            memory,
+            cpus,
+            shm_size,
+            ulimits,
+            devices,
+        })
+    }
+
+    pub fn expand_ctx(&mut self, ctx: &Context) -> Result<(), BError> {
+        self.memory = ctx.expand_str(&self.memory)?;
+        self.cpus = ctx.expand_str(&self.cpus)?;
+        self.shm_size = ctx.expand_str(&self.shm_size)?;
+        for ulimit in self.ulimits.iter_mut() {
+            *ulimit = ctx.expand_str(ulimit)?;
+        }
+        for device in self.devices.iter_mut() {
+            *device = ctx.expand_str(device)?;
+        }
+        Ok(())
+    }
+
+    /*
+     * Merge the resources of a task on top of the workspace resources. A ulimit
+     * set for the task replaces the workspace ulimit with the same name and the
+     * devices are added to the devices of the workspace.
+     */
+    pub fn merge(&self, other: &DockerResources) -> DockerResources {
+        let pick = |a: &String, b: &String| -> String {
+            if b.is_empty() {
+                return a.clone();
+            }
+            b.clone()
+        };
+        let name = |u: &String| -> String { u.split('=').next().unwrap_or_default().to_string() };
+
+        let mut ulimits: Vec<String> = self
+            .ulimits
+            .iter()
+            .filter(|u| !other.ulimits.iter().any(|o| name(o) == name(u)))
+            .cloned()
+            .collect();
+        ulimits.extend(other.ulimits.iter().cloned());
+
+        let mut devices: Vec<String> = self.devices.clone();
+        other.devices.iter().for_each(|d| {
+            if !devices.contains(d) {
+                devices.push(d.clone());
+            }
+        });
+
+        DockerResources {
+            memory: pick(&self.memory, &other.memory),
+            cpus: pick(&self.cpus, &other.cpus),
+            shm_size: pick(&self.shm_size, &other.shm_size),
+            ulimits,
+            devices,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == DockerResources::default()
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![];
+        if !self.memory.is_empty() {
+            args.append(&mut vec![String::from("--memory"), self.memory.clone()]);
+        }
+        if !self.cpus.is_empty() {
+            args.append(&mut vec![String::from("--cpus"), self.cpus.clone()]);
+        }
+        if !self.shm_size.is_empty() {
+            args.append(&mut vec![String::from("--shm-size"), self.shm_size.clone()]);
+        }
+        self.ulimits.iter().for_each(|u| {
+            args.append(&mut vec![String::from("--ulimit"), u.clone()]);
+        });
+        self.devices.iter().for_each(|d| {
+            args.append(&mut vec![String::from("--device"), d.clone()]);
+        });
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::executers::DockerResources;
+
+    #[test]
+    fn test_resources_default() {
+        let data: Value = serde_json::from_str(r#"{}"#).expect("Failed to parse json");
+        let resources: DockerResources =
+            DockerResources::from_value(&data).expect("Failed to parse resources");
+        assert_eq!(resources, DockerResources::default());
+        assert!(resources.args().is_empty());
+    }
+
+    #[test]
+    fn test_resources_args() {
+        let data: Value = serde_json::from_str(
+            r#"
+        {
+            "resources": {
+                "memory": "32g",
+                "cpus": "8",
+                "shmsize": "2g",
+                "ulimits": [
+                    "nofile=1024:4096"
+                ],
+                "devices": [
+                    "/dev/bus/usb"
+                ]
+            }
+        }"#,
+        )
+        .expect("Failed to parse json");
+        let resources: DockerResources =
+            DockerResources::from_value(&data).expect("Failed to parse resources");
+        assert_eq!(
+            resources.args(),
+            vec![
+                "--memory",
+                "32g",
+                "--cpus",
+                "8",
+                "--shm-size",
+                "2g",
+                "--ulimit",
+                "nofile=1024:4096",
+                "--device",
+                "/dev/bus/usb"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resources_merge() {
+        let workspace: DockerResources = DockerResources {
+            memory: String::from("8g"),
+            cpus: String::from("4"),
+            shm_size: String::new(),
+            ulimits: vec![String::from("nofile=1024:4096"), String::from("nproc=512")],
+            devices: vec![String::from("/dev/kvm")],
+        };
+        let task: DockerResources = DockerResources {
+            memory: String::from("64g"),
+            cpus: String::new(),
+            shm_size: String::from("2g"),
+            ulimits: vec![String::from("nofile=65536:65536")],
+            devices: vec![String::from("/dev/bus/usb"), String::from("/dev/kvm")],
+        };
+        let resources: DockerResources = workspace.merge(&task);
+        assert_eq!(resources.memory, "64g");
+        assert_eq!(resources.cpus, "4");
+        assert_eq!(resources.shm_size, "2g");
+        assert_eq!(resources.ulimits, vec!["nproc=512", "nofile=65536:65536"]);
+        assert_eq!(resources.devices, vec!["/dev/kvm", "/dev/bus/usb"]);
+    }
+}
@@ -0,0 +1,814 @@
+use crate::cli::Cli;
+use crate::data::WsUploadData;
+use crate::error::BError;
+use crate::executers::upload::{
+    agent, digest, download, fetch_path, file_digest, hex, request_error, retry, upload_files,
+    uri_encode,
+};
+use crate::executers::TaskExecuter;
+use crate::fs::parse_size;
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const S3_META_SHA256: &str = "x-amz-meta-sha256";
+/*
+ * The limits of a multipart upload. Every part except the last must be at
+ * least 5 MiB and a multipart upload can have at most 10000 parts.
+ */
+pub const S3_MIN_PART_SIZE: u64 = 5 << 20;
+pub const S3_MAX_PART_SIZE: u64 = 5 << 30;
+pub const S3_MAX_PARTS: u64 = 10000;
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/*
+ * Minimal client for S3 compatible object storage like MinIO using path style
+ * requests signed with AWS signature version 4. Only the requests needed to
+ * upload and download artifacts are supported.
+ */
+pub struct S3Client<'a> {
+    cli: &'a Cli,
+    data: &'a WsUploadData,
+    access_key: String,
+    secret_key: String,
+    agent: ureq::Agent,
+}
+
+impl<'a> S3Client<'a> {
+    pub fn new(
+        cli: &'a Cli,
+        data: &'a WsUploadData,
+        env_variables: &HashMap<String, String>,
+    ) -> Result<Self, BError> {
+        match (
+            env_variables.get(data.access_key_env()),
+            env_variables.get(data.secret_key_env()),
+        ) {
+            (Some(access_key), Some(secret_key)) => Ok(S3Client {
+                cli,
+                data,
+                access_key: access_key.clone(),
+                secret_key: secret_key.clone(),
+                agent: agent(),
+            }),
+            _ => Err(BError::UploadError(format!(
+                "Missing S3 credentials, '{}' and '{}' must be set in the env",
+                data.access_key_env(),
+                data.secret_key_env()
+            ))),
+        }
+    }
+
+    /*
+     * The object key of a file is the expanded path template used as prefix
+     * followed by the path of the file relative to the upload directory.
+     */
+    pub fn key(&self, relative: &str) -> String {
+        vec![self.data.path().as_str(), relative]
+            .into_iter()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<&str>>()
+            .join("/")
+    }
+
+    pub fn url(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.data.bucket(), key)
+    }
+
+    fn xml_unescape(value: &str) -> String {
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+        let mut mac: Hmac<Sha256> =
+            <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        Mac::update(&mut mac, data.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /*
+     * The host is signed so it is set explicitly to make sure the host header
+     * sent is the same as the one signed. Default ports are dropped the same
+     * way a http client would.
+     */
+    fn host(&self) -> String {
+        let url: &str = self.data.url();
+        let (scheme, without_scheme): (&str, &str) = url.split_once("://").unwrap_or(("", url));
+        let authority: &str = without_scheme.split('/').next().unwrap_or_default();
+        match (scheme, authority.rsplit_once(':')) {
+            ("http", Some((host, "80"))) | ("https", Some((host, "443"))) => host.to_string(),
+            _ => authority.to_string(),
+        }
+    }
+
+    /*
+     * Create a request signed using AWS signature version 4. The payload is
+     * either the hex encoded sha256 of the body which is verified by the
+     * server or UNSIGNED-PAYLOAD.
+     */
+    fn request(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, String)],
+        payload: &str,
+    ) -> ureq::Request {
+        let now: chrono::DateTime<chrono::Utc> = chrono::Utc::now();
+        let amz_date: String = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date: String = now.format("%Y%m%d").to_string();
+        let scope: String = format!("{}/{}/s3/aws4_request", date, self.data.region());
+
+        let mut uri: String = format!("/{}", uri_encode(self.data.bucket(), true));
+        if !key.is_empty() {
+            uri.push_str(&format!("/{}", uri_encode(key, false)));
+        }
+
+        let mut query_params: Vec<String> = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        query_params.sort();
+        let canonical_query: String = query_params.join("&");
+
+        let mut signed: Vec<(String, String)> = headers
+            .iter()
+            .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+            .collect();
+        signed.push((String::from("host"), self.host()));
+        signed.push((String::from("x-amz-content-sha256"), payload.to_string()));
+        signed.push((String::from("x-amz-date"), amz_date.clone()));
+        signed.sort();
+
+        let canonical_headers: String = signed
+            .iter()
+            .map(|(k, v)| format!("{}:{}\n", k, v))
+            .collect();
+        let signed_headers: String = signed
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect::<Vec<&str>>()
+            .join(";");
+        let canonical_request: String = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, uri, canonical_query, canonical_headers, signed_headers, payload
+        );
+        let string_to_sign: String = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&digest::<Sha256>(canonical_request.as_bytes()))
+        );
+
+        let mut signing_key: Vec<u8> =
+            Self::hmac(format!("AWS4{}", self.secret_key).as_bytes(), &date);
+        for part in [self.data.region().as_str(), "s3", "aws4_request"] {
+            signing_key = Self::hmac(&signing_key, part);
+        }
+        let signature: String = hex(&Self::hmac(&signing_key, &string_to_sign));
+
+        let mut url: String = format!("{}{}", self.data.url().trim_end_matches('/'), uri);
+        if !canonical_query.is_empty() {
+            url.push_str(&format!("?{}", canonical_query));
+        }
+
+        let mut request: ureq::Request = self.agent.request(method, &url);
+        for (k, v) in signed.iter() {
+            request = request.set(k, v);
+        }
+        request.set(
+            "Authorization",
+            &format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key, scope, signed_headers, signature
+            ),
+        )
+    }
+
+    fn payload(&self, sha256: &[u8]) -> String {
+        if self.data.checksums().iter().any(|c| c == "sha256") {
+            return hex(sha256);
+        }
+        UNSIGNED_PAYLOAD.to_string()
+    }
+
+    fn content_md5(&self, headers: &mut Vec<(&str, String)>, md5: Vec<u8>) {
+        if self.data.checksums().iter().any(|c| c == "md5") {
+            headers.push((
+                "content-md5",
+                base64::engine::general_purpose::STANDARD.encode(md5),
+            ));
+        }
+    }
+
+    pub fn put_object(&self, path: &Path, key: &str) -> Result<(), BError> {
+        let size: u64 = std::fs::metadata(path)?.len();
+        let sha256: Vec<u8> = file_digest::<Sha256>(path)?;
+        let mut headers: Vec<(&str, String)> = vec![
+            ("content-length", size.to_string()),
+            (S3_META_SHA256, hex(&sha256)),
+        ];
+        self.content_md5(&mut headers, file_digest::<md5::Md5>(path)?);
+
+        retry(
+            self.cli,
+            self.data,
+            &format!("upload '{}' to '{}'", path.display(), self.url(key)),
+            || {
+                let file: File = File::open(path).map_err(|err| (false, err.to_string()))?;
+                self.request("PUT", key, &[], &headers, &self.payload(&sha256))
+                    .send(file)
+                    .map_err(request_error)?;
+                Ok(())
+            },
+        )
+    }
+
+    /*
+     * Upload a large file in parts. If any part fails the multipart upload is
+     * aborted so the parts already uploaded are not left behind in the bucket.
+     */
+    pub fn put_multipart(&self, path: &Path, key: &str, part_size: u64) -> Result<(), BError> {
+        let what: String = format!("upload '{}' to '{}'", path.display(), self.url(key));
+        let sha256: String = hex(&file_digest::<Sha256>(path)?);
+        let empty: Vec<u8> = digest::<Sha256>(&[]);
+        let upload_id: String = retry(self.cli, self.data, &what, || {
+            let body: String = self
+                .request(
+                    "POST",
+                    key,
+                    &[("uploads", "")],
+                    &[(S3_META_SHA256, sha256.clone())],
+                    &hex(&empty),
+                )
+                .call()
+                .map_err(request_error)?
+                .into_string()
+                .map_err(|err| (true, err.to_string()))?;
+            Regex::new(r"<UploadId>(.*?)</UploadId>")
+                .unwrap()
+                .captures(&body)
+                .map(|c| Self::xml_unescape(&c[1]))
+                .ok_or((false, String::from("missing upload id in response")))
+        })?;
+
+        match self.put_parts(path, key, part_size, &upload_id, &what) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = self
+                    .request(
+                        "DELETE",
+                        key,
+                        &[("uploadId", &upload_id)],
+                        &[],
+                        &hex(&empty),
+                    )
+                    .call();
+                Err(err)
+            }
+        }
+    }
+
+    fn put_parts(
+        &self,
+        path: &Path,
+        key: &str,
+        part_size: u64,
+        upload_id: &str,
+        what: &str,
+    ) -> Result<(), BError> {
+        let mut file: File = File::open(path)?;
+        let mut etags: Vec<String> = vec![];
+        loop {
+            let mut part: Vec<u8> = vec![];
+            (&mut file).take(part_size).read_to_end(&mut part)?;
+            if part.is_empty() {
+                break;
+            }
+
+            let part_number: String = (etags.len() + 1).to_string();
+            let mut headers: Vec<(&str, String)> = vec![];
+            self.content_md5(&mut headers, digest::<md5::Md5>(&part));
+            let payload: String = self.payload(&digest::<Sha256>(&part));
+            let etag: String = retry(self.cli, self.data, what, || {
+                let response: ureq::Response = self
+                    .request(
+                        "PUT",
+                        key,
+                        &[("partNumber", &part_number), ("uploadId", upload_id)],
+                        &headers,
+                        &payload,
+                    )
+                    .send_bytes(&part)
+                    .map_err(request_error)?;
+                response
+                    .header("ETag")
+                    .map(|e| e.to_string())
+                    .ok_or((false, String::from("missing etag in response")))
+            })?;
+            self.cli.debug(format!(
+                "Uploaded part {} of '{}'",
+                part_number,
+                path.display()
+            ));
+            etags.push(etag);
+        }
+
+        let body: String = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            etags
+                .iter()
+                .enumerate()
+                .map(|(i, etag)| format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    i + 1,
+                    etag
+                ))
+                .collect::<String>()
+        );
+        let payload: String = hex(&digest::<Sha256>(body.as_bytes()));
+        retry(self.cli, self.data, what, || {
+            let response: String = self
+                .request("POST", key, &[("uploadId", upload_id)], &[], &payload)
+                .send_string(&body)
+                .map_err(request_error)?
+                .into_string()
+                .map_err(|err| (true, err.to_string()))?;
+            if response.contains("<Error>") {
+                return Err((false, response));
+            }
+            Ok(())
+        })
+    }
+
+    /*
+     * List all the keys with the prefix. The listing is paginated by the
+     * server so the continuation token is followed until all keys are listed.
+     */
+    pub fn list(&self, prefix: &str) -> Result<Vec<String>, BError> {
+        let mut keys: Vec<String> = vec![];
+        let mut token: String = String::new();
+        let empty: String = hex(&digest::<Sha256>(&[]));
+        let key_regex: Regex = Regex::new(r"<Key>(.*?)</Key>").unwrap();
+        let token_regex: Regex =
+            Regex::new(r"<NextContinuationToken>(.*?)</NextContinuationToken>").unwrap();
+        loop {
+            let body: String = retry(
+                self.cli,
+                self.data,
+                &format!("list '{}'", self.url(prefix)),
+                || {
+                    let mut query: Vec<(&str, &str)> = vec![("list-type", "2"), ("prefix", prefix)];
+                    if !token.is_empty() {
+                        query.push(("continuation-token", &token));
+                    }
+                    self.request("GET", "", &query, &[], &empty)
+                        .call()
+                        .map_err(request_error)?
+                        .into_string()
+                        .map_err(|err| (true, err.to_string()))
+                },
+            )?;
+            key_regex
+                .captures_iter(&body)
+                .for_each(|c| keys.push(Self::xml_unescape(&c[1])));
+            match token_regex.captures(&body) {
+                Some(c) if body.contains("<IsTruncated>true</IsTruncated>") => {
+                    token = Self::xml_unescape(&c[1]);
+                }
+                _ => break,
+            }
+        }
+        Ok(keys)
+    }
+
+    /*
+     * Download an object. If the object was uploaded by yaab the sha256 of the
+     * file is stored in the object metadata and used to verify the download.
+     */
+    pub fn get_object(&self, key: &str, path: &Path) -> Result<(), BError> {
+        let empty: String = hex(&digest::<Sha256>(&[]));
+        retry(
+            self.cli,
+            self.data,
+            &format!("fetch '{}'", self.url(key)),
+            || {
+                let response: ureq::Response = self
+                    .request("GET", key, &[], &[], &empty)
+                    .call()
+                    .map_err(request_error)?;
+                let expected: String = response
+                    .header(S3_META_SHA256)
+                    .unwrap_or_default()
+                    .to_string();
+                let sha256: String =
+                    download(response, path).map_err(|err| (false, err.to_string()))?;
+                if !expected.is_empty() && expected != sha256 {
+                    return Err((true, format!("checksum mismatch for '{}'", path.display())));
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct S3UploadExecuter<'a> {
+    cli: &'a Cli,
+    data: &'a WsUploadData,
+}
+
+impl<'a> TaskExecuter for S3UploadExecuter<'a> {
+    fn exec(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dry_run: bool,
+        _interactive: bool,
+    ) -> Result<(), BError> {
+        let part_size: u64 = match parse_size(self.data.part_size()) {
+            Some(size) if (S3_MIN_PART_SIZE..=S3_MAX_PART_SIZE).contains(&size) => size,
+            _ => {
+                return Err(BError::UploadError(format!(
+                    "Invalid part size '{}', must be between 5M and 5G",
+                    self.data.part_size()
+                )));
+            }
+        };
+        let dir: PathBuf = PathBuf::from(self.data.dir());
+        let files: Vec<PathBuf> = upload_files(&dir)?;
+
+        if dry_run {
+            for file in files.iter() {
+                self.cli.info(format!(
+                    "Dry run. Skipping upload of '{}' to 's3://{}/{}'",
+                    file.display(),
+                    self.data.bucket(),
+                    file.strip_prefix(&dir)?.display()
+                ));
+            }
+            return Ok(());
+        }
+
+        let client: S3Client = S3Client::new(self.cli, self.data, env_variables)?;
+        for file in files.iter() {
+            let key: String = client.key(&file.strip_prefix(&dir)?.to_string_lossy());
+            let size: u64 = std::fs::metadata(file)?.len();
+            if size > part_size {
+                client.put_multipart(file, &key, Self::file_part_size(size, part_size))?;
+            } else {
+                client.put_object(file, &key)?;
+            }
+            self.cli.info(format!(
+                "Uploaded '{}' to '{}'",
+                file.display(),
+                client.url(&key)
+            ));
+        }
+
+        self.cli.info(format!(
+            "Uploaded {} file(s) to '{}'",
+            files.len(),
+            client.url(&client.key(""))
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> S3UploadExecuter<'a> {
+    pub fn new(cli: &'a Cli, data: &'a WsUploadData) -> Self {
+        S3UploadExecuter { cli, data }
+    }
+
+    /*
+     * The part size is raised for a file that would otherwise need more
+     * parts than allowed in a multipart upload.
+     */
+    fn file_part_size(size: u64, part_size: u64) -> u64 {
+        part_size.max(size.div_ceil(S3_MAX_PARTS))
+    }
+
+    /*
+     * Download all the objects with the expanded path template as prefix
+     * keeping the layout relative to the prefix.
+     */
+    pub fn fetch(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dest: &Path,
+    ) -> Result<(), BError> {
+        let client: S3Client = S3Client::new(self.cli, self.data, env_variables)?;
+        let mut prefix: String = client.key("");
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+
+        let keys: Vec<String> = client.list(&prefix)?;
+        if keys.is_empty() {
+            return Err(BError::UploadError(format!(
+                "No artifacts found in '{}'",
+                client.url(&prefix)
+            )));
+        }
+
+        let mut fetch: Vec<(&String, PathBuf)> = vec![];
+        for key in keys.iter() {
+            let relative: &str = key.strip_prefix(&prefix).unwrap_or(key);
+            fetch.push((key, fetch_path(dest, relative, &client.url(&prefix))?));
+        }
+
+        for (key, path) in fetch.iter() {
+            client.get_object(key, path)?;
+            self.cli.info(format!(
+                "Fetched '{}' to '{}'",
+                client.url(key),
+                path.display()
+            ));
+        }
+
+        self.cli.info(format!(
+            "Fetched {} file(s) from '{}'",
+            keys.len(),
+            client.url(&prefix)
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use crate::data::WsUploadData;
+    use crate::executers::s3::{S3_MAX_PARTS, S3_MIN_PART_SIZE};
+    use crate::executers::upload::{digest, hex};
+    use crate::executers::{S3UploadExecuter, TaskExecuter};
+    use crate::helper::Helper;
+
+    use base64::Engine;
+    use sha2::Sha256;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::thread::JoinHandle;
+    use tempdir::TempDir;
+
+    type Objects = Arc<Mutex<HashMap<String, (Vec<u8>, String)>>>;
+
+    /*
+     * Local stand-in for an S3 compatible server like MinIO. It is keeping
+     * the objects in memory and supports put, multipart upload, list and get.
+     * Every request is required to be signed and a Content-MD5 header is
+     * verified against the body.
+     */
+    fn helper_s3_server(objects: Objects) -> (String, Arc<tiny_http::Server>, JoinHandle<usize>) {
+        let server: Arc<tiny_http::Server> =
+            Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("Failed to start server"));
+        let url: String = format!("http://{}", server.server_addr());
+        let s: Arc<tiny_http::Server> = server.clone();
+        let handle = std::thread::spawn(move || {
+            let mut parts: Vec<Vec<u8>> = vec![];
+            let mut meta: String = String::new();
+            let mut multipart: usize = 0;
+            for mut request in s.incoming_requests() {
+                let header = |name: &str| -> String {
+                    request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.to_string().eq_ignore_ascii_case(name))
+                        .map(|h| h.value.to_string())
+                        .unwrap_or_default()
+                };
+                let auth: String = header("Authorization");
+                let md5: String = header("Content-MD5");
+                let sha256: String = header("x-amz-meta-sha256");
+                let mut body: Vec<u8> = vec![];
+                request
+                    .as_reader()
+                    .read_to_end(&mut body)
+                    .expect("Failed to read body");
+                let url: String = request.url().to_string();
+                let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+                let key: String = path.trim_start_matches("/builds/").to_string();
+                if !auth.starts_with("AWS4-HMAC-SHA256 Credential=access/") {
+                    request
+                        .respond(tiny_http::Response::empty(403))
+                        .expect("Failed to respond");
+                    continue;
+                }
+                if !md5.is_empty()
+                    && md5
+                        != base64::engine::general_purpose::STANDARD
+                            .encode(digest::<md5::Md5>(&body))
+                {
+                    request
+                        .respond(tiny_http::Response::empty(400))
+                        .expect("Failed to respond");
+                    continue;
+                }
+                let mut response: String = String::new();
+                let mut content: Vec<u8> = vec![];
+                let method: String = request.method().to_string();
+                match (method.as_str(), query) {
+                    ("POST", "uploads=") => {
+                        multipart += 1;
+                        parts.clear();
+                        meta = sha256;
+                        response = String::from("<UploadId>upload1</UploadId>");
+                    }
+                    ("PUT", q) if q.contains("partNumber") => {
+                        parts.push(body);
+                    }
+                    ("POST", _) => {
+                        objects
+                            .lock()
+                            .unwrap()
+                            .insert(key, (parts.concat(), meta.clone()));
+                    }
+                    ("PUT", _) => {
+                        objects.lock().unwrap().insert(key, (body, sha256));
+                    }
+                    ("GET", q) if q.contains("list-type=2") => {
+                        let keys: Vec<String> = objects.lock().unwrap().keys().cloned().collect();
+                        response = keys
+                            .iter()
+                            .map(|k| format!("<Contents><Key>{}</Key></Contents>", k))
+                            .collect();
+                    }
+                    ("GET", _) => {
+                        let (data, sha256) = objects.lock().unwrap()[&key].clone();
+                        content = data;
+                        meta = sha256;
+                    }
+                    _ => {}
+                }
+                let mut reply = tiny_http::Response::from_data(if content.is_empty() {
+                    response.into_bytes()
+                } else {
+                    content
+                })
+                .with_header(tiny_http::Header::from_bytes("ETag", "\"etag\"").unwrap());
+                if method == "GET" && !query.contains("list-type") {
+                    reply = reply.with_header(
+                        tiny_http::Header::from_bytes("x-amz-meta-sha256", meta.as_bytes())
+                            .unwrap(),
+                    );
+                }
+                request.respond(reply).expect("Failed to respond");
+            }
+            multipart
+        });
+        (url, server, handle)
+    }
+
+    #[test]
+    fn test_s3_upload_executer() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let fetch_dir: PathBuf = work_dir.join("fetch");
+        let files: Vec<PathBuf> = vec![
+            artifacts_dir.join("dir/file2.txt"),
+            artifacts_dir.join("file1.txt"),
+        ];
+        Helper::create_test_files(&files);
+        // Large enough to be uploaded in two parts
+        for (i, file) in files.iter().enumerate() {
+            let mut content: Vec<u8> = std::fs::read(file).expect("Failed to read file");
+            content.resize((5 << 20) + 1024, i as u8);
+            std::fs::write(file, content).expect("Failed to write file");
+        }
+        std::fs::write(artifacts_dir.join("small.txt"), "small").expect("Failed to write file");
+        let objects: Objects = Arc::new(Mutex::new(HashMap::new()));
+        let (url, server, handle) = helper_s3_server(objects.clone());
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "s3",
+            "url": "{}",
+            "bucket": "builds",
+            "path": "product/42",
+            "dir": "{}",
+            "partsize": "5M",
+            "checksums": [
+                "sha256",
+                "md5"
+            ]
+        }}"#,
+            url,
+            artifacts_dir.display()
+        );
+        let data: WsUploadData =
+            WsUploadData::from_str(&json_build_config).expect("Failed to parse upload data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let env: HashMap<String, String> = HashMap::from([
+            (String::from("AWS_ACCESS_KEY_ID"), String::from("access")),
+            (
+                String::from("AWS_SECRET_ACCESS_KEY"),
+                String::from("secret"),
+            ),
+        ]);
+        let executer: S3UploadExecuter = S3UploadExecuter::new(&cli, &data);
+        executer
+            .exec(&env, false, false)
+            .expect("Failed to upload artifacts");
+        {
+            let stored = objects.lock().unwrap();
+            assert_eq!(stored.len(), 3);
+            for file in files.iter() {
+                let key: String = format!(
+                    "product/42/{}",
+                    file.strip_prefix(&artifacts_dir).unwrap().display()
+                );
+                let content: Vec<u8> = std::fs::read(file).expect("Failed to read file");
+                assert_eq!(stored[&key].0, content);
+                assert_eq!(stored[&key].1, hex(&digest::<Sha256>(&content)));
+            }
+            assert_eq!(stored["product/42/small.txt"].0, b"small");
+        }
+        executer
+            .fetch(&env, &fetch_dir)
+            .expect("Failed to fetch artifacts");
+        for file in files.iter() {
+            assert_eq!(
+                std::fs::read(fetch_dir.join(file.strip_prefix(&artifacts_dir).unwrap()))
+                    .expect("Failed to read file"),
+                std::fs::read(file).expect("Failed to read file")
+            );
+        }
+        objects.lock().unwrap().insert(
+            String::from("product/42/../evil.txt"),
+            (b"evil".to_vec(), String::new()),
+        );
+        match executer.fetch(&env, &fetch_dir) {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid path '../evil.txt' in 's3://builds/product/42/'"
+                );
+            }
+        }
+        assert!(!work_dir.join("evil.txt").exists());
+        assert!(executer.fetch(&HashMap::new(), &fetch_dir).is_err());
+        server.unblock();
+        assert_eq!(handle.join().expect("Failed to join server"), 2);
+    }
+
+    #[test]
+    fn test_s3_part_size() {
+        assert_eq!(
+            S3UploadExecuter::file_part_size(1 << 30, S3_MIN_PART_SIZE),
+            S3_MIN_PART_SIZE
+        );
+        assert_eq!(
+            S3UploadExecuter::file_part_size(100 << 30, S3_MIN_PART_SIZE),
+            (100 << 30) / S3_MAX_PARTS + 1
+        );
+        let json_build_config: &str = r#"
+        {
+            "type": "s3",
+            "url": "http://127.0.0.1:9000",
+            "bucket": "builds",
+            "dir": "/tmp/yaab-missing-artifacts",
+            "partsize": "1M"
+        }"#;
+        let data: WsUploadData =
+            WsUploadData::from_str(json_build_config).expect("Failed to parse upload data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let executer: S3UploadExecuter = S3UploadExecuter::new(&cli, &data);
+        match executer.exec(&HashMap::new(), true, false) {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid part size '1M', must be between 5M and 5G"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,698 @@
+use crate::cli::{signal, Cli};
+use crate::data::WsUploadData;
+use crate::error::BError;
+use crate::executers::TaskExecuter;
+use crate::fs::index::{ARTIFACTS_INDEX, ARTIFACTS_SHA256SUMS};
+use crate::fs::{ArtifactsIndex, Netrc};
+
+use base64::Engine;
+use serde_json::Value;
+use sha2::Digest;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+fn find_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), BError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry: std::fs::DirEntry = entry?;
+        // Symlinks like the latest link are not followed
+        let file_type: std::fs::FileType = entry.file_type()?;
+        if file_type.is_dir() {
+            find_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/*
+ * All the files in the upload directory including the files in any sub
+ * directory sorted by path. If the upload directory has an artifacts index
+ * only the artifacts in the index are uploaded together with the index and
+ * the SHA256SUMS file so any other build collected below the directory is
+ * left out.
+ */
+pub fn upload_files(dir: &Path) -> Result<Vec<PathBuf>, BError> {
+    if !dir.is_dir() {
+        return Err(BError::UploadError(format!(
+            "Upload directory '{}' does not exist",
+            dir.display()
+        )));
+    }
+
+    let mut files: Vec<PathBuf> = vec![];
+    find_files(dir, &mut files)?;
+    if dir.join(ARTIFACTS_INDEX).is_file() {
+        let index: ArtifactsIndex = ArtifactsIndex::load(dir, "")?;
+        files.retain(|f| {
+            let path: String = f
+                .strip_prefix(dir)
+                .unwrap_or(f)
+                .to_string_lossy()
+                .to_string();
+            path == ARTIFACTS_INDEX || path == ARTIFACTS_SHA256SUMS || index.contains(&path)
+        });
+    }
+    files.sort();
+    Ok(files)
+}
+
+/*
+ * The path a fetched file is written to. The relative path is coming from the
+ * server so it is rejected if it is empty, absolute or has a parent directory
+ * component since the file could then be written outside of the destination.
+ */
+pub fn fetch_path(dest: &Path, relative: &str, source: &str) -> Result<PathBuf, BError> {
+    let path: &Path = Path::new(relative);
+    if relative.is_empty()
+        || path.is_absolute()
+        || path.components().any(|c| c == Component::ParentDir)
+    {
+        return Err(BError::UploadError(format!(
+            "Invalid path '{}' in '{}'",
+            relative, source
+        )));
+    }
+    Ok(dest.join(path))
+}
+
+pub fn digest<D: Digest>(data: &[u8]) -> Vec<u8> {
+    D::digest(data).to_vec()
+}
+
+pub fn file_digest<D: Digest>(path: &Path) -> Result<Vec<u8>, BError> {
+    let mut file: File = File::open(path)?;
+    let mut hasher: D = D::new();
+    let mut buffer: Vec<u8> = vec![0; 1024 * 1024];
+    loop {
+        signal::check()?;
+        let n: usize = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/*
+ * Percent-encode a value used in the path or query of a url. All characters
+ * except the unreserved characters are encoded, the slash is kept as is when
+ * encoding a path.
+ */
+pub fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded: String = String::new();
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(30))
+        .timeout_read(Duration::from_secs(300))
+        .build()
+}
+
+/*
+ * Connection errors and server errors are worth retrying. Client errors like
+ * an unauthorized request are not retried since retrying will not change the
+ * outcome.
+ */
+pub fn request_error(error: ureq::Error) -> (bool, String) {
+    match error {
+        ureq::Error::Status(code, response) => (
+            code >= 500 || code == 408 || code == 429,
+            format!("{} {}", code, response.status_text()),
+        ),
+        ureq::Error::Transport(transport) => (true, transport.to_string()),
+    }
+}
+
+/*
+ * Run a request until it succeeds or the retries configured for the upload
+ * are used up. The backoff is doubled for every retry.
+ */
+pub fn retry<T>(
+    cli: &Cli,
+    data: &WsUploadData,
+    what: &str,
+    mut request: impl FnMut() -> Result<T, (bool, String)>,
+) -> Result<T, BError> {
+    let mut attempt: u32 = 0;
+    loop {
+        signal::check()?;
+        let (retry, error): (bool, String) = match request() {
+            Ok(result) => return Ok(result),
+            Err(err) => err,
+        };
+
+        if !retry || attempt >= data.retries() {
+            return Err(BError::UploadError(format!(
+                "Failed to {}: {}",
+                what, error
+            )));
+        }
+
+        let backoff: u64 = u64::from(data.backoff()) << attempt;
+        attempt += 1;
+        cli.info(format!(
+            "Failed to {}: {}. Retrying in {}s ({}/{})",
+            what,
+            error,
+            backoff,
+            attempt,
+            data.retries()
+        ));
+        std::thread::sleep(Duration::from_secs(backoff));
+    }
+}
+
+/*
+ * Write the body of a response to a file creating any missing parent
+ * directory. The sha256 of the written file is returned.
+ */
+pub fn download(response: ureq::Response, path: &Path) -> Result<String, BError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file: File = File::create(path)?;
+    std::io::copy(&mut response.into_reader(), &mut file)?;
+    Ok(hex(&file_digest::<sha2::Sha256>(path)?))
+}
+
+pub struct HttpUploadExecuter<'a> {
+    cli: &'a Cli,
+    data: &'a WsUploadData,
+}
+
+impl<'a> TaskExecuter for HttpUploadExecuter<'a> {
+    fn exec(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dry_run: bool,
+        _interactive: bool,
+    ) -> Result<(), BError> {
+        let dir: PathBuf = PathBuf::from(self.data.dir());
+        let files: Vec<PathBuf> = upload_files(&dir)?;
+        let authorization: Option<String> = self.authorization(env_variables)?;
+        let agent: ureq::Agent = agent();
+
+        for file in files.iter() {
+            let url: String = self.url(&file.strip_prefix(&dir)?.to_string_lossy());
+
+            if dry_run {
+                self.cli.info(format!(
+                    "Dry run. Skipping upload of '{}' to '{}'",
+                    file.display(),
+                    url
+                ));
+                continue;
+            }
+
+            let mut headers: Vec<(String, String)> = self.checksums(file)?;
+            if let Some(auth) = &authorization {
+                headers.push((String::from("Authorization"), auth.clone()));
+            }
+            self.put(&agent, &url, file, &headers)?;
+            self.cli
+                .info(format!("Uploaded '{}' to '{}'", file.display(), url));
+        }
+
+        self.cli.info(format!(
+            "Uploaded {} file(s) to '{}'",
+            files.len(),
+            self.url("")
+        ));
+        Ok(())
+    }
+}
+
+impl<'a> HttpUploadExecuter<'a> {
+    pub fn new(cli: &'a Cli, data: &'a WsUploadData) -> Self {
+        HttpUploadExecuter { cli, data }
+    }
+
+    /*
+     * Download the artifacts of a previous upload. The server is not expected
+     * to support listing so the artifacts index uploaded together with the
+     * artifacts is downloaded first and then every artifact in the index is
+     * downloaded and verified against the checksum in the index.
+     */
+    pub fn fetch(
+        &self,
+        env_variables: &HashMap<String, String>,
+        dest: &Path,
+    ) -> Result<(), BError> {
+        let authorization: Option<String> = self.authorization(env_variables)?;
+        let agent: ureq::Agent = agent();
+
+        let index_path: PathBuf = fetch_path(dest, ARTIFACTS_INDEX, &self.url(""))?;
+        self.get(&agent, &authorization, ARTIFACTS_INDEX, &index_path, "")?;
+        let index: Value = serde_json::from_str(&std::fs::read_to_string(&index_path)?)?;
+        let artifacts: Vec<Value> = index
+            .get("artifacts")
+            .and_then(|a| a.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        /*
+         * Every path in the index is verified before anything is downloaded
+         * so a tampered index is not leaving a partial fetch behind.
+         */
+        let mut fetch: Vec<(&str, PathBuf, &str)> = vec![];
+        for artifact in artifacts.iter() {
+            let relative: &str = artifact["path"].as_str().unwrap_or_default();
+            fetch.push((
+                relative,
+                fetch_path(dest, relative, &self.url(ARTIFACTS_INDEX))?,
+                artifact["sha256"].as_str().unwrap_or_default(),
+            ));
+        }
+
+        for (relative, path, sha256) in fetch.iter() {
+            self.get(&agent, &authorization, relative, path, sha256)?;
+        }
+
+        self.cli.info(format!(
+            "Fetched {} file(s) from '{}'",
+            artifacts.len() + 1,
+            self.url("")
+        ));
+        Ok(())
+    }
+
+    fn get(
+        &self,
+        agent: &ureq::Agent,
+        authorization: &Option<String>,
+        relative: &str,
+        path: &Path,
+        sha256: &str,
+    ) -> Result<(), BError> {
+        let url: String = self.url(relative);
+        retry(self.cli, self.data, &format!("fetch '{}'", url), || {
+            let mut request: ureq::Request = agent.get(&url);
+            if let Some(auth) = authorization {
+                request = request.set("Authorization", auth);
+            }
+            let response: ureq::Response = request.call().map_err(request_error)?;
+            let checksum: String =
+                download(response, path).map_err(|err| (false, err.to_string()))?;
+            if !sha256.is_empty() && checksum != sha256 {
+                return Err((true, format!("checksum mismatch for '{}'", path.display())));
+            }
+            Ok(())
+        })?;
+        self.cli
+            .info(format!("Fetched '{}' to '{}'", url, path.display()));
+        Ok(())
+    }
+
+    /*
+     * The url of a file is the base url followed by the expanded path template
+     * and the path of the file relative to the upload directory. The path is
+     * percent-encoded since both the file names and the context values used
+     * in the path template could contain characters like spaces, '#' or '?'.
+     */
+    fn url(&self, relative: &str) -> String {
+        let parts: Vec<&str> = vec![self.data.path().as_str(), relative]
+            .into_iter()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty())
+            .collect();
+        format!(
+            "{}/{}",
+            self.data.url().trim_end_matches('/'),
+            uri_encode(&parts.join("/"), false)
+        )
+    }
+
+    fn host(&self) -> String {
+        let url: &str = self.data.url();
+        let without_scheme: &str = url.split_once("://").map(|(_, u)| u).unwrap_or(url);
+        let authority: &str = without_scheme.split('/').next().unwrap_or_default();
+        let host_port: &str = authority.rsplit('@').next().unwrap_or_default();
+        host_port.split(':').next().unwrap_or_default().to_string()
+    }
+
+    /*
+     * A bearer token in the env is preferred over a user and password in the
+     * env. If neither is available the credentials for the host are looked up
+     * in the netrc file which by default is $HOME/.netrc.
+     */
+    fn authorization(
+        &self,
+        env_variables: &HashMap<String, String>,
+    ) -> Result<Option<String>, BError> {
+        if let Some(token) = env_variables.get(self.data.token_env()) {
+            return Ok(Some(format!("Bearer {}", token)));
+        }
+
+        if let (Some(user), Some(password)) = (
+            env_variables.get(self.data.user_env()),
+            env_variables.get(self.data.password_env()),
+        ) {
+            return Ok(Some(Self::basic(user, password)));
+        }
+
+        let mut netrc: PathBuf = PathBuf::from(self.data.netrc());
+        if self.data.netrc().is_empty() {
+            match env_variables.get("HOME") {
+                Some(home) => netrc = PathBuf::from(home).join(".netrc"),
+                None => return Ok(None),
+            }
+        }
+
+        if netrc.is_file() {
+            if let Some((login, password)) = Netrc::from_file(&netrc)?.credentials(&self.host()) {
+                return Ok(Some(Self::basic(&login, &password)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn basic(user: &str, password: &str) -> String {
+        format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password))
+        )
+    }
+
+    fn checksums(&self, path: &Path) -> Result<Vec<(String, String)>, BError> {
+        let mut headers: Vec<(String, String)> = vec![];
+        for checksum in self.data.checksums().iter() {
+            match checksum.as_str() {
+                "sha256" => headers.push((
+                    String::from("X-Checksum-Sha256"),
+                    hex(&file_digest::<sha2::Sha256>(path)?),
+                )),
+                "sha1" => headers.push((
+                    String::from("X-Checksum-Sha1"),
+                    hex(&file_digest::<sha1::Sha1>(path)?),
+                )),
+                "md5" => headers.push((
+                    String::from("X-Checksum-Md5"),
+                    hex(&file_digest::<md5::Md5>(path)?),
+                )),
+                _ => {}
+            }
+        }
+        Ok(headers)
+    }
+
+    fn put(
+        &self,
+        agent: &ureq::Agent,
+        url: &str,
+        path: &Path,
+        headers: &[(String, String)],
+    ) -> Result<(), BError> {
+        let size: u64 = std::fs::metadata(path)?.len();
+        retry(
+            self.cli,
+            self.data,
+            &format!("upload '{}' to '{}'", path.display(), url),
+            || {
+                let mut request: ureq::Request =
+                    agent.put(url).set("Content-Length", &size.to_string());
+                for (name, value) in headers.iter() {
+                    request = request.set(name, value);
+                }
+                let file: File = File::open(path).map_err(|err| (false, err.to_string()))?;
+                request.send(file).map_err(request_error)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use crate::data::WsUploadData;
+    use crate::executers::upload::{fetch_path, upload_files, uri_encode};
+    use crate::executers::{HttpUploadExecuter, TaskExecuter};
+    use crate::fs::ArtifactsIndex;
+    use crate::helper::Helper;
+
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::thread::JoinHandle;
+    use tempdir::TempDir;
+
+    type Received = Vec<(String, String, String, Vec<u8>)>;
+
+    /*
+     * Local stand-in for an artifact server recording the url, the checksum
+     * and authorization headers and the body of every PUT request. The first
+     * request is answered with a server error to exercise the retries.
+     */
+    fn helper_upload_server(requests: usize) -> (String, JoinHandle<Received>) {
+        let server: tiny_http::Server =
+            tiny_http::Server::http("127.0.0.1:0").expect("Failed to start server");
+        let url: String = format!("http://{}", server.server_addr());
+        let handle = std::thread::spawn(move || {
+            let mut received: Received = vec![];
+            for i in 0..requests {
+                let mut request: tiny_http::Request = server.recv().expect("Failed to receive");
+                let header = |name: &str| -> String {
+                    request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.to_string().eq_ignore_ascii_case(name))
+                        .map(|h| h.value.to_string())
+                        .unwrap_or_default()
+                };
+                let checksum: String = header("X-Checksum-Sha256");
+                let auth: String = header("Authorization");
+                let mut body: Vec<u8> = vec![];
+                request
+                    .as_reader()
+                    .read_to_end(&mut body)
+                    .expect("Failed to read body");
+                let url: String = request.url().to_string();
+                let status: u16 = if i == 0 { 503 } else { 201 };
+                request
+                    .respond(tiny_http::Response::empty(status))
+                    .expect("Failed to respond");
+                if status == 201 {
+                    received.push((url, checksum, auth, body));
+                }
+            }
+            received
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_upload_files() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        let build_dir: PathBuf = artifacts_dir.join("1.0.1");
+        Helper::create_test_files(&vec![
+            artifacts_dir.join("1.0.0/old.img"),
+            artifacts_dir.join("1.0.0/artifacts.json"),
+            build_dir.join("boot.img"),
+            build_dir.join("images/system.img"),
+            build_dir.join("untracked.log"),
+        ]);
+        std::os::unix::fs::symlink("1.0.1", artifacts_dir.join("latest"))
+            .expect("Failed to create symlink");
+
+        // Without an index all files are uploaded except the latest link
+        assert_eq!(
+            upload_files(&artifacts_dir).expect("Failed to list files"),
+            vec![
+                artifacts_dir.join("1.0.0/artifacts.json"),
+                artifacts_dir.join("1.0.0/old.img"),
+                build_dir.join("boot.img"),
+                build_dir.join("images/system.img"),
+                build_dir.join("untracked.log"),
+            ]
+        );
+
+        let mut index: ArtifactsIndex = ArtifactsIndex::new(&build_dir, "default");
+        index
+            .add(&build_dir.join("boot.img"), "task1", "file")
+            .expect("Failed to add artifact");
+        index
+            .add(&build_dir.join("images/system.img"), "task1", "file")
+            .expect("Failed to add artifact");
+        index.write(true).expect("Failed to write index");
+        assert_eq!(
+            upload_files(&build_dir).expect("Failed to list files"),
+            vec![
+                build_dir.join("SHA256SUMS"),
+                build_dir.join("artifacts.json"),
+                build_dir.join("boot.img"),
+                build_dir.join("images/system.img"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_http_upload_executer() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let artifacts_dir: PathBuf = work_dir.join("artifacts");
+        let files: Vec<PathBuf> = vec![
+            artifacts_dir.join("dir/file #2.txt"),
+            artifacts_dir.join("file1.txt"),
+        ];
+        Helper::create_test_files(&files);
+        let netrc: PathBuf = work_dir.join("netrc");
+        std::fs::write(&netrc, "machine 127.0.0.1 login user password secret\n")
+            .expect("Failed to write netrc");
+        let (url, handle) = helper_upload_server(3);
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "http",
+            "url": "{}/artifactory/",
+            "path": "/product/42/",
+            "dir": "{}",
+            "backoff": "0",
+            "auth": {{
+                "tokenenv": "UPLOAD_TOKEN",
+                "netrc": "{}"
+            }}
+        }}"#,
+            url,
+            artifacts_dir.display(),
+            netrc.display()
+        );
+        let data: WsUploadData =
+            WsUploadData::from_str(&json_build_config).expect("Failed to parse upload data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let executer: HttpUploadExecuter = HttpUploadExecuter::new(&cli, &data);
+        executer
+            .exec(&HashMap::new(), false, false)
+            .expect("Failed to upload artifacts");
+        let received: Received = handle.join().expect("Failed to join server");
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].0, "/artifactory/product/42/dir/file%20%232.txt");
+        assert_eq!(received[1].0, "/artifactory/product/42/file1.txt");
+        for (i, file) in files.iter().enumerate() {
+            let content: Vec<u8> = std::fs::read(file).expect("Failed to read file");
+            assert_eq!(received[i].3, content);
+            assert_eq!(
+                received[i].1,
+                crate::fs::ArtifactsIndex::sha256(file).expect("Failed to calculate checksum")
+            );
+            assert_eq!(received[i].2, "Basic dXNlcjpzZWNyZXQ=");
+        }
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(
+            uri_encode("a b/c#d?e%f~g.h-i_j", false),
+            "a%20b/c%23d%3Fe%25f~g.h-i_j"
+        );
+        assert_eq!(uri_encode("a/b=c", true), "a%2Fb%3Dc");
+    }
+
+    #[test]
+    fn test_fetch_path() {
+        let dest: PathBuf = PathBuf::from("/workspace/fetch");
+        assert_eq!(
+            fetch_path(&dest, "dir/file.txt", "index").expect("Invalid path"),
+            PathBuf::from("/workspace/fetch/dir/file.txt")
+        );
+        for relative in ["", "/etc/passwd", "../file.txt", "dir/../../file.txt"] {
+            match fetch_path(&dest, relative, "index") {
+                Ok(_path) => {
+                    panic!("Was expecting an error!");
+                }
+                Err(e) => {
+                    assert_eq!(
+                        e.to_string(),
+                        format!("Invalid path '{}' in 'index'", relative)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_http_fetch_invalid_path() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.into_path();
+        let fetch_dir: PathBuf = work_dir.join("fetch");
+        let server: tiny_http::Server =
+            tiny_http::Server::http("127.0.0.1:0").expect("Failed to start server");
+        let url: String = format!("http://{}", server.server_addr());
+        let handle = std::thread::spawn(move || {
+            let request: tiny_http::Request = server.recv().expect("Failed to receive");
+            let url: String = request.url().to_string();
+            request
+                .respond(tiny_http::Response::from_string(
+                    r#"{"artifacts": [{"path": "image.img"}, {"path": "../evil.txt"}]}"#,
+                ))
+                .expect("Failed to respond");
+            url
+        });
+        let json_build_config: String = format!(
+            r#"
+        {{
+            "type": "http",
+            "url": "{}/builds",
+            "path": "42"
+        }}"#,
+            url
+        );
+        let data: WsUploadData =
+            WsUploadData::from_str(&json_build_config).expect("Failed to parse upload data");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let executer: HttpUploadExecuter = HttpUploadExecuter::new(&cli, &data);
+        match executer.fetch(&HashMap::new(), &fetch_dir) {
+            Ok(()) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    format!(
+                        "Invalid path '../evil.txt' in '{}/builds/42/artifacts.json'",
+                        url
+                    )
+                );
+            }
+        }
+        assert_eq!(
+            handle.join().expect("Failed to join server"),
+            "/builds/42/artifacts.json"
+        );
+        assert!(!fetch_dir.join("image.img").exists());
+        assert!(!work_dir.join("evil.txt").exists());
+    }
+}
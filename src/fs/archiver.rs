@@ -1,7 +1,12 @@
-use std::fs::File;
+use chrono::{Datelike, Timelike};
+use std::fs::{File, Metadata};
+use std::io::{Read, Write};
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use zip::{write::FileOptions, ZipWriter};
 
+use crate::cli::signal;
 use crate::error::BError;
 
 #[derive(Debug)]
@@ -10,6 +15,9 @@ pub struct Archiver {
     name: String,
     extension: String,
     compression: String,
+    level: Option<u32>,
+    threads: Option<u32>,
+    mtime: Option<u64>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -46,6 +54,10 @@ impl Archiver {
                     archive_type = "zip".to_string();
                     break;
                 }
+                "tar" if i + 1 == suffixes.len() => {
+                    archive_type = "tar".to_string();
+                    break;
+                }
                 "tar" if i + 1 < suffixes.len() => {
                     archive_type = "tar".to_string();
                     match suffixes[i + 1] {
@@ -57,6 +69,14 @@ impl Archiver {
                             compression = "bz2".to_string();
                             break;
                         }
+                        "xz" => {
+                            compression = "xz".to_string();
+                            break;
+                        }
+                        "zst" => {
+                            compression = "zst".to_string();
+                            break;
+                        }
                         _ => {
                             return Err(BError::ArchiverError(format!(
                                 "Unsupported compression '{}'!",
@@ -76,20 +96,143 @@ impl Archiver {
             )));
         }
 
-        if archive_type == "tar" && compression.is_empty() {
-            return Err(BError::ArchiverError(
-                "Archive must have a compression!".to_string(),
-            ));
-        }
-
         Ok(Archiver {
             path: path.clone(),
             name,
             extension: archive_type,
             compression,
+            level: None,
+            threads: None,
+            mtime: None,
         })
     }
 
+    pub fn set_level(&mut self, level: u32) {
+        self.level = Some(level);
+    }
+
+    /*
+     * The number of threads is only used by the xz and zstd compressions
+     * all other compressions are single threaded.
+     */
+    pub fn set_threads(&mut self, threads: u32) {
+        self.threads = Some(threads);
+    }
+
+    /*
+     * In reproducible mode the archive content only depends on the content of
+     * the files. The entries are sorted, the owner is set to root, the
+     * permissions are normalized to 0644 or 0755 and the mtime of all files
+     * newer than the mtime is clamped to the mtime.
+     */
+    pub fn set_reproducible(&mut self, mtime: u64) {
+        self.mtime = Some(mtime);
+    }
+
+    pub fn reproducible(&self) -> bool {
+        self.mtime.is_some()
+    }
+
+    fn entry_mode(metadata: &Metadata) -> u32 {
+        if metadata.permissions().mode() & 0o111 != 0 {
+            return 0o755;
+        }
+        0o644
+    }
+
+    fn entry_mtime(&self, metadata: &Metadata) -> u64 {
+        let mtime: u64 = metadata
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        match self.mtime {
+            Some(clamp) => mtime.min(clamp),
+            None => mtime,
+        }
+    }
+
+    /*
+     * The zip format can only store dates from 1980 and onwards so any mtime
+     * before that is stored as 1980-01-01.
+     */
+    fn zip_mtime(mtime: u64) -> zip::DateTime {
+        chrono::DateTime::from_timestamp(mtime as i64, 0)
+            .and_then(|t| {
+                zip::DateTime::from_date_and_time(
+                    t.year() as u16,
+                    t.month() as u8,
+                    t.day() as u8,
+                    t.hour() as u8,
+                    t.minute() as u8,
+                    t.second() as u8,
+                )
+                .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    fn verify_level(&self) -> Result<(), BError> {
+        if let Some(level) = self.level {
+            let (min, max): (u32, u32) = match self.compression.as_str() {
+                "bz2" => (1, 9),
+                "zst" => (1, 22),
+                _ => (0, 9),
+            };
+            if level < min || level > max {
+                return Err(BError::ArchiverError(format!(
+                    "Unsupported compression level '{}' for '{}', must be {}-{}!",
+                    level, self.name, min, max
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn encoder(&self, archive_file: File) -> Result<Box<dyn std::io::Write>, BError> {
+        let threads: u32 = self.threads.unwrap_or(1);
+        match self.compression() {
+            "" => Ok(Box::new(archive_file)),
+            "gz" => Ok(Box::new(flate2::write::GzEncoder::new(
+                archive_file,
+                self.level.map(flate2::Compression::new).unwrap_or_default(),
+            ))),
+            "bz2" => Ok(Box::new(bzip2::write::BzEncoder::new(
+                archive_file,
+                self.level.map(bzip2::Compression::new).unwrap_or_default(),
+            ))),
+            "xz" => {
+                let preset: u32 = self.level.unwrap_or(6);
+                if threads > 1 {
+                    let stream: xz2::stream::Stream = xz2::stream::MtStreamBuilder::new()
+                        .threads(threads)
+                        .preset(preset)
+                        .check(xz2::stream::Check::Crc64)
+                        .encoder()
+                        .map_err(|e| BError::ArchiverError(e.to_string()))?;
+                    return Ok(Box::new(xz2::write::XzEncoder::new_stream(
+                        archive_file,
+                        stream,
+                    )));
+                }
+                Ok(Box::new(xz2::write::XzEncoder::new(archive_file, preset)))
+            }
+            "zst" => {
+                let mut enc: zstd::Encoder<'static, File> =
+                    zstd::Encoder::new(archive_file, self.level.unwrap_or(0) as i32)?;
+                if threads > 1 {
+                    enc.multithread(threads)?;
+                }
+                Ok(Box::new(enc.auto_finish()))
+            }
+            _ => Err(BError::ArchiverError(format!(
+                "Unsupported compression '{}'!",
+                self.compression
+            ))),
+        }
+    }
+
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
@@ -106,9 +249,11 @@ impl Archiver {
         &self.compression
     }
 
-    pub fn add_files(&self, files: &Vec<PathBuf>, work_dir: &Path) -> Result<(), BError> {
+    pub fn add_files(&self, files: &[PathBuf], work_dir: &Path) -> Result<(), BError> {
         let mut mode: Mode = Mode::Write;
 
+        self.verify_level()?;
+
         if let Some(parent_dir) = self.path.parent() {
             std::fs::create_dir_all(parent_dir)?;
         }
@@ -122,35 +267,37 @@ impl Archiver {
             // mode == Mode::Append;
         }
 
+        let mut files: Vec<(PathBuf, &PathBuf)> = files
+            .iter()
+            .map(|path| Ok((path.strip_prefix(work_dir.as_os_str())?.to_path_buf(), path)))
+            .collect::<Result<Vec<(PathBuf, &PathBuf)>, BError>>()?;
+        if self.reproducible() {
+            files.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
         let archive_file: File = File::create(&self.path)?;
 
         if self.extension() == "tar" {
             if mode == Mode::Append {}
 
-            let enc: Box<dyn std::io::Write>;
-            let mut tar: tar::Builder<Box<dyn std::io::Write>>;
-            if self.compression() == "gz" {
-                enc = Box::new(flate2::write::GzEncoder::new(
-                    archive_file,
-                    flate2::Compression::default(),
-                ));
-            } else if self.compression() == "bz2" {
-                enc = Box::new(bzip2::write::BzEncoder::new(
-                    archive_file,
-                    bzip2::Compression::default(),
-                ));
-            } else {
-                return Err(BError::ArchiverError(format!(
-                    "Unsupported compression '{}'!",
-                    self.compression
-                )));
-            }
-
-            tar = tar::Builder::new(enc);
-            for path in files {
-                let striped_path: PathBuf = path.strip_prefix(work_dir.as_os_str())?.to_path_buf();
+            let enc: Box<dyn std::io::Write> = self.encoder(archive_file)?;
+            let mut tar: tar::Builder<Box<dyn std::io::Write>> = tar::Builder::new(enc);
+            for (striped_path, path) in files {
+                signal::check()?;
                 let mut file: File = File::open(path)?;
-                tar.append_file(striped_path, &mut file)?;
+                if self.reproducible() {
+                    let metadata: Metadata = file.metadata()?;
+                    let mut header: tar::Header = tar::Header::new_gnu();
+                    header.set_size(metadata.len());
+                    header.set_mode(Self::entry_mode(&metadata));
+                    header.set_mtime(self.entry_mtime(&metadata));
+                    header.set_uid(0);
+                    header.set_gid(0);
+                    header.set_cksum();
+                    tar.append_data(&mut header, striped_path, &mut file)?;
+                } else {
+                    tar.append_file(striped_path, &mut file)?;
+                }
             }
 
             tar.finish()?;
@@ -162,15 +309,25 @@ impl Archiver {
             let mut options: FileOptions =
                 zip::write::FileOptions::default().unix_permissions(0o755);
             options = options.large_file(true);
+            if let Some(level) = self.level {
+                options = options.compression_level(Some(level as i32));
+            }
 
-            for path in files {
+            for (striped_path, path) in files {
                 //println!("{}", path.display());
                 //println!("{}", work_dir.display());
-                let striped_path: PathBuf = path.strip_prefix(work_dir.as_os_str())?.to_path_buf();
-
+                signal::check()?;
                 let mut file: File = File::open(path)?;
 
-                zip.start_file(striped_path.to_string_lossy().to_owned(), options)?;
+                let mut file_options: FileOptions = options;
+                if self.reproducible() {
+                    let metadata: Metadata = file.metadata()?;
+                    file_options = file_options
+                        .unix_permissions(Self::entry_mode(&metadata))
+                        .last_modified_time(Self::zip_mtime(self.entry_mtime(&metadata)));
+                }
+
+                zip.start_file(striped_path.to_string_lossy().to_string(), file_options)?;
 
                 std::io::copy(&mut file, &mut zip)?;
             }
@@ -180,15 +337,151 @@ impl Archiver {
 
         Ok(())
     }
+
+    /*
+     * The name of a volume of a split archive. A zip archive is split into
+     * archive.zip.001, archive.zip.002 and so on and a tar archive is split
+     * into archive.tar.zst.part-00, archive.tar.zst.part-01 and so on.
+     */
+    fn volume_path(&self, index: usize) -> PathBuf {
+        let volume: String = if self.extension() == "zip" {
+            format!("{}.{:03}", self.name, index + 1)
+        } else {
+            format!("{}.part-{:02}", self.name, index)
+        };
+        self.path.with_file_name(volume)
+    }
+
+    /*
+     * Setup the archiver from the path of an archive or any of the volumes of
+     * a split archive.
+     */
+    pub fn from_volume(path: &Path) -> Result<Self, BError> {
+        let volume: String = path.to_string_lossy().to_string();
+        let re: regex::Regex = regex::Regex::new(r"(\.[0-9]{3}|\.part-[0-9]{2,})$").unwrap();
+        Self::new(&PathBuf::from(re.replace(&volume, "").to_string()))
+    }
+
+    /*
+     * The volumes of the archive in order. Empty if the archive has not been
+     * split.
+     */
+    pub fn volumes(&self) -> Vec<PathBuf> {
+        let mut volumes: Vec<PathBuf> = vec![];
+        while self.volume_path(volumes.len()).exists() {
+            volumes.push(self.volume_path(volumes.len()));
+        }
+        volumes
+    }
+
+    /*
+     * Split the archive into volumes of at most size bytes. The archive is
+     * removed when it has been split. The volumes can be joined again using
+     * join or simply by concatenating them in order.
+     */
+    pub fn split(&self, size: u64) -> Result<Vec<PathBuf>, BError> {
+        if size == 0 {
+            return Err(BError::ArchiverError(
+                "Archive split size must be larger than 0!".to_string(),
+            ));
+        }
+
+        // Remove any volumes left from a previous split of the archive
+        for volume in self.volumes() {
+            std::fs::remove_file(volume)?;
+        }
+
+        let mut archive_file: File = File::open(&self.path)?;
+        let archive_size: u64 = archive_file.metadata()?.len();
+        let mut volumes: Vec<PathBuf> = vec![];
+        let mut buffer: Vec<u8> = vec![0; 1024 * 1024];
+        loop {
+            let volume: PathBuf = self.volume_path(volumes.len());
+            let mut volume_file: File = File::create(&volume)?;
+            let mut remaining: u64 = size;
+            while remaining > 0 {
+                let len: usize = buffer.len().min(remaining as usize);
+                let n: usize = archive_file.read(&mut buffer[..len])?;
+                if n == 0 {
+                    break;
+                }
+                volume_file.write_all(&buffer[..n])?;
+                remaining -= n as u64;
+            }
+            volumes.push(volume);
+            if volumes.len() as u64 * size >= archive_size {
+                break;
+            }
+        }
+        std::fs::remove_file(&self.path)?;
+        Ok(volumes)
+    }
+
+    /*
+     * Join the volumes of a split archive into the archive at dest.
+     */
+    pub fn join(&self, dest: &Path) -> Result<(), BError> {
+        let volumes: Vec<PathBuf> = self.volumes();
+        if volumes.is_empty() {
+            return Err(BError::ArchiverError(format!(
+                "No volumes found for archive '{}'!",
+                self.path.display()
+            )));
+        }
+
+        if let Some(parent_dir) = dest.parent() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut archive_file: File = File::create(dest)?;
+        for volume in volumes.iter() {
+            signal::check()?;
+            std::io::copy(&mut File::open(volume)?, &mut archive_file)?;
+        }
+        Ok(())
+    }
+
+    /*
+     * Extract the archive to the dest directory. If the archive has been split
+     * the volumes are joined to a temporary archive that is extracted.
+     */
+    pub fn extract(&self, dest: &Path) -> Result<(), BError> {
+        let temp_dir: tempdir::TempDir = tempdir::TempDir::new("yaab-archiver")?;
+        let mut archive_path: PathBuf = self.path.clone();
+        if !archive_path.exists() {
+            archive_path = temp_dir.path().join(&self.name);
+            self.join(&archive_path)?;
+        }
+
+        std::fs::create_dir_all(dest)?;
+        let archive_file: File = File::open(&archive_path)?;
+        if self.extension() == "zip" {
+            let mut zip: zip::ZipArchive<File> = zip::ZipArchive::new(archive_file)?;
+            zip.extract(dest)?;
+            return Ok(());
+        }
+
+        let dec: Box<dyn Read> = match self.compression() {
+            "gz" => Box::new(flate2::read::GzDecoder::new(archive_file)),
+            "bz2" => Box::new(bzip2::read::BzDecoder::new(archive_file)),
+            "xz" => Box::new(xz2::read::XzDecoder::new(archive_file)),
+            "zst" => Box::new(zstd::Decoder::new(archive_file)?),
+            _ => Box::new(archive_file),
+        };
+        tar::Archive::new(dec).unpack(dest)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs::File;
+    use std::os::unix::fs::PermissionsExt;
     use std::path::{Path, PathBuf};
+    use std::time::SystemTime;
     use tempdir::TempDir;
 
     use crate::error::BError;
-    use crate::fs::Archiver;
+    use crate::fs::{Archiver, ArtifactsIndex};
     use crate::helper::Helper;
 
     #[test]
@@ -251,10 +544,10 @@ mod tests {
         assert_eq!(archiver.compression(), "bz2");
     }
 
-    /*
     #[test]
     fn test_archiver_tar_xz() {
-        let temp_dir: TempDir = TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
         let path: &Path = temp_dir.path();
         let archiver_path: PathBuf = path.join("test-archiver.tar.xz");
         let archiver: Archiver = Archiver::new(&archiver_path).expect("Failed to setup archiver!");
@@ -262,7 +555,30 @@ mod tests {
         assert_eq!(archiver.extension(), "tar");
         assert_eq!(archiver.compression(), "xz");
     }
-    */
+
+    #[test]
+    fn test_archiver_tar_zst() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let path: &Path = temp_dir.path();
+        let archiver_path: PathBuf = path.join("test-x.y.z-archiver.tar.zst");
+        let archiver: Archiver = Archiver::new(&archiver_path).expect("Failed to setup archiver!");
+        assert_eq!(archiver.name(), "test-x.y.z-archiver.tar.zst");
+        assert_eq!(archiver.extension(), "tar");
+        assert_eq!(archiver.compression(), "zst");
+    }
+
+    #[test]
+    fn test_archiver_tar() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let path: &Path = temp_dir.path();
+        let archiver_path: PathBuf = path.join("test-archiver.tar");
+        let archiver: Archiver = Archiver::new(&archiver_path).expect("Failed to setup archiver!");
+        assert_eq!(archiver.name(), "test-archiver.tar");
+        assert_eq!(archiver.extension(), "tar");
+        assert_eq!(archiver.compression(), "");
+    }
 
     #[test]
     fn test_archiver_error_unsupported_archive() {
@@ -293,16 +609,21 @@ mod tests {
     }
 
     #[test]
-    fn test_archiver_error_no_compression() {
+    fn test_archiver_error_compression_level() {
         let temp_dir: TempDir =
             TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
-        let path: &Path = temp_dir.path();
-        let archiver_path: PathBuf = path.join("test-archiver.tar");
-        let error: BError = Archiver::new(&archiver_path)
-            .expect_err("We are expecting an error but got an Archiver");
+        let work_dir: &Path = temp_dir.path();
+        let archiver_path: PathBuf = work_dir.join("test-archiver.tar.bz2");
+        let mut archiver: Archiver =
+            Archiver::new(&archiver_path).expect("Failed to setup archiver!");
+        archiver.set_level(0);
+        let error: BError = archiver
+            .add_files(&[], work_dir)
+            .expect_err("We are expecting an error but got an archive");
         assert_eq!(
             error.to_string(),
-            "Unsupported archive 'test-archiver.tar'!".to_string()
+            "Unsupported compression level '0' for 'test-archiver.tar.bz2', must be 1-9!"
+                .to_string()
         );
     }
 
@@ -398,4 +719,211 @@ mod tests {
         // we are iterating over the content and is collecting the file names
         Helper::verify_archived_files(&files, &archived_files, work_dir);
     }
+
+    fn archive_files(archiver: &Archiver, work_dir: &Path) {
+        let files: Vec<PathBuf> = vec![
+            work_dir.join("dir1/file1.txt"),
+            work_dir.join("file2.txt"),
+            work_dir.join("dir2/file3.txt"),
+        ];
+
+        Helper::create_test_files(&files);
+
+        archiver
+            .add_files(&files, work_dir)
+            .expect("Failed too create archive");
+        assert!(archiver.path().exists());
+
+        let archived_files: Vec<PathBuf> =
+            Helper::list_files_in_archive(archiver, &work_dir.join(PathBuf::from("unpack/")))
+                .expect("Failed to list files in archive");
+        Helper::verify_archived_files(&files, &archived_files, work_dir);
+    }
+
+    fn split_archive(name: &str, volumes: &[&str]) {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &Path = temp_dir.path();
+        let archiver: Archiver =
+            Archiver::new(&work_dir.join(name)).expect("Failed to setup archiver!");
+        let files: Vec<PathBuf> = vec![work_dir.join("dir1/file1.txt"), work_dir.join("file2.txt")];
+        Helper::create_test_files(&files);
+        archiver
+            .add_files(&files, work_dir)
+            .expect("Failed too create archive");
+        let size: u64 = std::fs::metadata(archiver.path())
+            .expect("Failed to read metadata")
+            .len();
+        let split: Vec<PathBuf> = archiver
+            .split(size / volumes.len() as u64 + 1)
+            .expect("Failed to split archive");
+        assert!(!archiver.path().exists());
+        assert_eq!(
+            split,
+            volumes
+                .iter()
+                .map(|v| work_dir.join(v))
+                .collect::<Vec<PathBuf>>()
+        );
+        assert_eq!(archiver.volumes(), split);
+        let volumes_size: u64 = split
+            .iter()
+            .map(|v| std::fs::metadata(v).expect("Failed to read metadata").len())
+            .sum();
+        assert_eq!(volumes_size, size);
+
+        let archiver: Archiver =
+            Archiver::from_volume(&split[1]).expect("Failed to setup archiver!");
+        assert_eq!(archiver.path(), &work_dir.join(name));
+        archiver
+            .extract(&work_dir.join("extract"))
+            .expect("Failed to extract archive");
+        for f in files.iter() {
+            let extracted: PathBuf = work_dir
+                .join("extract")
+                .join(f.strip_prefix(work_dir).unwrap());
+            assert_eq!(
+                std::fs::read(&extracted).expect("Failed to read file"),
+                std::fs::read(f).expect("Failed to read file")
+            );
+        }
+
+        archiver
+            .join(&work_dir.join(name))
+            .expect("Failed to join archive");
+        assert_eq!(
+            std::fs::metadata(archiver.path())
+                .expect("Failed to read metadata")
+                .len(),
+            size
+        );
+    }
+
+    #[test]
+    fn test_archiver_split_zip() {
+        split_archive(
+            "test-archiver.zip",
+            &["test-archiver.zip.001", "test-archiver.zip.002"],
+        );
+    }
+
+    #[test]
+    fn test_archiver_split_tar_gz() {
+        split_archive(
+            "test-archiver.tar.gz",
+            &[
+                "test-archiver.tar.gz.part-00",
+                "test-archiver.tar.gz.part-01",
+                "test-archiver.tar.gz.part-02",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_archiver_file_tar_xz() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &Path = temp_dir.path();
+        let mut archiver: Archiver = Archiver::new(&work_dir.join("test-archiver.tar.xz"))
+            .expect("Failed to setup archiver!");
+        archiver.set_level(9);
+        archiver.set_threads(2);
+        archive_files(&archiver, work_dir);
+    }
+
+    #[test]
+    fn test_archiver_file_tar_zst() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &Path = temp_dir.path();
+        let mut archiver: Archiver = Archiver::new(&work_dir.join("test-archiver.tar.zst"))
+            .expect("Failed to setup archiver!");
+        archiver.set_level(19);
+        archiver.set_threads(2);
+        archive_files(&archiver, work_dir);
+    }
+
+    #[test]
+    fn test_archiver_file_tar() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &Path = temp_dir.path();
+        let archiver: Archiver =
+            Archiver::new(&work_dir.join("test-archiver.tar")).expect("Failed to setup archiver!");
+        archive_files(&archiver, work_dir);
+    }
+
+    fn reproducible_archive(name: &str, work_dir: &Path, files: &[PathBuf]) -> String {
+        let mut archiver: Archiver =
+            Archiver::new(&work_dir.join(name)).expect("Failed to setup archiver!");
+        archiver.set_reproducible(1700000000);
+        archiver
+            .add_files(files, work_dir)
+            .expect("Failed too create archive");
+        ArtifactsIndex::sha256(archiver.path()).expect("Failed to hash archive")
+    }
+
+    #[test]
+    fn test_archiver_reproducible() {
+        for name in ["test.tar", "test.tar.gz", "test.tar.zst", "test.zip"] {
+            let temp_dir: TempDir =
+                TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+            let work_dir: &Path = temp_dir.path();
+            let files: Vec<PathBuf> = vec![work_dir.join("b/file1.txt"), work_dir.join("a.txt")];
+            Helper::create_test_files(&files);
+            let first: String = reproducible_archive(name, work_dir, &files);
+
+            // Touch the files and change the permissions and the order of the
+            // files and verify that the archive is still the same
+            for f in files.iter() {
+                let file: File = File::options()
+                    .write(true)
+                    .open(f)
+                    .expect("Failed to open file");
+                file.set_modified(SystemTime::now())
+                    .expect("Failed to set mtime");
+                std::fs::set_permissions(f, std::fs::Permissions::from_mode(0o664))
+                    .expect("Failed to set permissions");
+            }
+            let files: Vec<PathBuf> = files.into_iter().rev().collect();
+            let second: String = reproducible_archive(name, work_dir, &files);
+            assert_eq!(first, second, "Archive '{}' is not reproducible", name);
+        }
+    }
+
+    #[test]
+    fn test_archiver_reproducible_tar_header() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: &Path = temp_dir.path();
+        let files: Vec<PathBuf> = vec![work_dir.join("file1.sh"), work_dir.join("file2.txt")];
+        Helper::create_test_files(&files);
+        std::fs::set_permissions(&files[0], std::fs::Permissions::from_mode(0o700))
+            .expect("Failed to set permissions");
+        reproducible_archive("test.tar", work_dir, &files);
+
+        let mut tar: tar::Archive<File> =
+            tar::Archive::new(File::open(work_dir.join("test.tar")).expect("Failed to open"));
+        let entries: Vec<(String, u32, u64, u64)> = tar
+            .entries()
+            .expect("Failed to read entries")
+            .map(|e| {
+                let e = e.expect("Failed to read entry");
+                let header: &tar::Header = e.header();
+                (
+                    e.path().unwrap().to_string_lossy().to_string(),
+                    header.mode().unwrap(),
+                    header.mtime().unwrap(),
+                    header.uid().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (String::from("file1.sh"), 0o755, 1700000000, 0),
+                (String::from("file2.txt"), 0o644, 1700000000, 0),
+            ]
+        );
+    }
 }
@@ -0,0 +1,442 @@
+use std::fs::{File, FileTimes, Metadata};
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::cli::signal;
+use crate::error::BError;
+
+/*
+ * Copy a collected file to the artifacts directory. By default the file is
+ * copied the same way as std::fs::copy but the copier can keep symlinks as
+ * symlinks, preserve the mode bits and timestamps of the source and copy
+ * sparse files without expanding the holes. When copying sparse files the
+ * file is first cloned using a reflink if the filesystem supports it and
+ * otherwise only the data segments found using SEEK_DATA/SEEK_HOLE are
+ * copied.
+ */
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Copier {
+    pub symlinks: bool,
+    pub preserve: bool,
+    pub sparse: bool,
+    pub mode: CopyMode,
+}
+
+/*
+ * The collection mode decides if a file is copied, hardlinked or cloned using
+ * a reflink. Hardlinks and reflinks only work when the source and the
+ * destination are on the same device, if not the file is copied. In auto mode
+ * a reflink is tried first then a hardlink and last a copy, also when the
+ * hardlink failed.
+ */
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum CopyMode {
+    #[default]
+    Copy,
+    Hardlink,
+    Reflink,
+    Auto,
+}
+
+impl FromStr for CopyMode {
+    type Err = BError;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "copy" | "" => Ok(CopyMode::Copy),
+            "hardlink" => Ok(CopyMode::Hardlink),
+            "reflink" => Ok(CopyMode::Reflink),
+            "auto" => Ok(CopyMode::Auto),
+            _ => Err(BError::ValueError(format!(
+                "Invalid collection mode '{}', must be copy, hardlink, reflink or auto!",
+                mode
+            ))),
+        }
+    }
+}
+
+impl Copier {
+    pub fn copy(&self, src: &Path, dest: &Path) -> Result<(), BError> {
+        signal::check()?;
+        let metadata: Metadata = std::fs::symlink_metadata(src)?;
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if self.symlinks && metadata.file_type().is_symlink() {
+            return self.copy_symlink(src, dest);
+        }
+
+        // Remove the destination first as it might be a symlink or a hardlink
+        // to the source from a previous collection
+        if dest.is_symlink() || dest.is_file() {
+            std::fs::remove_file(dest)?;
+        }
+
+        if self.link(src, dest)? {
+            return Ok(());
+        }
+
+        if self.sparse {
+            self.copy_sparse(src, dest)?;
+        } else {
+            std::fs::copy(src, dest)?;
+        }
+
+        if self.preserve {
+            self.preserve(src, dest)?;
+        }
+        Ok(())
+    }
+
+    /*
+     * Link the destination to the source according to the collection mode.
+     * Returns false if the file should be copied instead.
+     */
+    fn link(&self, src: &Path, dest: &Path) -> Result<bool, BError> {
+        if self.mode == CopyMode::Copy {
+            return Ok(false);
+        }
+        let metadata: Metadata = std::fs::metadata(src)?;
+        if !Self::same_device(&metadata, dest)? {
+            return Ok(false);
+        }
+
+        if self.mode == CopyMode::Reflink || self.mode == CopyMode::Auto {
+            let src_file: File = File::open(src)?;
+            let dest_file: File = File::create(dest)?;
+            if Self::reflink(&src_file, &dest_file) {
+                std::fs::set_permissions(dest, metadata.permissions())?;
+                if self.preserve {
+                    self.preserve(src, dest)?;
+                }
+                return Ok(true);
+            }
+            drop(dest_file);
+            std::fs::remove_file(dest)?;
+        }
+
+        if self.mode == CopyMode::Hardlink || self.mode == CopyMode::Auto {
+            return self.hard_link(src, dest);
+        }
+        Ok(false)
+    }
+
+    /*
+     * A hardlink can fail even on the same device, for example with EPERM
+     * when the source is owned by another user and protected_hardlinks is
+     * enabled, with EMLINK or with EXDEV across bind mounts. In auto mode the
+     * file is then copied instead.
+     */
+    fn hard_link(&self, src: &Path, dest: &Path) -> Result<bool, BError> {
+        // Link the file and not the symlink pointing to it
+        match std::fs::hard_link(std::fs::canonicalize(src)?, dest) {
+            Ok(()) => Ok(true),
+            Err(_err) if self.mode == CopyMode::Auto => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn same_device(metadata: &Metadata, dest: &Path) -> Result<bool, BError> {
+        match dest.parent() {
+            Some(parent) => Ok(std::fs::metadata(parent)?.dev() == metadata.dev()),
+            None => Ok(false),
+        }
+    }
+
+    fn copy_symlink(&self, src: &Path, dest: &Path) -> Result<(), BError> {
+        let target: PathBuf = std::fs::read_link(src)?;
+        if dest.is_symlink() || dest.exists() {
+            std::fs::remove_file(dest)?;
+        }
+        std::os::unix::fs::symlink(target, dest)?;
+        Ok(())
+    }
+
+    /*
+     * The mode bits are always copied by std::fs::copy but not by the sparse
+     * copy so they are set here as well together with the timestamps.
+     */
+    fn preserve(&self, src: &Path, dest: &Path) -> Result<(), BError> {
+        let metadata: Metadata = std::fs::metadata(src)?;
+        std::fs::set_permissions(dest, metadata.permissions())?;
+        let file: File = File::options().write(true).open(dest)?;
+        file.set_times(
+            FileTimes::new()
+                .set_accessed(metadata.accessed()?)
+                .set_modified(metadata.modified()?),
+        )?;
+        Ok(())
+    }
+
+    pub fn reflink(src: &File, dest: &File) -> bool {
+        unsafe { libc::ioctl(dest.as_raw_fd(), libc::FICLONE as _, src.as_raw_fd()) == 0 }
+    }
+
+    fn copy_sparse(&self, src: &Path, dest: &Path) -> Result<(), BError> {
+        let src_file: File = File::open(src)?;
+        let dest_file: File = File::create(dest)?;
+        std::fs::set_permissions(dest, src_file.metadata()?.permissions())?;
+        if Self::reflink(&src_file, &dest_file) {
+            return Ok(());
+        }
+
+        let size: u64 = src_file.metadata()?.len();
+        dest_file.set_len(size)?;
+
+        let fd: i32 = src_file.as_raw_fd();
+        let mut buffer: Vec<u8> = vec![0; 1024 * 1024];
+        let mut offset: i64 = 0;
+        while (offset as u64) < size {
+            let data: i64 = unsafe { libc::lseek(fd, offset, libc::SEEK_DATA) };
+            if data < 0 {
+                let err: std::io::Error = std::io::Error::last_os_error();
+                if err.raw_os_error() == Some(libc::ENXIO) {
+                    // No more data only a hole until the end of the file
+                    break;
+                }
+                // The filesystem does not support SEEK_DATA so copy all of it
+                drop(dest_file);
+                std::fs::copy(src, dest)?;
+                return Ok(());
+            }
+            let mut hole: i64 = unsafe { libc::lseek(fd, data, libc::SEEK_HOLE) };
+            if hole < 0 {
+                hole = size as i64;
+            }
+
+            let mut pos: u64 = data as u64;
+            while pos < hole as u64 {
+                let len: usize = buffer.len().min((hole as u64 - pos) as usize);
+                let n: usize = src_file.read_at(&mut buffer[..len], pos)?;
+                if n == 0 {
+                    break;
+                }
+                dest_file.write_all_at(&buffer[..n], pos)?;
+                pos += n as u64;
+            }
+            offset = hole;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+    use tempdir::TempDir;
+
+    use crate::fs::{Copier, CopyMode};
+
+    #[test]
+    fn test_copier_symlink() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        std::fs::write(dir.join("file.txt"), "content").expect("Failed to write file");
+        std::os::unix::fs::symlink("file.txt", dir.join("link.txt"))
+            .expect("Failed to create link");
+
+        let copier: Copier = Copier {
+            symlinks: true,
+            ..Default::default()
+        };
+        copier
+            .copy(&dir.join("link.txt"), &dir.join("dest/link.txt"))
+            .expect("Failed to copy");
+        assert!(dir.join("dest/link.txt").is_symlink());
+        assert_eq!(
+            std::fs::read_link(dir.join("dest/link.txt")).expect("Failed to read link"),
+            PathBuf::from("file.txt")
+        );
+
+        Copier::default()
+            .copy(&dir.join("link.txt"), &dir.join("dest/file.txt"))
+            .expect("Failed to copy");
+        assert!(!dir.join("dest/file.txt").is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("dest/file.txt")).expect("Failed to read file"),
+            "content"
+        );
+    }
+
+    #[test]
+    fn test_copier_preserve() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        let src: PathBuf = dir.join("file.sh");
+        std::fs::write(&src, "content").expect("Failed to write file");
+        std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o750))
+            .expect("Failed to set permissions");
+        let mtime: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000);
+        File::options()
+            .write(true)
+            .open(&src)
+            .expect("Failed to open file")
+            .set_modified(mtime)
+            .expect("Failed to set mtime");
+
+        let copier: Copier = Copier {
+            preserve: true,
+            sparse: true,
+            ..Default::default()
+        };
+        copier
+            .copy(&src, &dir.join("dest/file.sh"))
+            .expect("Failed to copy");
+        let metadata: std::fs::Metadata =
+            std::fs::metadata(dir.join("dest/file.sh")).expect("Failed to read metadata");
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+        assert_eq!(metadata.mtime(), 1700000000);
+    }
+
+    #[test]
+    fn test_copier_sparse() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        let src: PathBuf = dir.join("sparse.img");
+        let size: u64 = 64 * 1024 * 1024;
+        {
+            let file: File = File::create(&src).expect("Failed to create file");
+            file.set_len(size).expect("Failed to set size");
+            std::os::unix::fs::FileExt::write_all_at(&file, b"start", 0).expect("Failed to write");
+            std::os::unix::fs::FileExt::write_all_at(&file, b"end", size - 3)
+                .expect("Failed to write");
+        }
+
+        let copier: Copier = Copier {
+            sparse: true,
+            ..Default::default()
+        };
+        copier
+            .copy(&src, &dir.join("dest/sparse.img"))
+            .expect("Failed to copy");
+        let content: Vec<u8> = std::fs::read(dir.join("dest/sparse.img")).expect("Failed to read");
+        assert_eq!(content.len() as u64, size);
+        assert_eq!(&content[..5], b"start");
+        assert_eq!(&content[content.len() - 3..], b"end");
+        let src_blocks: u64 = std::fs::metadata(&src).unwrap().blocks();
+        let dest_blocks: u64 = std::fs::metadata(dir.join("dest/sparse.img"))
+            .unwrap()
+            .blocks();
+        assert!(dest_blocks <= src_blocks.max(1024));
+    }
+
+    #[test]
+    fn test_copier_hardlink() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        let src: PathBuf = dir.join("system.img");
+        std::fs::write(&src, "content").expect("Failed to write file");
+
+        let copier: Copier = Copier {
+            mode: CopyMode::Hardlink,
+            ..Default::default()
+        };
+        copier
+            .copy(&src, &dir.join("dest/system.img"))
+            .expect("Failed to link");
+        let src_metadata: std::fs::Metadata = std::fs::metadata(&src).unwrap();
+        let dest_metadata: std::fs::Metadata =
+            std::fs::metadata(dir.join("dest/system.img")).unwrap();
+        assert_eq!(src_metadata.ino(), dest_metadata.ino());
+        assert_eq!(src_metadata.nlink(), 2);
+
+        // Collecting again as a copy must not truncate the source
+        Copier::default()
+            .copy(&src, &dir.join("dest/system.img"))
+            .expect("Failed to copy");
+        let dest_metadata: std::fs::Metadata =
+            std::fs::metadata(dir.join("dest/system.img")).unwrap();
+        assert_ne!(src_metadata.ino(), dest_metadata.ino());
+        assert_eq!(
+            std::fs::read_to_string(&src).expect("Failed to read file"),
+            "content"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("dest/system.img")).expect("Failed to read file"),
+            "content"
+        );
+    }
+
+    #[test]
+    fn test_copier_auto() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        let src: PathBuf = dir.join("system.img");
+        std::fs::write(&src, "content").expect("Failed to write file");
+
+        for mode in [CopyMode::Reflink, CopyMode::Auto] {
+            let copier: Copier = Copier {
+                mode,
+                ..Default::default()
+            };
+            copier
+                .copy(&src, &dir.join("dest/system.img"))
+                .expect("Failed to collect");
+            assert_eq!(
+                std::fs::read_to_string(dir.join("dest/system.img")).expect("Failed to read file"),
+                "content"
+            );
+        }
+    }
+
+    #[test]
+    fn test_copier_hardlink_fallback() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir: PathBuf = PathBuf::from(temp_dir.path());
+        let src: PathBuf = dir.join("system.img");
+        let dest: PathBuf = dir.join("dest.img");
+        std::fs::write(&src, "content").expect("Failed to write file");
+        // An existing destination makes the hardlink fail
+        std::fs::write(&dest, "old").expect("Failed to write file");
+
+        let copier: Copier = Copier {
+            mode: CopyMode::Auto,
+            ..Default::default()
+        };
+        assert!(!copier.hard_link(&src, &dest).expect("Failed to fall back"));
+
+        let copier: Copier = Copier {
+            mode: CopyMode::Hardlink,
+            ..Default::default()
+        };
+        match copier.hard_link(&src, &dest) {
+            Ok(_linked) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert!(e.to_string().contains("File exists"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_copier_mode() {
+        assert_eq!("".parse::<CopyMode>().unwrap(), CopyMode::Copy);
+        assert_eq!("hardlink".parse::<CopyMode>().unwrap(), CopyMode::Hardlink);
+        assert_eq!("reflink".parse::<CopyMode>().unwrap(), CopyMode::Reflink);
+        assert_eq!("auto".parse::<CopyMode>().unwrap(), CopyMode::Auto);
+        match "symlink".parse::<CopyMode>() {
+            Ok(_m) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid collection mode 'symlink', must be copy, hardlink, reflink or auto!"
+                );
+            }
+        }
+    }
+}
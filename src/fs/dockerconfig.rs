@@ -0,0 +1,160 @@
+use base64::Engine;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::error::BError;
+
+/*
+ * Reader for the credentials in the docker config file written by
+ * 'docker login'. The credentials are either stored base64 encoded in the
+ * auths node or in a credential store accessed using a docker credential
+ * helper.
+ */
+pub struct DockerConfig {
+    data: Value,
+}
+
+impl DockerConfig {
+    pub fn from_file(path: &Path) -> Result<Self, BError> {
+        let content: String = std::fs::read_to_string(path)?;
+        Self::from_str(&content)
+    }
+
+    pub fn from_str(content: &str) -> Result<Self, BError> {
+        Ok(DockerConfig {
+            data: serde_json::from_str(content)?,
+        })
+    }
+
+    /*
+     * The keys of the auths node are either a registry host or a url like
+     * https://registry.example.com/v1/ so the scheme and path are dropped
+     * before comparing with the host.
+     */
+    fn registry(key: &str) -> &str {
+        let without_scheme: &str = key.split_once("://").map(|(_, k)| k).unwrap_or(key);
+        without_scheme.split('/').next().unwrap_or_default()
+    }
+
+    /*
+     * The user and password for the registry host. A credential helper
+     * configured for the host in credHelpers is preferred over the
+     * credsStore and the credsStore is preferred over the auths node.
+     */
+    pub fn credentials(&self, host: &str) -> Result<Option<(String, String)>, BError> {
+        let helper: Option<&str> = self
+            .data
+            .get("credHelpers")
+            .and_then(|h| h.get(host))
+            .or_else(|| self.data.get("credsStore"))
+            .and_then(|h| h.as_str());
+        if let Some(helper) = helper {
+            return Self::helper_credentials(helper, host);
+        }
+
+        let auth: Option<&Value> =
+            self.data
+                .get("auths")
+                .and_then(|a| a.as_object())
+                .and_then(|auths| {
+                    auths
+                        .iter()
+                        .find(|(key, _)| Self::registry(key) == host)
+                        .map(|(_, auth)| auth)
+                });
+
+        match auth {
+            Some(auth) => {
+                if let Some(encoded) = auth.get("auth").and_then(|a| a.as_str()) {
+                    let decoded: Vec<u8> = base64::engine::general_purpose::STANDARD
+                        .decode(encoded)
+                        .map_err(|err| {
+                            BError::UploadError(format!(
+                                "Invalid auth for '{}' in docker config: {}",
+                                host, err
+                            ))
+                        })?;
+                    let decoded: String = String::from_utf8_lossy(&decoded).to_string();
+                    if let Some((user, password)) = decoded.split_once(':') {
+                        return Ok(Some((user.to_string(), password.to_string())));
+                    }
+                }
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn helper_credentials(helper: &str, host: &str) -> Result<Option<(String, String)>, BError> {
+        let program: String = format!("docker-credential-{}", helper);
+        let mut child = Command::new(&program)
+            .arg("get")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| BError::UploadError(format!("Failed to run '{}': {}", program, err)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(host.as_bytes())?;
+        }
+        let output: std::process::Output = child.wait_with_output()?;
+
+        /*
+         * The helper is failing if there are no credentials stored for the
+         * host which is not an error since the registry might not require
+         * any credentials.
+         */
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let data: Value = serde_json::from_slice(&output.stdout)?;
+        Ok(Some((
+            data["Username"].as_str().unwrap_or_default().to_string(),
+            data["Secret"].as_str().unwrap_or_default().to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fs::DockerConfig;
+
+    #[test]
+    fn test_docker_config_credentials() {
+        let config: DockerConfig = DockerConfig::from_str(
+            r#"
+            {
+                "auths": {
+                    "registry.example.com:5000": {
+                        "auth": "dXNlcjE6c2VjcmV0MQ=="
+                    },
+                    "https://other.example.com/v1/": {
+                        "auth": "dXNlcjI6c2VjcmV0Mg=="
+                    }
+                }
+            }"#,
+        )
+        .expect("Failed to parse docker config");
+        assert_eq!(
+            config
+                .credentials("registry.example.com:5000")
+                .expect("Failed to get credentials"),
+            Some((String::from("user1"), String::from("secret1")))
+        );
+        assert_eq!(
+            config
+                .credentials("other.example.com")
+                .expect("Failed to get credentials"),
+            Some((String::from("user2"), String::from("secret2")))
+        );
+        assert_eq!(
+            config
+                .credentials("unknown.example.com")
+                .expect("Failed to get credentials"),
+            None
+        );
+    }
+}
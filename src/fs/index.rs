@@ -0,0 +1,399 @@
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cli::signal;
+use crate::error::BError;
+
+pub const ARTIFACTS_INDEX: &str = "artifacts.json";
+pub const ARTIFACTS_SHA256SUMS: &str = "SHA256SUMS";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct IndexEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub task: String,
+    pub atype: String,
+}
+
+/*
+ * A difference between the artifacts indexes of two builds. An artifact is
+ * changed if the checksum is different.
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub enum IndexChange {
+    Added(IndexEntry),
+    Removed(IndexEntry),
+    Changed(IndexEntry, IndexEntry),
+}
+
+/*
+ * Index of all artifacts collected to the artifacts directory. The index is
+ * written to the artifacts directory as artifacts.json and is updated every
+ * time the artifacts of a task are collected. The path of each entry is
+ * relative to the artifacts directory so the index can be used by any tool
+ * processing the artifacts without having to walk the artifacts directory
+ * and hash all the files again. The index is also keeping track of the
+ * duration in seconds of each task built.
+ */
+pub struct ArtifactsIndex {
+    artifacts_dir: PathBuf,
+    build: String,
+    entries: IndexMap<String, IndexEntry>,
+    tasks: IndexMap<String, u64>,
+}
+
+impl ArtifactsIndex {
+    pub fn new(artifacts_dir: &Path, build: &str) -> Self {
+        ArtifactsIndex {
+            artifacts_dir: artifacts_dir.to_path_buf(),
+            build: build.to_string(),
+            entries: IndexMap::new(),
+            tasks: IndexMap::new(),
+        }
+    }
+
+    /*
+     * Load the index from the artifacts directory if there is one. Entries for
+     * files that no longer exists in the artifacts directory are dropped. An
+     * index written by another build config is not loaded at all.
+     */
+    pub fn load(artifacts_dir: &Path, build: &str) -> Result<Self, BError> {
+        let mut index: ArtifactsIndex = Self::new(artifacts_dir, build);
+        let index_path: PathBuf = index.path();
+        if !index_path.exists() {
+            return Ok(index);
+        }
+
+        let data: Value = serde_json::from_str(&std::fs::read_to_string(&index_path)?)?;
+        let index_build: &str = data["build"].as_str().unwrap_or_default();
+        if !build.is_empty() && index_build != build {
+            return Ok(index);
+        }
+        if let Some(artifacts) = data.get("artifacts").and_then(|a| a.as_array()) {
+            for a in artifacts.iter() {
+                let entry: IndexEntry = IndexEntry {
+                    path: a["path"].as_str().unwrap_or_default().to_string(),
+                    size: a["size"].as_u64().unwrap_or_default(),
+                    sha256: a["sha256"].as_str().unwrap_or_default().to_string(),
+                    task: a["task"].as_str().unwrap_or_default().to_string(),
+                    atype: a["type"].as_str().unwrap_or_default().to_string(),
+                };
+                if index.artifacts_dir.join(&entry.path).is_file() {
+                    index.entries.insert(entry.path.clone(), entry);
+                }
+            }
+        }
+        if let Some(tasks) = data.get("tasks").and_then(|t| t.as_array()) {
+            for t in tasks.iter() {
+                index.tasks.insert(
+                    t["name"].as_str().unwrap_or_default().to_string(),
+                    t["duration"].as_u64().unwrap_or_default(),
+                );
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn sha256(path: &Path) -> Result<String, BError> {
+        let mut file: File = File::open(path)?;
+        let mut hasher: Sha256 = Sha256::new();
+        let mut buffer: Vec<u8> = vec![0; 1024 * 1024];
+        loop {
+            signal::check()?;
+            let n: usize = file.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /*
+     * Add a collected artifact to the index. Directories are skipped since
+     * any file collected into a directory is added as an entry of its own.
+     */
+    pub fn add(&mut self, dest: &Path, task: &str, atype: &str) -> Result<(), BError> {
+        if !dest.is_file() {
+            return Ok(());
+        }
+
+        let path: String = dest
+            .strip_prefix(&self.artifacts_dir)
+            .unwrap_or(dest)
+            .to_string_lossy()
+            .to_string();
+        let entry: IndexEntry = IndexEntry {
+            path: path.clone(),
+            size: std::fs::metadata(dest)?.len(),
+            sha256: Self::sha256(dest)?,
+            task: task.to_string(),
+            atype: atype.to_string(),
+        };
+        self.entries.insert(path, entry);
+        Ok(())
+    }
+
+    pub fn add_task(&mut self, task: &str, duration: Duration) {
+        self.tasks.insert(task.to_string(), duration.as_secs());
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.artifacts_dir.join(ARTIFACTS_INDEX)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /*
+     * Compare the index with the index of another build. The removed and
+     * changed artifacts are listed in the order of this index followed by
+     * the artifacts only available in the other index.
+     */
+    pub fn diff(&self, other: &ArtifactsIndex) -> Vec<IndexChange> {
+        let mut changes: Vec<IndexChange> = vec![];
+        for (path, entry) in self.entries.iter() {
+            match other.entries.get(path) {
+                Some(o) => {
+                    if o.sha256 != entry.sha256 {
+                        changes.push(IndexChange::Changed(entry.clone(), o.clone()));
+                    }
+                }
+                None => changes.push(IndexChange::Removed(entry.clone())),
+            }
+        }
+        for (path, entry) in other.entries.iter() {
+            if !self.entries.contains_key(path) {
+                changes.push(IndexChange::Added(entry.clone()));
+            }
+        }
+        changes
+    }
+
+    pub fn artifacts_json(&self) -> Value {
+        let artifacts: Vec<Value> = self
+            .entries
+            .values()
+            .map(|e| {
+                json!({
+                    "path": e.path,
+                    "size": e.size,
+                    "sha256": e.sha256,
+                    "task": e.task,
+                    "type": e.atype,
+                })
+            })
+            .collect();
+        Value::Array(artifacts)
+    }
+
+    pub fn tasks_json(&self) -> Value {
+        let tasks: Vec<Value> = self
+            .tasks
+            .iter()
+            .map(|(name, duration)| {
+                json!({
+                    "name": name,
+                    "duration": duration,
+                })
+            })
+            .collect();
+        Value::Array(tasks)
+    }
+
+    pub fn to_json(&self) -> Result<String, BError> {
+        let mut index: Value = json!({
+            "build": self.build,
+            "artifacts": self.artifacts_json(),
+        });
+        if !self.tasks.is_empty() {
+            index["tasks"] = self.tasks_json();
+        }
+        Ok(serde_json::to_string_pretty(&index)?)
+    }
+
+    /*
+     * The SHA256SUMS file is using the same format as sha256sum so it can be
+     * verified using 'sha256sum -c SHA256SUMS' from the artifacts directory.
+     */
+    pub fn sha256sums(&self) -> String {
+        self.entries
+            .values()
+            .map(|e| format!("{}  {}\n", e.sha256, e.path))
+            .collect()
+    }
+
+    pub fn write(&self, sha256sums: bool) -> Result<(), BError> {
+        std::fs::create_dir_all(&self.artifacts_dir)?;
+        let mut file: File = File::create(self.path())?;
+        file.write_all(self.to_json()?.as_bytes())?;
+        if sha256sums {
+            let mut file: File = File::create(self.artifacts_dir.join(ARTIFACTS_SHA256SUMS))?;
+            file.write_all(self.sha256sums().as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    use crate::fs::index::{IndexChange, IndexEntry, ARTIFACTS_INDEX, ARTIFACTS_SHA256SUMS};
+    use crate::fs::ArtifactsIndex;
+
+    #[test]
+    fn test_index_sha256() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let file: PathBuf = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "hello world\n").expect("Failed to write file");
+        assert_eq!(
+            ArtifactsIndex::sha256(&file).expect("Failed to hash file"),
+            "a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447"
+        );
+    }
+
+    #[test]
+    fn test_index_write_and_load() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        std::fs::create_dir_all(artifacts_dir.join("images")).expect("Failed to create dir");
+        std::fs::write(artifacts_dir.join("images/system.img"), "hello world\n")
+            .expect("Failed to write file");
+        std::fs::write(artifacts_dir.join("removed.txt"), "removed").expect("Failed to write file");
+        let mut index: ArtifactsIndex = ArtifactsIndex::new(&artifacts_dir, "test");
+        index
+            .add(&artifacts_dir.join("images/system.img"), "task1", "file")
+            .expect("Failed to add entry");
+        index
+            .add(&artifacts_dir.join("removed.txt"), "task1", "file")
+            .expect("Failed to add entry");
+        index
+            .add(&artifacts_dir.join("images"), "task1", "directory")
+            .expect("Failed to add entry");
+        index.write(true).expect("Failed to write index");
+        assert!(artifacts_dir.join(ARTIFACTS_INDEX).exists());
+        let removed_sha256: String = ArtifactsIndex::sha256(&artifacts_dir.join("removed.txt"))
+            .expect("Failed to hash file");
+        assert_eq!(
+            std::fs::read_to_string(artifacts_dir.join(ARTIFACTS_SHA256SUMS))
+                .expect("Failed to read file"),
+            format!(
+                "a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447  images/system.img\n{}  removed.txt\n",
+                removed_sha256
+            )
+        );
+
+        std::fs::remove_file(artifacts_dir.join("removed.txt")).expect("Failed to remove file");
+        let index: ArtifactsIndex =
+            ArtifactsIndex::load(&artifacts_dir, "test").expect("Failed to load index");
+        assert_eq!(index.entries.len(), 1);
+        let entry: &IndexEntry = &index.entries["images/system.img"];
+        assert_eq!(entry.path, "images/system.img");
+        assert_eq!(entry.size, 12);
+        assert_eq!(entry.task, "task1");
+        assert_eq!(entry.atype, "file");
+    }
+
+    #[test]
+    fn test_index_tasks() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        let mut index: ArtifactsIndex = ArtifactsIndex::new(&artifacts_dir, "test");
+        index.add_task("task1", Duration::from_secs(12));
+        index.add_task("task2", Duration::from_millis(3500));
+        index.write(false).expect("Failed to write index");
+        let index: ArtifactsIndex =
+            ArtifactsIndex::load(&artifacts_dir, "test").expect("Failed to load index");
+        assert_eq!(index.tasks.get("task1"), Some(&12));
+        assert_eq!(index.tasks.get("task2"), Some(&3));
+        assert!(index.entries.is_empty());
+    }
+
+    #[test]
+    fn test_index_load_other_build() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        std::fs::create_dir_all(&artifacts_dir).expect("Failed to create dir");
+        std::fs::write(artifacts_dir.join("system.img"), "hello world\n")
+            .expect("Failed to write file");
+        let mut index: ArtifactsIndex = ArtifactsIndex::new(&artifacts_dir, "other");
+        index
+            .add(&artifacts_dir.join("system.img"), "task1", "file")
+            .expect("Failed to add entry");
+        index.add_task("task1", Duration::from_secs(12));
+        index.write(false).expect("Failed to write index");
+        let index: ArtifactsIndex =
+            ArtifactsIndex::load(&artifacts_dir, "test").expect("Failed to load index");
+        assert!(index.entries.is_empty());
+        assert!(index.tasks.is_empty());
+        let index: ArtifactsIndex =
+            ArtifactsIndex::load(&artifacts_dir, "").expect("Failed to load index");
+        assert_eq!(index.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_index_diff() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let dir_a: PathBuf = temp_dir.path().join("a");
+        let dir_b: PathBuf = temp_dir.path().join("b");
+        for (dir, files) in [
+            (
+                &dir_a,
+                vec![
+                    ("boot.img", "boot"),
+                    ("system.img", "system"),
+                    ("old.img", "old"),
+                ],
+            ),
+            (
+                &dir_b,
+                vec![
+                    ("boot.img", "boot"),
+                    ("system.img", "system2"),
+                    ("new.img", "new"),
+                ],
+            ),
+        ] {
+            std::fs::create_dir_all(dir).expect("Failed to create dir");
+            let mut index: ArtifactsIndex = ArtifactsIndex::new(dir, "test");
+            for (name, content) in files {
+                std::fs::write(dir.join(name), content).expect("Failed to write file");
+                index
+                    .add(&dir.join(name), "task1", "file")
+                    .expect("Failed to add entry");
+            }
+            index.write(false).expect("Failed to write index");
+        }
+
+        let index_a: ArtifactsIndex = ArtifactsIndex::load(&dir_a, "").expect("Failed to load");
+        let index_b: ArtifactsIndex = ArtifactsIndex::load(&dir_b, "").expect("Failed to load");
+        let changes: Vec<IndexChange> = index_a.diff(&index_b);
+        assert_eq!(changes.len(), 3);
+        match &changes[0] {
+            IndexChange::Changed(a, b) => {
+                assert_eq!(a.path, "system.img");
+                assert_eq!(a.size, 6);
+                assert_eq!(b.size, 7);
+            }
+            _ => panic!("Was expecting a changed artifact!"),
+        }
+        assert!(matches!(&changes[1], IndexChange::Removed(e) if e.path == "old.img"));
+        assert!(matches!(&changes[2], IndexChange::Added(e) if e.path == "new.img"));
+        assert!(index_a.diff(&index_a).is_empty());
+    }
+}
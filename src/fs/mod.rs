@@ -1,7 +1,19 @@
 pub mod archiver;
 pub mod config;
+pub mod copier;
+pub mod dockerconfig;
+pub mod index;
 pub mod manifest;
+pub mod netrc;
+pub mod retention;
+pub mod revisions;
 
 pub use archiver::Archiver;
 pub use config::ConfigFileReader;
+pub use copier::{Copier, CopyMode};
+pub use dockerconfig::DockerConfig;
+pub use index::{ArtifactsIndex, IndexChange};
 pub use manifest::Manifest;
+pub use netrc::Netrc;
+pub use retention::{link_latest, parse_size, ArtifactsBuild, Retention};
+pub use revisions::workspace_revisions;
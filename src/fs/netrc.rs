@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use crate::error::BError;
+
+/*
+ * Minimal reader for the netrc file format. Only the machine, default,
+ * login and password tokens are used, any other token like account or
+ * macdef is skipped together with its value.
+ */
+pub struct Netrc {
+    machines: Vec<(String, String, String)>,
+}
+
+impl Netrc {
+    pub fn from_file(path: &Path) -> Result<Self, BError> {
+        let content: String = std::fs::read_to_string(path)?;
+        Ok(Self::from_str(&content))
+    }
+
+    pub fn from_str(content: &str) -> Self {
+        let mut machines: Vec<(String, String, String)> = vec![];
+        let mut tokens = content.split_whitespace();
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "machine" => {
+                    let host: String = tokens.next().unwrap_or_default().to_string();
+                    machines.push((host, String::new(), String::new()));
+                }
+                "default" => {
+                    machines.push((String::new(), String::new(), String::new()));
+                }
+                "login" => {
+                    if let Some(machine) = machines.last_mut() {
+                        machine.1 = tokens.next().unwrap_or_default().to_string();
+                    }
+                }
+                "password" => {
+                    if let Some(machine) = machines.last_mut() {
+                        machine.2 = tokens.next().unwrap_or_default().to_string();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Netrc { machines }
+    }
+
+    /*
+     * The login and password of the machine matching the host. If no machine
+     * is matching the host the default entry is used if there is one.
+     */
+    pub fn credentials(&self, host: &str) -> Option<(String, String)> {
+        self.machines
+            .iter()
+            .find(|(h, _, _)| h == host)
+            .or_else(|| self.machines.iter().find(|(h, _, _)| h.is_empty()))
+            .map(|(_, login, password)| (login.clone(), password.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fs::Netrc;
+
+    #[test]
+    fn test_netrc_credentials() {
+        let netrc: Netrc = Netrc::from_str(
+            r#"
+            machine artifactory.example.com
+                login user1
+                password secret1
+            machine nexus.example.com login user2 account acc password secret2
+            default login anonymous password guest
+            "#,
+        );
+        assert_eq!(
+            netrc.credentials("artifactory.example.com"),
+            Some((String::from("user1"), String::from("secret1")))
+        );
+        assert_eq!(
+            netrc.credentials("nexus.example.com"),
+            Some((String::from("user2"), String::from("secret2")))
+        );
+        assert_eq!(
+            netrc.credentials("unknown.example.com"),
+            Some((String::from("anonymous"), String::from("guest")))
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::configs::Config;
+use crate::error::BError;
+use crate::fs::index::ARTIFACTS_INDEX;
+
+pub const ARTIFACTS_LATEST: &str = "latest";
+
+/*
+ * A build collected to a versioned sub directory of the artifacts directory.
+ * Any directory containing an artifacts.json index is a build and the mtime
+ * of the index is the time the build was last collected.
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct ArtifactsBuild {
+    pub path: PathBuf,
+    pub mtime: SystemTime,
+    pub size: u64,
+}
+
+/*
+ * The retention policy for the versioned builds in the artifacts directory.
+ * A build is removed if it is not one of the last 'keep' builds, if it is older
+ * than 'maxage' or if the total size of it and all newer builds is bigger than
+ * 'maxsize'. The build the latest link is pointing to is never removed, if
+ * there is no latest link the most recently collected build is kept instead.
+ */
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Retention {
+    pub keep: String,
+    pub max_age: String,
+    pub max_size: String,
+}
+
+impl Config for Retention {}
+
+impl Retention {
+    pub fn from_value(data: &Value) -> Result<Self, BError> {
+        match Self::get_value("retention", data) {
+            Ok(retention_data) => Self::new(retention_data),
+            Err(_err) => Ok(Retention::default()),
+        }
+    }
+
+    pub fn new(data: &Value) -> Result<Self, BError> {
+        let keep: String = Self::get_str_value("keep", data, Some(String::from("")))?;
+        let max_age: String = Self::get_str_value("maxage", data, Some(String::from("")))?;
+        let max_size: String = Self::get_str_value("maxsize", data, Some(String::from("")))?;
+
+        Ok(Retention {
+            keep,
+            max_age,
+            max_size,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keep.is_empty() && self.max_age.is_empty() && self.max_size.is_empty()
+    }
+
+    fn keep(&self) -> Result<Option<usize>, BError> {
+        if self.keep.is_empty() {
+            return Ok(None);
+        }
+        match self.keep.parse::<usize>() {
+            Ok(keep) => Ok(Some(keep)),
+            Err(_e) => Err(BError::ValueError(format!(
+                "Invalid retention keep '{}', must be a number!",
+                self.keep
+            ))),
+        }
+    }
+
+    /*
+     * The max age is a number followed by one of the units d (days), h (hours)
+     * or m (minutes). Without a unit the max age is in days.
+     */
+    fn max_age(&self) -> Result<Option<Duration>, BError> {
+        if self.max_age.is_empty() {
+            return Ok(None);
+        }
+        let (value, unit) = Self::split_unit(&self.max_age);
+        let seconds: u64 = match unit {
+            "" | "d" => 24 * 60 * 60,
+            "h" => 60 * 60,
+            "m" => 60,
+            _ => 0,
+        };
+        match value.parse::<u64>() {
+            Ok(v) if seconds != 0 => Ok(Some(Duration::from_secs(v * seconds))),
+            _ => Err(BError::ValueError(format!(
+                "Invalid retention maxage '{}', must be a number of days (d), hours (h) or minutes (m)!",
+                self.max_age
+            ))),
+        }
+    }
+
+    /*
+     * The max size is a number followed by one of the units K, M, G or T.
+     * Without a unit the max size is in bytes.
+     */
+    fn max_size(&self) -> Result<Option<u64>, BError> {
+        if self.max_size.is_empty() {
+            return Ok(None);
+        }
+        match parse_size(&self.max_size) {
+            Some(size) => Ok(Some(size)),
+            None => Err(BError::ValueError(format!(
+                "Invalid retention maxsize '{}', must be a size in bytes or K, M, G or T!",
+                self.max_size
+            ))),
+        }
+    }
+
+    fn split_unit(value: &str) -> (&str, &str) {
+        let index: usize = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        value.split_at(index)
+    }
+
+    fn dir_size(dir: &Path) -> Result<u64, BError> {
+        let mut size: u64 = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let entry: std::fs::DirEntry = entry?;
+            let metadata: std::fs::Metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                size += Self::dir_size(&entry.path())?;
+            } else {
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+
+    fn find_builds(dir: &Path, builds: &mut Vec<ArtifactsBuild>) -> Result<(), BError> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry: std::fs::DirEntry = entry?;
+            // Symlinks like the latest link are not followed
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let path: PathBuf = entry.path();
+            let index: PathBuf = path.join(ARTIFACTS_INDEX);
+            if index.exists() {
+                builds.push(ArtifactsBuild {
+                    mtime: std::fs::metadata(&index)?.modified()?,
+                    size: Self::dir_size(&path)?,
+                    path,
+                });
+            } else {
+                Self::find_builds(&path, builds)?;
+            }
+        }
+        Ok(())
+    }
+
+    /*
+     * List all the builds in the artifacts directory sorted with the most
+     * recently collected build first.
+     */
+    pub fn builds(artifacts_dir: &Path) -> Result<Vec<ArtifactsBuild>, BError> {
+        let mut builds: Vec<ArtifactsBuild> = vec![];
+        if artifacts_dir.is_dir() {
+            Self::find_builds(artifacts_dir, &mut builds)?;
+        }
+        builds.sort_by(|a, b| b.mtime.cmp(&a.mtime).then(a.path.cmp(&b.path)));
+        Ok(builds)
+    }
+
+    pub fn expired(
+        &self,
+        builds: &[ArtifactsBuild],
+        latest: Option<&Path>,
+        now: SystemTime,
+    ) -> Result<Vec<ArtifactsBuild>, BError> {
+        let keep: Option<usize> = self.keep()?;
+        let max_age: Option<Duration> = self.max_age()?;
+        let max_size: Option<u64> = self.max_size()?;
+        let mut expired: Vec<ArtifactsBuild> = vec![];
+        let mut total: u64 = 0;
+
+        for (i, build) in builds.iter().enumerate() {
+            total += build.size;
+            let is_latest: bool = match latest {
+                Some(path) => build.path == path,
+                None => i == 0,
+            };
+            if is_latest {
+                continue;
+            }
+            let age: Duration = now.duration_since(build.mtime).unwrap_or_default();
+            if keep.is_some_and(|k| i >= k)
+                || max_age.is_some_and(|a| age > a)
+                || max_size.is_some_and(|s| total > s)
+            {
+                expired.push(build.clone());
+            }
+        }
+        Ok(expired)
+    }
+
+    /*
+     * Remove the expired builds from the artifacts directory together with any
+     * parent directory left empty. If the latest link is no longer pointing to
+     * a build it is removed as well.
+     */
+    pub fn prune(
+        &self,
+        artifacts_dir: &Path,
+        dry_run: bool,
+    ) -> Result<Vec<ArtifactsBuild>, BError> {
+        let builds: Vec<ArtifactsBuild> = Self::builds(artifacts_dir)?;
+        let latest: PathBuf = artifacts_dir.join(ARTIFACTS_LATEST);
+        let latest_build: Option<PathBuf> = if latest.is_symlink() && latest.exists() {
+            Some(artifacts_dir.join(std::fs::read_link(&latest)?))
+        } else {
+            None
+        };
+        let expired: Vec<ArtifactsBuild> =
+            self.expired(&builds, latest_build.as_deref(), SystemTime::now())?;
+        if dry_run {
+            return Ok(expired);
+        }
+
+        for build in expired.iter() {
+            std::fs::remove_dir_all(&build.path)?;
+            let mut parent: Option<&Path> = build.path.parent();
+            while let Some(dir) = parent {
+                if dir == artifacts_dir || std::fs::read_dir(dir)?.next().is_some() {
+                    break;
+                }
+                std::fs::remove_dir(dir)?;
+                parent = dir.parent();
+            }
+        }
+
+        if latest.is_symlink() && !latest.exists() {
+            std::fs::remove_file(&latest)?;
+        }
+        Ok(expired)
+    }
+}
+
+/*
+ * Parse a size in bytes with an optional K, M, G or T unit. None is returned
+ * if the size is not valid.
+ */
+pub fn parse_size(size: &str) -> Option<u64> {
+    let index: usize = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (value, unit) = size.split_at(index);
+    let bytes: u64 = match unit {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+    value.parse::<u64>().ok().map(|v| v * bytes)
+}
+
+/*
+ * Point the latest link in the artifacts directory to the build directory. The
+ * link is relative so the artifacts directory can be moved or mounted at a
+ * different path.
+ */
+pub fn link_latest(artifacts_dir: &Path, build_dir: &Path) -> Result<(), BError> {
+    let latest: PathBuf = artifacts_dir.join(ARTIFACTS_LATEST);
+    let target: &Path = build_dir.strip_prefix(artifacts_dir)?;
+    if latest.is_symlink() {
+        std::fs::remove_file(&latest)?;
+    } else if latest.exists() {
+        return Err(BError::IOError(format!(
+            "Failed to link '{}', it is not a symlink",
+            latest.display()
+        )));
+    }
+    std::os::unix::fs::symlink(target, &latest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+    use tempdir::TempDir;
+
+    use crate::fs::retention::{link_latest, ArtifactsBuild, Retention};
+
+    fn build(path: &str, days: u64, size: u64, now: SystemTime) -> ArtifactsBuild {
+        ArtifactsBuild {
+            path: PathBuf::from(path),
+            mtime: now - Duration::from_secs(days * 24 * 60 * 60),
+            size,
+        }
+    }
+
+    fn setup_build(artifacts_dir: &Path, build: &str, mtime: SystemTime) {
+        let build_dir: PathBuf = artifacts_dir.join(build);
+        std::fs::create_dir_all(&build_dir).expect("Failed to create build dir");
+        std::fs::write(build_dir.join("system.img"), "content").expect("Failed to write file");
+        let index: std::fs::File =
+            std::fs::File::create(build_dir.join("artifacts.json")).expect("Failed to write index");
+        index.set_modified(mtime).expect("Failed to set mtime");
+    }
+
+    #[test]
+    fn test_retention_expired() {
+        let now: SystemTime = SystemTime::now();
+        let builds: Vec<ArtifactsBuild> = vec![
+            build("1.0.3", 0, 10, now),
+            build("1.0.2", 2, 10, now),
+            build("1.0.1", 10, 10, now),
+            build("1.0.0", 40, 10, now),
+        ];
+        let retention = |keep: &str, max_age: &str, max_size: &str| Retention {
+            keep: keep.to_string(),
+            max_age: max_age.to_string(),
+            max_size: max_size.to_string(),
+        };
+        let paths = |expired: Vec<ArtifactsBuild>| -> Vec<PathBuf> {
+            expired.iter().map(|b| b.path.clone()).collect()
+        };
+
+        assert!(retention("", "", "")
+            .expired(&builds, None, now)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            paths(retention("2", "", "").expired(&builds, None, now).unwrap()),
+            vec![PathBuf::from("1.0.1"), PathBuf::from("1.0.0")]
+        );
+        assert_eq!(
+            paths(
+                retention("", "30d", "")
+                    .expired(&builds, None, now)
+                    .unwrap()
+            ),
+            vec![PathBuf::from("1.0.0")]
+        );
+        assert_eq!(
+            paths(retention("", "", "25").expired(&builds, None, now).unwrap()),
+            vec![PathBuf::from("1.0.1"), PathBuf::from("1.0.0")]
+        );
+        // The latest build is always kept
+        assert_eq!(
+            paths(
+                retention("0", "1h", "1")
+                    .expired(&builds, None, now)
+                    .unwrap()
+            ),
+            vec![
+                PathBuf::from("1.0.2"),
+                PathBuf::from("1.0.1"),
+                PathBuf::from("1.0.0")
+            ]
+        );
+        // The build the latest link is pointing to is kept even if it is not
+        // the most recently collected build
+        assert_eq!(
+            paths(
+                retention("1", "", "")
+                    .expired(&builds, Some(Path::new("1.0.1")), now)
+                    .unwrap()
+            ),
+            vec![PathBuf::from("1.0.2"), PathBuf::from("1.0.0")]
+        );
+    }
+
+    #[test]
+    fn test_retention_error() {
+        let retention: Retention = Retention {
+            keep: String::from(""),
+            max_age: String::from("2w"),
+            max_size: String::from(""),
+        };
+        match retention.expired(&[], None, SystemTime::now()) {
+            Ok(_e) => {
+                panic!("Was expecting an error!");
+            }
+            Err(e) => {
+                assert_eq!(
+                    e.to_string(),
+                    "Invalid retention maxage '2w', must be a number of days (d), hours (h) or minutes (m)!"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_retention_prune() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = PathBuf::from(temp_dir.path());
+        let now: SystemTime = SystemTime::now();
+        setup_build(&artifacts_dir, "1.0.0/user", now - Duration::from_secs(300));
+        setup_build(&artifacts_dir, "1.0.1/user", now - Duration::from_secs(200));
+        setup_build(&artifacts_dir, "1.0.1/eng", now - Duration::from_secs(100));
+        link_latest(&artifacts_dir, &artifacts_dir.join("1.0.0/user")).expect("Failed to link");
+        assert_eq!(
+            std::fs::read_link(artifacts_dir.join("latest")).expect("Failed to read link"),
+            PathBuf::from("1.0.0/user")
+        );
+
+        let builds: Vec<ArtifactsBuild> =
+            Retention::builds(&artifacts_dir).expect("Failed to list builds");
+        assert_eq!(builds.len(), 3);
+        assert_eq!(builds[0].path, artifacts_dir.join("1.0.1/eng"));
+        assert_eq!(builds[0].size, 7);
+
+        let retention: Retention = Retention {
+            keep: String::from("1"),
+            ..Default::default()
+        };
+        let expired: Vec<ArtifactsBuild> = retention
+            .prune(&artifacts_dir, true)
+            .expect("Failed to prune");
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].path, artifacts_dir.join("1.0.1/user"));
+        assert!(artifacts_dir.join("1.0.1/user").exists());
+
+        // The latest link is pointing to the oldest build which is kept
+        retention
+            .prune(&artifacts_dir, false)
+            .expect("Failed to prune");
+        assert!(artifacts_dir.join("1.0.0/user").exists());
+        assert!(!artifacts_dir.join("1.0.1/user").exists());
+        assert!(artifacts_dir.join("1.0.1/eng").exists());
+        assert!(artifacts_dir.join("latest").exists());
+
+        // Without a valid latest link the most recently collected build is kept
+        std::fs::remove_dir_all(artifacts_dir.join("1.0.0")).expect("Failed to remove build");
+        setup_build(&artifacts_dir, "1.0.2/user", now - Duration::from_secs(400));
+        Retention {
+            keep: String::from("1"),
+            ..Default::default()
+        }
+        .prune(&artifacts_dir, false)
+        .expect("Failed to prune");
+        assert!(artifacts_dir.join("1.0.1/eng").exists());
+        assert!(!artifacts_dir.join("1.0.2").exists());
+        assert!(!artifacts_dir.join("latest").is_symlink());
+    }
+}
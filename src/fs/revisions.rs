@@ -0,0 +1,99 @@
+use indexmap::IndexMap;
+use std::path::Path;
+
+use crate::cli::Cli;
+
+/*
+ * The git revisions of the workspace. If the workspace is managed by repo
+ * the revision of every project in the repo project list is included and if
+ * the workspace itself is a git repository the revision of the workspace is
+ * included as '.'. The key is the path of the project relative to the
+ * workspace.
+ */
+pub fn workspace_revisions(cli: &Cli, work_dir: &Path) -> IndexMap<String, String> {
+    let mut revisions: IndexMap<String, String> = IndexMap::new();
+    let mut projects: Vec<String> = vec![];
+
+    if work_dir.join(".git").exists() {
+        projects.push(String::from("."));
+    }
+
+    if let Ok(project_list) = std::fs::read_to_string(work_dir.join(".repo/project.list")) {
+        project_list
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .for_each(|l| projects.push(l.to_string()));
+    }
+
+    for project in projects.iter() {
+        if let Some(revision) = git_revision(cli, &work_dir.join(project)) {
+            revisions.insert(project.clone(), revision);
+        }
+    }
+    revisions
+}
+
+fn git_revision(cli: &Cli, dir: &Path) -> Option<String> {
+    let output: String = cli
+        .check_output(
+            &[
+                String::from("git"),
+                String::from("-C"),
+                dir.to_string_lossy().to_string(),
+                String::from("rev-parse"),
+                String::from("HEAD"),
+            ],
+            &cli.env(),
+            true,
+        )
+        .ok()?;
+    Some(output.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use std::path::{Path, PathBuf};
+    use tempdir::TempDir;
+
+    use crate::cli::{BLogger, BSystem, Cli};
+    use crate::fs::workspace_revisions;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status: std::process::ExitStatus = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@test"])
+            .args(args)
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_workspace_revisions() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let work_dir: PathBuf = temp_dir.path().to_path_buf();
+        let project: PathBuf = work_dir.join("layers/meta-test");
+        std::fs::create_dir_all(&project).expect("Failed to create dir");
+        std::fs::create_dir_all(work_dir.join(".repo")).expect("Failed to create dir");
+        std::fs::write(
+            work_dir.join(".repo/project.list"),
+            "layers/meta-test\nlayers/missing\n",
+        )
+        .expect("Failed to write project list");
+        git(&project, &["init", "-q"]);
+        git(&project, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(BSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        let revisions: IndexMap<String, String> = workspace_revisions(&cli, &work_dir);
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions["layers/meta-test"].len(), 40);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cli::{handshake, signal};
 use crate::data::WsBuildData;
 use crate::workspace::{Workspace, WsArtifactsHandler, WsBuildConfigHandler, WsSettingsHandler};
 
@@ -119,6 +120,12 @@ impl Helper {
                 tar = tar::Archive::new(Box::new(flate2::read::GzDecoder::new(file)));
             } else if archive.compression() == "bz2" {
                 tar = tar::Archive::new(Box::new(bzip2::read::BzDecoder::new(file)));
+            } else if archive.compression() == "xz" {
+                tar = tar::Archive::new(Box::new(xz2::read::XzDecoder::new(file)));
+            } else if archive.compression() == "zst" {
+                tar = tar::Archive::new(Box::new(zstd::Decoder::new(file)?));
+            } else if archive.compression().is_empty() {
+                tar = tar::Archive::new(Box::new(file));
             } else {
                 return Err(BError::ArchiverError(format!(
                     "Unsupported compression '{}'!",
@@ -331,6 +338,8 @@ impl Helper {
             String::from("run"),
             String::from("--name"),
             format!("yaab-workspace-{}", std::process::id()),
+            String::from("--label"),
+            format!("yaab.session={}", signal::session()),
             String::from("-t"),
             String::from("--rm"),
         ];
@@ -347,29 +356,22 @@ impl Helper {
                 cmd_line.append(&mut vec![String::from("-v"), v.to_string()]);
             })
         }
-        cmd_line.append(&mut vec![
-            String::from("-v"),
+        let home: String = Helper::env_home();
+        [
             String::from("/etc/passwd:/etc/passwd:ro"),
-            String::from("-v"),
             String::from("/etc/group:/etc/group:ro"),
-            String::from("-v"),
-            format!(
-                "{}/.gitconfig:{}/.gitconfig:rw",
-                Helper::env_home(),
-                Helper::env_home()
-            ),
-            String::from("-v"),
-            format!("{}/.ssh:{}/.ssh:rw", Helper::env_home(), Helper::env_home()),
-            String::from("-v"),
-            format!(
-                "{}/.docker:{}/.docker",
-                Helper::env_home(),
-                Helper::env_home()
-            ),
-            String::from("-v"),
-            format!("{}/.yaab:{}/.yaab", Helper::env_home(), Helper::env_home()),
-            String::from("-v"),
+            format!("{}/.gitconfig:{}/.gitconfig:rw", home, home),
+            format!("{}/.ssh:{}/.ssh:rw", home, home),
+            format!("{}/.docker:{}/.docker", home, home),
+            format!("{}/.yaab:{}/.yaab", home, home),
             String::from("/var/run/docker.sock:/var/run/docker.sock"),
+        ]
+        .iter()
+        .filter(|m| PathBuf::from(m.split(':').next().unwrap()).exists())
+        .for_each(|m| {
+            cmd_line.append(&mut vec![String::from("-v"), m.to_string()]);
+        });
+        cmd_line.append(&mut vec![
             String::from("-u"),
             format!("{}:{}", users::get_current_uid(), users::get_current_gid()),
             String::from("-v"),
@@ -379,6 +381,7 @@ impl Helper {
             String::from("-w"),
             format!("{}", work_dir.display()),
         ]);
+        cmd_line.append(&mut handshake::args());
         if !args.is_empty() {
             cmd_line.append(&mut args.clone());
         }
@@ -398,6 +401,8 @@ impl Helper {
         let mut cmd_line: Vec<String> = vec![
             String::from("docker"),
             String::from("run"),
+            String::from("--label"),
+            format!("yaab.session={}", signal::session()),
             String::from("-u"),
             format!("{}:{}", users::get_current_uid(), users::get_current_gid()),
             String::from("-v"),
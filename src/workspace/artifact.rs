@@ -1,14 +1,19 @@
 use crate::configs::Context;
-use crate::data::{WsArtifactData, WsBuildData};
+use crate::data::{AType, WsArtifactData, WsBuildData};
 use crate::error::BError;
 use crate::fs::ConfigFileReader;
 
+use indexmap::IndexMap;
 use serde_json::Value;
 use std::path::PathBuf;
 
 pub struct WsArtifactsHandler {
     data: WsArtifactData,
     children: Vec<WsArtifactsHandler>,
+    else_children: Vec<WsArtifactsHandler>,
+    cases: IndexMap<String, Vec<WsArtifactsHandler>>,
+    default_mode: String,
+    docker_image: String,
 }
 
 impl WsArtifactsHandler {
@@ -27,28 +32,145 @@ impl WsArtifactsHandler {
         build_data: &WsBuildData,
     ) -> Result<Self, BError> {
         let artifact_data: WsArtifactData = WsArtifactData::from_value(data)?;
-        let children: Vec<WsArtifactsHandler> = build_data.get_artifacts(data, task_build_dir)?;
+        let mut children: Vec<WsArtifactsHandler> =
+            build_data.get_artifacts(data, task_build_dir)?;
+        /*
+         * The artifacts collected when the condition of a conditional is false
+         * are defined by the 'else' node and when no case of a switch matches
+         * by the 'default' node.
+         */
+        let else_node: &str = match artifact_data.atype() {
+            AType::Switch => "default",
+            _ => "else",
+        };
+        let mut else_children: Vec<WsArtifactsHandler> =
+            build_data.get_artifacts_node(else_node, data, task_build_dir)?;
+        let mut cases: IndexMap<String, Vec<WsArtifactsHandler>> =
+            Self::get_cases(data, task_build_dir, build_data)?;
+        for c in children
+            .iter_mut()
+            .chain(else_children.iter_mut())
+            .chain(cases.values_mut().flatten())
+        {
+            c.inherit(&artifact_data);
+        }
         Ok(WsArtifactsHandler {
             data: artifact_data,
             children,
+            else_children,
+            cases,
+            default_mode: build_data.settings().artifacts_mode().to_string(),
+            docker_image: Self::docker_image_str(build_data),
         })
     }
 
+    fn docker_image_str(build_data: &WsBuildData) -> String {
+        if build_data.settings().docker_disabled() {
+            return String::new();
+        }
+        format!("{}", build_data.settings().docker_image())
+    }
+
+    fn get_cases(
+        data: &Value,
+        task_build_dir: &PathBuf,
+        build_data: &WsBuildData,
+    ) -> Result<IndexMap<String, Vec<WsArtifactsHandler>>, BError> {
+        let mut cases: IndexMap<String, Vec<WsArtifactsHandler>> = IndexMap::new();
+        if let Some(value) = data.get("cases") {
+            match value.as_object() {
+                Some(case_map) => {
+                    for name in case_map.keys() {
+                        cases.insert(
+                            name.clone(),
+                            build_data.get_artifacts_node(name, value, task_build_dir)?,
+                        );
+                    }
+                }
+                None => {
+                    return Err(BError::ParseArtifactsError(
+                        "No 'cases' object node found in build config".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(cases)
+    }
+
     pub fn expand_ctx(&mut self, ctx: &Context) -> Result<(), BError> {
         self.data.expand_ctx(ctx)?;
-        for c in self.children.iter_mut() {
+        for c in self
+            .children
+            .iter_mut()
+            .chain(self.else_children.iter_mut())
+            .chain(self.cases.values_mut().flatten())
+        {
             c.expand_ctx(ctx)?;
         }
         Ok(())
     }
 
+    fn inherit(&mut self, parent: &WsArtifactData) {
+        self.data.inherit(parent);
+        for c in self
+            .children
+            .iter_mut()
+            .chain(self.else_children.iter_mut())
+            .chain(self.cases.values_mut().flatten())
+        {
+            c.inherit(parent);
+        }
+    }
+
     pub fn data(&self) -> &WsArtifactData {
         &self.data
     }
 
+    /*
+     * True if the artifact or any of its children is a manifest. A manifest
+     * can include the artifacts index so the index has to be written before
+     * the manifest is collected.
+     */
+    pub fn has_manifest(&self) -> bool {
+        self.data.atype() == &AType::Manifest
+            || self
+                .children
+                .iter()
+                .chain(self.else_children.iter())
+                .chain(self.cases.values().flatten())
+                .any(|c| c.has_manifest())
+    }
+
+    /*
+     * The collection mode of the artifact. If the mode is not set for the node
+     * or any of its parent nodes the collection mode of the workspace is used.
+     */
+    pub fn mode(&self) -> &str {
+        if self.data.mode().is_empty() {
+            return &self.default_mode;
+        }
+        self.data.mode()
+    }
+
+    /*
+     * The docker image used by the build or an empty string if docker is
+     * disabled in the workspace.
+     */
+    pub fn docker_image(&self) -> &str {
+        &self.docker_image
+    }
+
     pub fn children(&self) -> &Vec<WsArtifactsHandler> {
         &self.children
     }
+
+    pub fn else_children(&self) -> &Vec<WsArtifactsHandler> {
+        &self.else_children
+    }
+
+    pub fn cases(&self) -> &IndexMap<String, Vec<WsArtifactsHandler>> {
+        &self.cases
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use indexmap::IndexMap;
 use serde_json::Value;
 
-use crate::configs::Context;
+use crate::configs::{Context, YAAB_CONFIG_VERSION};
 use crate::data::{TType, WsBuildData};
 use crate::error::BError;
 use crate::fs::ConfigFileReader;
@@ -26,7 +26,7 @@ impl WsBuildConfigHandler {
         let tasks: IndexMap<String, WsTaskHandler> = build_data.get_tasks(data)?;
         let subcmds: IndexMap<String, WsCustomSubCmdHandler> = build_data.get_subcmds(data)?;
 
-        if build_data.version() != "5" {
+        if build_data.version() != YAAB_CONFIG_VERSION {
             return Err(BError::InvalidBuildConfigError(
                 build_data.version().to_string(),
             ));
@@ -102,23 +102,11 @@ impl WsBuildConfigHandler {
         for (key, value) in self.subcmds.drain(..) {
             if !subcmds.contains_key(&key) {
                 subcmds.insert(key.clone(), value);
-            } else {
-                match subcmds.get(&key) {
-                    Some(cmd) => {
-                        /*
-                         * If the command is the default then we can overwrite it
-                         */
-                        if cmd.data().cmd()
-                            == &format!(
-                                "echo \"INFO: currently no '{}' sub-command defined\"",
-                                cmd.data().name()
-                            )
-                        {
-                            subcmds.insert(key.clone(), value);
-                        }
-                    }
-                    None => {}
-                }
+            } else if subcmds.get(&key).is_some_and(|cmd| cmd.is_default()) {
+                /*
+                 * If the command is the default then we can overwrite it
+                 */
+                subcmds.insert(key.clone(), value);
             }
         }
     }
@@ -1,15 +1,21 @@
 use crate::cli::Cli;
 use crate::configs::Context;
-use crate::data::WsCustomSubCmdData;
+use crate::data::{DType, UType, WsCustomSubCmdData, WsDeployData, WsUploadData};
 use crate::error::BError;
-use crate::executers::{CustomSubCmdExecuter, TaskExecuter};
+use crate::executers::{
+    AdbDeployExecuter, CustomSubCmdExecuter, FastbootDeployExecuter, HttpUploadExecuter,
+    OciUploadExecuter, S3UploadExecuter, TaskExecuter,
+};
 use crate::fs::ConfigFileReader;
 
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
 pub struct WsCustomSubCmdHandler {
     data: WsCustomSubCmdData,
+    upload: Option<WsUploadData>,
+    deploy: Option<WsDeployData>,
 }
 
 impl WsCustomSubCmdHandler {
@@ -20,15 +26,65 @@ impl WsCustomSubCmdHandler {
 
     pub fn new(name: &str, data: &Value) -> Result<Self, BError> {
         let taskcmd_data: WsCustomSubCmdData = WsCustomSubCmdData::from_value(name, data)?;
+        let mut upload: Option<WsUploadData> = None;
+        let mut deploy: Option<WsDeployData> = None;
 
-        Ok(WsCustomSubCmdHandler { data: taskcmd_data })
+        /*
+         * The upload sub-command can either run the cmd or use one of the
+         * built-in upload backends selected by the type of the upload node.
+         */
+        if name == "upload" {
+            upload = Some(WsUploadData::from_value(data)?);
+        }
+
+        /*
+         * Same for the deploy sub-command which can use one of the built-in
+         * deploy modes selected by the type of the deploy node.
+         */
+        if name == "deploy" {
+            deploy = Some(WsDeployData::from_value(data)?);
+        }
+
+        Ok(WsCustomSubCmdHandler {
+            data: taskcmd_data,
+            upload,
+            deploy,
+        })
     }
 
     pub fn expand_ctx(&mut self, ctx: &Context) -> Result<(), BError> {
         self.data.expand_ctx(ctx)?;
+        if let Some(upload) = self.upload.as_mut() {
+            upload.expand_ctx(ctx)?;
+        }
+        if let Some(deploy) = self.deploy.as_mut() {
+            deploy.expand_ctx(ctx)?;
+        }
         Ok(())
     }
 
+    /*
+     * The sub-command is the default if it is not defined in the build config
+     * meaning that it can be replaced by a sub-command from an included config.
+     */
+    pub fn is_default(&self) -> bool {
+        let cmd_type: UType = self
+            .upload()
+            .map(|u| u.utype().clone())
+            .unwrap_or(UType::Cmd);
+        let deploy_type: DType = self
+            .deploy()
+            .map(|d| d.dtype().clone())
+            .unwrap_or(DType::Cmd);
+        cmd_type == UType::Cmd
+            && deploy_type == DType::Cmd
+            && self.data().cmd()
+                == &format!(
+                    "echo \"INFO: currently no '{}' sub-command defined\"",
+                    self.data().name()
+                )
+    }
+
     pub fn run<'a>(
         &self,
         cli: &'a Cli,
@@ -36,13 +92,65 @@ impl WsCustomSubCmdHandler {
         dry_run: bool,
         interactive: bool,
     ) -> Result<(), BError> {
-        let executer: Box<dyn TaskExecuter> = Box::new(CustomSubCmdExecuter::new(cli, &self.data));
+        let executer: Box<dyn TaskExecuter> = match (&self.upload, &self.deploy) {
+            (Some(upload), _) if upload.utype() == &UType::Http => {
+                Box::new(HttpUploadExecuter::new(cli, upload))
+            }
+            (Some(upload), _) if upload.utype() == &UType::S3 => {
+                Box::new(S3UploadExecuter::new(cli, upload))
+            }
+            (Some(upload), _) if upload.utype() == &UType::Oci => {
+                Box::new(OciUploadExecuter::new(cli, upload))
+            }
+            (_, Some(deploy)) if deploy.dtype() == &DType::Fastboot => {
+                Box::new(FastbootDeployExecuter::new(cli, deploy))
+            }
+            (_, Some(deploy)) if deploy.dtype() == &DType::Adb => {
+                Box::new(AdbDeployExecuter::new(cli, deploy))
+            }
+            _ => Box::new(CustomSubCmdExecuter::new(cli, &self.data)),
+        };
         executer.exec(env_variables, dry_run, interactive)
     }
 
+    /*
+     * Download the artifacts of a previous upload. Only the built-in upload
+     * backends knows where the artifacts were uploaded.
+     */
+    pub fn fetch(
+        &self,
+        cli: &Cli,
+        env_variables: &HashMap<String, String>,
+        dest: &Path,
+    ) -> Result<(), BError> {
+        match &self.upload {
+            Some(upload) if upload.utype() == &UType::Http => {
+                HttpUploadExecuter::new(cli, upload).fetch(env_variables, dest)
+            }
+            Some(upload) if upload.utype() == &UType::S3 => {
+                S3UploadExecuter::new(cli, upload).fetch(env_variables, dest)
+            }
+            Some(upload) if upload.utype() == &UType::Oci => {
+                OciUploadExecuter::new(cli, upload).fetch(env_variables, dest)
+            }
+            _ => Err(BError::UploadError(
+                "Fetching artifacts requires an 'upload' node of type 'http', 's3' or 'oci'"
+                    .to_string(),
+            )),
+        }
+    }
+
     pub fn data(&self) -> &WsCustomSubCmdData {
         &self.data
     }
+
+    pub fn upload(&self) -> Option<&WsUploadData> {
+        self.upload.as_ref()
+    }
+
+    pub fn deploy(&self) -> Option<&WsDeployData> {
+        self.deploy.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -50,7 +158,13 @@ mod tests {
     use crate::workspace::WsCustomSubCmdHandler;
 
     use crate::cli::*;
+    use crate::configs::Context;
+    use crate::data::{DType, UType};
+    use crate::helper::Helper;
+    use indexmap::{indexmap, IndexMap};
     use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
 
     #[test]
     fn test_ws_deploy_handler() {
@@ -89,4 +203,82 @@ mod tests {
             .run(&cli, &HashMap::new(), false, true)
             .expect("Failed to run handler");
     }
+
+    #[test]
+    fn test_ws_upload_handler_http() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        std::fs::create_dir_all(&artifacts_dir).expect("Failed to create artifacts dir");
+        let json_build_config = r#"
+        {
+            "upload": {
+                "type": "http",
+                "url": "http://localhost:8081/artifactory",
+                "path": "$#[YAAB_PRODUCT_NAME]"
+            }
+        }"#;
+        let mut handler: WsCustomSubCmdHandler =
+            WsCustomSubCmdHandler::from_str("upload", json_build_config)
+                .expect("Failed to parse build config");
+        assert!(!handler.is_default());
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => artifacts_dir.to_string_lossy().to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+        };
+        handler.expand_ctx(&Context::new(&variables)).unwrap();
+        let upload = handler.upload().expect("Missing upload data");
+        assert_eq!(upload.utype(), &UType::Http);
+        assert_eq!(upload.path(), "product");
+        assert_eq!(upload.dir(), &artifacts_dir.to_string_lossy());
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        handler
+            .run(&cli, &HashMap::new(), true, false)
+            .expect("Failed to run handler");
+    }
+
+    #[test]
+    fn test_ws_deploy_handler_fastboot() {
+        let temp_dir: TempDir =
+            TempDir::new("yaab-test-dir").expect("Failed to create temp directory");
+        let artifacts_dir: PathBuf = temp_dir.path().join("artifacts");
+        Helper::create_test_files(&vec![artifacts_dir.join("boot.img")]);
+        let json_build_config = r#"
+        {
+            "deploy": {
+                "type": "fastboot",
+                "images": [
+                    "boot=boot.img"
+                ]
+            }
+        }"#;
+        let mut handler: WsCustomSubCmdHandler =
+            WsCustomSubCmdHandler::from_str("deploy", json_build_config)
+                .expect("Failed to parse build config");
+        assert!(!handler.is_default());
+        let variables: IndexMap<String, String> = indexmap! {
+            "YAAB_ARTIFACTS_DIR".to_string() => artifacts_dir.to_string_lossy().to_string(),
+            "YAAB_WORKSPACE_DIR".to_string() => temp_dir.path().to_string_lossy().to_string(),
+            "YAAB_PRODUCT_NAME".to_string() => "product".to_string(),
+            "YAAB_DEVICE".to_string() => "serial1".to_string(),
+        };
+        handler.expand_ctx(&Context::new(&variables)).unwrap();
+        let deploy = handler.deploy().expect("Missing deploy data");
+        assert_eq!(deploy.dtype(), &DType::Fastboot);
+        assert_eq!(deploy.device(), "serial1");
+        let cli: Cli = Cli::new(
+            Box::new(BLogger::new()),
+            Box::new(MockSystem::new()),
+            clap::Command::new("yaab"),
+            Some(vec!["yaab"]),
+        );
+        handler
+            .run(&cli, &HashMap::new(), true, false)
+            .expect("Failed to run handler");
+    }
 }
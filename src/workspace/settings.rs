@@ -1,5 +1,10 @@
 use crate::error::BError;
-use crate::{configs::WsSettings, executers::DockerImage};
+use crate::{
+    configs::WsSettings,
+    data::WsDeviceData,
+    executers::{DockerImage, DockerMounts, DockerResources},
+    fs::Retention,
+};
 
 use std::path::{Path, PathBuf};
 
@@ -84,6 +89,22 @@ impl WsSettingsHandler {
         self.append_dir(&self.ws_settings.artifacts_dir)
     }
 
+    pub fn artifacts_sha256sums(&self) -> bool {
+        self.ws_settings.artifacts_sha256sums == "true"
+    }
+
+    pub fn artifacts_mode(&self) -> &str {
+        &self.ws_settings.artifacts_mode
+    }
+
+    pub fn artifacts_layout(&self) -> &str {
+        &self.ws_settings.artifacts_layout
+    }
+
+    pub fn artifacts_retention(&self) -> &Retention {
+        &self.ws_settings.artifacts_retention
+    }
+
     pub fn configs_dir(&self) -> PathBuf {
         self.append_dir(&self.ws_settings.configs_dir)
     }
@@ -131,6 +152,78 @@ impl WsSettingsHandler {
         }
     }
 
+    pub fn docker_persistent(&self) -> bool {
+        self.ws_settings.docker_persistent == "true"
+    }
+
+    pub fn docker_mounts(&self) -> DockerMounts {
+        DockerMounts {
+            add: self.ws_settings.docker_mounts_add.clone(),
+            remove: self.ws_settings.docker_mounts_remove.clone(),
+            readonly: self.ws_settings.docker_mounts_readonly.clone(),
+            docker_sock: self.ws_settings.docker_sock != "false",
+            ssh_agent: self.ws_settings.docker_ssh_agent == "true",
+            credential_sock: self.ws_settings.docker_credential_sock.clone(),
+        }
+    }
+
+    pub fn docker_resources(&self) -> &DockerResources {
+        &self.ws_settings.docker_resources
+    }
+
+    pub fn docker_host_yaab(&self) -> bool {
+        self.ws_settings.docker_host_yaab == "true"
+    }
+
+    pub fn container_runtime(&self) -> &str {
+        &self.ws_settings.docker_runtime
+    }
+
+    pub fn apptainer_enabled(&self) -> bool {
+        matches!(self.container_runtime(), "apptainer" | "singularity")
+    }
+
+    /*
+     * The image used by apptainer when bootstrapping yaab. If no SIF image is
+     * specified the docker image of the workspace is used.
+     */
+    pub fn apptainer_image(&self) -> String {
+        if !self.ws_settings.docker_sif.is_empty() {
+            return self
+                .append_dir(&self.ws_settings.docker_sif)
+                .to_string_lossy()
+                .to_string();
+        }
+        format!("docker://{}", self.docker_image())
+    }
+
+    pub fn devices(&self) -> &Vec<WsDeviceData> {
+        &self.ws_settings.devices
+    }
+
+    pub fn device(&self, name: &str) -> Option<&WsDeviceData> {
+        self.ws_settings.devices.iter().find(|d| d.name() == name)
+    }
+
+    /*
+     * Merge a user wide device inventory into the device inventory of the
+     * workspace. A device with the same name as a device in the workspace
+     * config is replacing it.
+     */
+    pub fn merge_devices(&mut self, devices: Vec<WsDeviceData>) {
+        for device in devices.into_iter() {
+            match self
+                .ws_settings
+                .devices
+                .iter()
+                .position(|d| d.name() == device.name())
+            {
+                Some(index) => self.ws_settings.devices[index] = device,
+                None => self.ws_settings.devices.push(device),
+            }
+        }
+    }
+
     pub fn supported_builds(&self) -> &Vec<String> {
         &self.ws_settings.supported
     }
@@ -140,6 +233,8 @@ impl WsSettingsHandler {
 mod tests {
     use std::path::PathBuf;
 
+    use crate::configs::{Config, WsSettings};
+    use crate::data::WsDeviceData;
     use crate::executers::DockerImage;
     use crate::helper::Helper;
     use crate::workspace::WsSettingsHandler;
@@ -313,6 +408,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_settings_apptainer_image() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "runtime": "apptainer",
+                "tag": "0.1",
+                "image": "test-image",
+                "registry": "test-registry"
+            }
+        }"#;
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let settings: WsSettingsHandler =
+            WsSettingsHandler::new(work_dir.clone(), Helper::setup_ws_settings(json_test_str));
+        assert!(settings.apptainer_enabled());
+        assert_eq!(
+            settings.apptainer_image(),
+            "docker://test-registry/test-image:0.1"
+        );
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "docker": {
+                "runtime": "singularity",
+                "sif": "images/workspace.sif"
+            }
+        }"#;
+        let settings: WsSettingsHandler =
+            WsSettingsHandler::new(work_dir, Helper::setup_ws_settings(json_test_str));
+        assert!(settings.apptainer_enabled());
+        assert_eq!(settings.apptainer_image(), "/workspace/images/workspace.sif");
+    }
+
     #[test]
     fn test_settings_default_supported_builds() {
         let json_test_str = r#"
@@ -345,4 +474,56 @@ mod tests {
             &vec!["build1".to_string(), "build2".to_string()]
         );
     }
+    #[test]
+    fn test_settings_merge_devices() {
+        let json_test_str = r#"
+        {
+            "version": "5",
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "8A3X0N1Q2"
+                },
+                {
+                    "name": "lab-tablet",
+                    "serial": "192.168.1.10:5555",
+                    "transport": "tcp"
+                }
+            ]
+        }"#;
+        let json_devices_str = r#"
+        {
+            "devices": [
+                {
+                    "name": "lab-pixel-3",
+                    "serial": "9B4Y1P2R3",
+                    "deploy": "adb"
+                },
+                {
+                    "name": "desk-board",
+                    "serial": "1C5Z2Q3S4"
+                }
+            ]
+        }"#;
+        let work_dir: PathBuf = PathBuf::from("/workspace");
+        let mut settings: WsSettingsHandler =
+            WsSettingsHandler::new(work_dir, Helper::setup_ws_settings(json_test_str));
+        let devices: Vec<WsDeviceData> =
+            WsSettings::devices(&WsSettings::parse(json_devices_str).unwrap()).unwrap();
+        settings.merge_devices(devices);
+        let names: Vec<(&str, &str)> = settings
+            .devices()
+            .iter()
+            .map(|d| (d.name().as_str(), d.serial().as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("lab-pixel-3", "9B4Y1P2R3"),
+                ("lab-tablet", "192.168.1.10:5555"),
+                ("desk-board", "1C5Z2Q3S4")
+            ]
+        );
+        assert_eq!(settings.device("lab-pixel-3").unwrap().mode(), "adb");
+    }
 }
@@ -3,13 +3,14 @@ use crate::collector::{Collected, Collector, CollectorFactory};
 use crate::configs::Context;
 use crate::data::{WsBuildData, WsTaskData};
 use crate::error::BError;
-use crate::executers::{BuildExecuter, CleanExecuter, TaskExecuter,
-};
-use crate::fs::ConfigFileReader;
+use crate::executers::{BuildExecuter, CleanExecuter, TaskExecuter};
+use crate::fs::{link_latest, ArtifactsIndex, ConfigFileReader};
 use crate::workspace::WsArtifactsHandler;
 
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
 
 pub struct WsTaskHandler {
     data: WsTaskData,
@@ -43,7 +44,6 @@ impl WsTaskHandler {
         interactive: bool,
         force: bool,
     ) -> Result<(), BError> {
-
         if !force && self.data.disabled() {
             cli.info(format!("Task '{}' disabled, skipping", self.data.name()));
             return Ok(());
@@ -57,11 +57,21 @@ impl WsTaskHandler {
             return Ok(());
         }
 
-        let executer: Box<dyn TaskExecuter> = Box::new(BuildExecuter::new(cli, &self.data));
+        let executer: Box<dyn TaskExecuter> =
+            Box::new(BuildExecuter::new(cli, &self.data, build_data));
+        let started: Instant = Instant::now();
         executer.exec(env_variables, dry_run, interactive)?;
 
         if !dry_run {
-            self.collect(cli, build_data)?;
+            /*
+             * The duration of the task is kept in the artifacts index so it
+             * can be included in a generated manifest.
+             */
+            let mut index: ArtifactsIndex =
+                ArtifactsIndex::load(&build_data.artifacts_dir(), build_data.name())?;
+            index.add_task(self.data.name(), started.elapsed());
+            self.collect_to(cli, build_data, &mut index)?;
+            Self::write_index(cli, build_data, &index)?;
         }
 
         Ok(())
@@ -70,7 +80,7 @@ impl WsTaskHandler {
     pub fn clean<'a>(
         &self,
         cli: &'a Cli,
-        _build_data: &WsBuildData,
+        build_data: &WsBuildData,
         env_variables: &HashMap<String, String>,
     ) -> Result<(), BError> {
         if self.data.disabled() {
@@ -89,14 +99,42 @@ impl WsTaskHandler {
             return Ok(());
         }
 
-        
-        let executer: Box<dyn TaskExecuter> = Box::new(CleanExecuter::new(cli, &self.data));
+        let executer: Box<dyn TaskExecuter> =
+            Box::new(CleanExecuter::new(cli, &self.data, build_data));
         executer.exec(env_variables, false, false)?;
 
         Ok(())
     }
 
     pub fn collect(&self, cli: &Cli, build_data: &WsBuildData) -> Result<Vec<Collected>, BError> {
+        let mut index: ArtifactsIndex =
+            ArtifactsIndex::load(&build_data.artifacts_dir(), build_data.name())?;
+        let collected: Vec<Collected> = self.collect_to(cli, build_data, &mut index)?;
+        if !self.artifacts.is_empty() {
+            Self::write_index(cli, build_data, &index)?;
+        }
+        Ok(collected)
+    }
+
+    fn write_index(
+        cli: &Cli,
+        build_data: &WsBuildData,
+        index: &ArtifactsIndex,
+    ) -> Result<(), BError> {
+        index.write(build_data.settings().artifacts_sha256sums())?;
+        cli.info(format!(
+            "Artifacts index written to '{}'",
+            index.path().display()
+        ));
+        Ok(())
+    }
+
+    fn collect_to(
+        &self,
+        cli: &Cli,
+        build_data: &WsBuildData,
+        index: &mut ArtifactsIndex,
+    ) -> Result<Vec<Collected>, BError> {
         let mut collected: Vec<Collected> = vec![];
 
         if !self.artifacts.is_empty() {
@@ -104,23 +142,47 @@ impl WsTaskHandler {
                 "Collecting artifacts for task '{}'",
                 self.data.name()
             ));
+            /*
+             * The artifacts index is updated with every artifact collected by
+             * the task. The type of each entry is the type of the artifact
+             * node in the task that collected it.
+             */
+            build_data.verify_artifacts_dir()?;
+            let artifacts_dir: PathBuf = build_data.artifacts_dir();
             for artifact in self.artifacts.iter() {
+                /*
+                 * The index is written before collecting a manifest so a
+                 * manifest listing the artifacts includes the artifacts
+                 * collected before it by the same task.
+                 */
+                if artifact.has_manifest() {
+                    index.write(build_data.settings().artifacts_sha256sums())?;
+                }
                 let collector: Box<dyn Collector> = CollectorFactory::create(artifact, Some(cli))?;
-                let mut c: Vec<Collected> = collector.collect(
-                    self.data.build_dir(),
-                    &build_data.settings().artifacts_dir(),
-                )?;
+                let mut c: Vec<Collected> =
+                    collector.collect(self.data.build_dir(), &artifacts_dir)?;
+                for a in c.iter() {
+                    index.add(
+                        &a.dest,
+                        self.data.name(),
+                        &artifact.data().atype().to_string(),
+                    )?;
+                }
                 collected.append(&mut c);
             }
 
+            /*
+             * When the artifacts are collected to a versioned sub directory the
+             * latest link in the artifacts directory is pointing to it.
+             */
+            if artifacts_dir != build_data.settings().artifacts_dir() {
+                link_latest(&build_data.settings().artifacts_dir(), &artifacts_dir)?;
+            }
+
             cli.info(format!(
                 "All artifacts for task '{}' have been collected to '{}'",
                 self.data.name(),
-                build_data
-                    .settings()
-                    .artifacts_dir()
-                    .to_string_lossy()
-                    .to_string()
+                artifacts_dir.to_string_lossy()
             ));
         } else {
             cli.info(format!(